@@ -0,0 +1,198 @@
+//! Asset lock file that maps asset files (images, audio) to their content IDs
+//! and uploaders that are used by `argon assets` command to obtain these IDs
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+	collections::BTreeMap,
+	fmt::{self, Debug, Formatter},
+	fs,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+use crate::{constants::ASSET_LOCK_HEADER, ext::PathExt, lock, vfs::Vfs};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssetKind {
+	Image,
+	Audio,
+}
+
+impl AssetKind {
+	pub fn from_path(path: &Path) -> Option<Self> {
+		match path.get_ext().to_lowercase().as_str() {
+			"png" | "jpg" | "jpeg" | "bmp" | "tga" => Some(AssetKind::Image),
+			"ogg" | "mp3" | "wav" | "flac" => Some(AssetKind::Audio),
+			_ => None,
+		}
+	}
+
+	/// Class of instances created from assets of this kind
+	/// when no other class is specified in the instance data
+	pub fn default_class(&self) -> &'static str {
+		match self {
+			AssetKind::Image => "Decal",
+			AssetKind::Audio => "Sound",
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedAsset {
+	pub id: String,
+	pub hash: String,
+}
+
+/// Asset lock file, keys are paths relative to the lock file directory
+#[derive(Debug, Clone, Default)]
+pub struct AssetLock {
+	path: PathBuf,
+	assets: BTreeMap<String, LockedAsset>,
+}
+
+impl AssetLock {
+	pub fn load(path: &Path, vfs: &Vfs) -> Result<Self> {
+		let assets = if vfs.exists(path) {
+			toml::from_str(&vfs.read_to_string(path)?)?
+		} else {
+			BTreeMap::new()
+		};
+
+		Ok(Self {
+			path: path.to_owned(),
+			assets,
+		})
+	}
+
+	pub fn save(&self) -> Result<()> {
+		let contents = toml::to_string(&self.assets)?;
+
+		fs::write(&self.path, format!("{ASSET_LOCK_HEADER}\n\n{contents}"))?;
+
+		Ok(())
+	}
+
+	pub fn get(&self, path: &Path) -> Option<&LockedAsset> {
+		self.assets.get(&self.key(path))
+	}
+
+	pub fn insert(&mut self, path: &Path, asset: LockedAsset) {
+		self.assets.insert(self.key(path), asset);
+	}
+
+	/// Removes assets whose files are not in the given list,
+	/// returns the number of removed entries
+	pub fn retain(&mut self, paths: &[PathBuf]) -> usize {
+		let keys = paths.iter().map(|path| self.key(path)).collect::<Vec<String>>();
+		let len = self.assets.len();
+
+		self.assets.retain(|key, _| keys.contains(key));
+
+		len - self.assets.len()
+	}
+
+	fn key(&self, path: &Path) -> String {
+		let path = path.strip_prefix(self.path.get_parent()).unwrap_or(path);
+
+		path.components()
+			.map(|component| component.as_os_str().to_string_lossy())
+			.collect::<Vec<_>>()
+			.join("/")
+	}
+}
+
+/// Asset lock shared by all contexts of the project, it is loaded
+/// once and kept in memory until the lock file changes
+#[derive(Clone)]
+pub struct SharedAssetLock {
+	path: PathBuf,
+	lock: Arc<Mutex<Option<AssetLock>>>,
+}
+
+impl SharedAssetLock {
+	pub fn new(path: &Path) -> Self {
+		Self {
+			path: path.to_owned(),
+			lock: Arc::new(Mutex::new(None)),
+		}
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Returns content ID of the given asset file
+	pub fn get_id(&self, path: &Path, vfs: &Vfs) -> Result<Option<String>> {
+		let mut lock = lock!(self.lock);
+
+		if lock.is_none() {
+			*lock = Some(AssetLock::load(&self.path, vfs)?);
+		}
+
+		Ok(lock
+			.as_ref()
+			.and_then(|lock| lock.get(path))
+			.map(|asset| asset.id.clone()))
+	}
+
+	/// Forces the lock file to be read again on the next access
+	pub fn invalidate(&self) {
+		*lock!(self.lock) = None;
+	}
+}
+
+impl PartialEq for SharedAssetLock {
+	fn eq(&self, other: &Self) -> bool {
+		self.path == other.path
+	}
+}
+
+impl Debug for SharedAssetLock {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_tuple("SharedAssetLock").field(&self.path).finish()
+	}
+}
+
+/// Service that stores asset files and returns their content IDs
+pub trait Uploader {
+	fn name(&self) -> &str;
+
+	fn upload(&mut self, path: &Path, kind: AssetKind, contents: &[u8]) -> Result<String>;
+}
+
+/// Uploader that copies assets to a local directory and returns
+/// stable IDs derived from their contents, meant for offline testing
+pub struct LocalUploader {
+	directory: PathBuf,
+}
+
+impl LocalUploader {
+	pub fn new(directory: &Path) -> Self {
+		Self {
+			directory: directory.to_owned(),
+		}
+	}
+}
+
+impl Uploader for LocalUploader {
+	fn name(&self) -> &str {
+		"local"
+	}
+
+	fn upload(&mut self, path: &Path, _kind: AssetKind, contents: &[u8]) -> Result<String> {
+		let hash = hash(contents);
+
+		fs::create_dir_all(&self.directory)?;
+		fs::write(self.directory.join(format!("{}.{}", hash, path.get_ext())), contents)?;
+
+		let id = u64::from_str_radix(&hash[..15], 16)?;
+
+		Ok(format!("rbxassetid://{id}"))
+	}
+}
+
+pub fn hash(contents: &[u8]) -> String {
+	format!("{:x}", Sha256::digest(contents))
+}
@@ -0,0 +1,136 @@
+use anyhow::{bail, Result};
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use std::{fs, path::PathBuf};
+
+use crate::{
+	argon_error, argon_info,
+	assets::{self, AssetKind, AssetLock, LocalUploader, LockedAsset, Uploader},
+	config::Config,
+	constants::ASSET_LOCK,
+	core::{helpers::syncback::get_file_middleware, Core},
+	ext::PathExt,
+	middleware::Middleware,
+	project::{self, Project},
+	util,
+	vfs::Vfs,
+};
+
+/// Upload new and changed asset files and update the asset lock file
+#[derive(Parser)]
+pub struct Assets {
+	/// Project path
+	#[arg()]
+	project: Option<PathBuf>,
+
+	/// Service that assets are uploaded to
+	#[arg(short, long, default_value = "local")]
+	uploader: UploaderKind,
+
+	/// Directory that the local uploader stores assets in (`~/.argon/assets` by default)
+	#[arg(short, long)]
+	output: Option<PathBuf>,
+
+	/// Upload all assets, even the ones that did not change
+	#[arg(short, long)]
+	force: bool,
+}
+
+impl Assets {
+	pub fn main(self) -> Result<()> {
+		let project_path = project::resolve(self.project.clone().unwrap_or_default())?;
+
+		Config::load_workspace(project_path.get_parent());
+
+		if !project_path.exists() {
+			bail!(
+				"No project files found in {}",
+				project_path.get_parent().to_string().bold()
+			);
+		}
+
+		let project = Project::load(&project_path)?;
+		let workspace_dir = project.workspace_dir.clone();
+		let core = Core::new(project, false, false)?;
+
+		let mut paths = core
+			.tree()
+			.meta_map()
+			.values()
+			.filter(|meta| get_file_middleware(meta) == Some(Middleware::Asset))
+			.filter_map(|meta| meta.source.get_file().map(|file| file.path().to_owned()))
+			.collect::<Vec<PathBuf>>();
+
+		paths.sort();
+		paths.dedup();
+
+		let mut uploader: Box<dyn Uploader> = match self.uploader {
+			UploaderKind::Local => {
+				let directory = match self.output.clone() {
+					Some(output) => output,
+					None => util::get_argon_dir()?.join("assets"),
+				};
+
+				Box::new(LocalUploader::new(&directory))
+			}
+		};
+
+		let mut lock = AssetLock::load(&workspace_dir.join(ASSET_LOCK), &Vfs::new(false))?;
+		let mut uploaded = 0;
+		let mut failed = 0;
+
+		for path in &paths {
+			let kind = match AssetKind::from_path(path) {
+				Some(kind) => kind,
+				None => continue,
+			};
+
+			let contents = fs::read(path)?;
+			let hash = assets::hash(&contents);
+
+			if !self.force && lock.get(path).is_some_and(|asset| asset.hash == hash) {
+				continue;
+			}
+
+			match uploader.upload(path, kind, &contents) {
+				Ok(id) => {
+					argon_info!("Uploaded asset: {} as: {}", path.to_string().bold(), id.bold());
+
+					lock.insert(path, LockedAsset { id, hash });
+					uploaded += 1;
+				}
+				Err(err) => {
+					argon_error!("Failed to upload asset: {}: {}", path.to_string().bold(), err);
+					failed += 1;
+				}
+			}
+		}
+
+		let removed = lock.retain(&paths);
+
+		if uploaded > 0 || removed > 0 {
+			lock.save()?;
+		}
+
+		argon_info!(
+			"Asset lock is up to date: {} uploaded, {} removed, {} total (using {} uploader)",
+			uploaded.to_string().bold(),
+			removed.to_string().bold(),
+			(paths.len() - failed).to_string().bold(),
+			uploader.name().bold()
+		);
+
+		if failed > 0 {
+			bail!("Failed to upload {} assets", failed);
+		}
+
+		Ok(())
+	}
+}
+
+#[derive(Clone, Default, ValueEnum)]
+enum UploaderKind {
+	/// Copy assets to a local directory (for offline testing)
+	#[default]
+	Local,
+}
@@ -8,11 +8,13 @@ use std::{fs, path::PathBuf, process};
 use crate::{
 	argon_info,
 	config::Config,
-	core::Core,
+	constants::BUILD_DEBOUNCE_TIME,
+	core::{changes::Changes, Core},
 	ext::PathExt,
 	integration,
 	program::{Program, ProgramName},
 	project::{self, Project},
+	server::{Message, SyncChanges},
 	sessions,
 };
 
@@ -168,7 +170,7 @@ impl Build {
 			}
 		}
 
-		let core = Core::new(project, self.watch)?;
+		let core = Core::new(project, self.watch, false)?;
 
 		core.build(&path, xml)?;
 
@@ -197,7 +199,7 @@ impl Build {
 					.spawn()?;
 			}
 
-			sessions::add(self.session, None, None, process::id(), config.run_async)?;
+			sessions::add(self.session, None, None, None, process::id(), config.run_async)?;
 
 			argon_info!("Watching for changes..");
 
@@ -205,14 +207,32 @@ impl Build {
 			queue.subscribe_internal().unwrap();
 
 			loop {
-				let _message = queue.get_change(0).unwrap();
+				let mut message = Some(queue.get_change(0)?);
+				let mut changes = Changes::new();
+				let mut needs_build = false;
+
+				// Collect all changes made in a quick succession
+				while let Some(received) = message {
+					match received {
+						Message::SyncChanges(SyncChanges(received)) => changes.merge(received),
+						_ => needs_build = true,
+					}
+
+					message = queue.get_change_timeout(0, BUILD_DEBOUNCE_TIME)?;
+				}
 
-				info!("Rebuilding project..");
-				core.build(&path, xml)?;
+				if needs_build || core.affects_build(&changes) {
+					info!("Rebuilding project..");
+					core.build(&path, xml)?;
+				} else {
+					debug!("Skipping rebuild, changes do not affect the output");
+				}
 
 				if let Some(path) = &sourcemap_path {
-					info!("Regenerating sourcemap..");
-					core.sourcemap(Some(path.clone()), false)?;
+					if needs_build || !changes.is_empty() {
+						info!("Regenerating sourcemap..");
+						core.sourcemap(Some(path.clone()), false)?;
+					}
 				}
 			}
 		}
@@ -1,12 +1,29 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use clap::Parser;
+use colored::Colorize;
+use log::trace;
 use reqwest::{blocking::Client, header::CONTENT_TYPE};
 use serde::Serialize;
-use std::{fs, path::Path};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::Arc,
+	thread,
+	time::{Duration, Instant},
+};
 
-use crate::{argon_error, argon_info, ext::PathExt, sessions};
+use crate::{
+	argon_error, argon_info, argon_warn,
+	config::Config,
+	constants::{EXEC_TIMEOUT, STANDALONE_TIMEOUT},
+	core::{queue::Queue, Core},
+	ext::PathExt,
+	project::{self, Project},
+	server::{self, ExecutionResult, LogKind, Server},
+	sessions,
+};
 
-/// Execute Luau code in Roblox Studio (requires running session)
+/// Execute Luau code in Roblox Studio (requires running session or standalone mode)
 #[derive(Parser)]
 pub struct Exec {
 	/// Luau code to execute (can be file path)
@@ -25,6 +42,14 @@ pub struct Exec {
 	#[arg(short, long)]
 	standalone: bool,
 
+	/// Maximum time to wait for the result in seconds
+	#[arg(short, long)]
+	timeout: Option<u64>,
+
+	/// Project path (used in standalone mode)
+	#[arg(short, long)]
+	project: Option<PathBuf>,
+
 	/// Server host name
 	#[arg(short = 'H', long)]
 	host: Option<String>,
@@ -43,60 +68,215 @@ impl Exec {
 		{
 			fs::read_to_string(path)?
 		} else {
-			self.code
+			self.code.clone()
 		};
 
 		if self.standalone {
-			// TODO: Implement standalone mode
-			argon_error!("Standalone mode is not implemented yet!");
-		} else if let Some(session) = sessions::get(self.session, self.host, self.port)? {
-			let address = session.get_address().or_else(|| {
-				sessions::get_all()
-					.unwrap_or_default()
-					.into_iter()
-					.find_map(|(_, session)| session.get_address())
-			});
-
-			if let Some(address) = address {
-				let url = format!("{address}/exec");
-
-				let body = rmp_serde::to_vec(&Request {
-					code: code.to_owned(),
-					focus: if cfg!(not(target_os = "windows")) {
-						self.focus
-					} else {
-						false
-					},
-				})?;
-
-				let response = Client::default()
-					.post(url)
-					.header(CONTENT_TYPE, "application/msgpack")
-					.body(body)
-					.send();
-
-				match response {
-					Ok(_) => argon_info!("Code executed successfully!"),
-					Err(err) => argon_error!("Code execution failed: {}", err),
-				}
-
-				#[cfg(target_os = "windows")]
-				if self.focus {
-					crate::studio::focus(None)?;
-				}
-			} else {
-				argon_error!("Code execution failed: running session does not have an address");
-			}
+			return self.run_standalone(code);
+		}
+
+		let Some(session) = sessions::get(self.session, self.host, self.port)? else {
+			bail!("Code execution failed: no running session was found");
+		};
+
+		let session = if session.get_address().is_some() {
+			Some(session)
 		} else {
-			argon_error!("Code execution failed: no running session was found");
+			sessions::get_all()
+				.unwrap_or_default()
+				.into_values()
+				.find(|session| session.get_address().is_some())
+		};
+
+		let Some((address, token)) = session.and_then(|session| Some((session.get_address()?, session.token))) else {
+			bail!("Code execution failed: running session does not have an address");
+		};
+
+		#[cfg(target_os = "windows")]
+		if self.focus {
+			crate::studio::focus(None)?;
 		}
 
-		Ok(())
+		execute(
+			&address,
+			token.as_deref(),
+			code,
+			cfg!(not(target_os = "windows")) && self.focus,
+			self.timeout,
+		)
 	}
+
+	fn run_standalone(self, code: String) -> Result<()> {
+		let project_path = project::resolve(self.project.clone().unwrap_or_default())?;
+
+		Config::load_workspace(project_path.get_parent());
+		let config = Config::new();
+
+		if !project_path.exists() {
+			bail!(
+				"No project files found in {}",
+				project_path.get_parent().to_string().bold()
+			);
+		}
+
+		let project = Project::load(&project_path)?;
+
+		if !project.is_place() {
+			bail!("Cannot execute code in non-place project!");
+		}
+
+		let core = Core::new(project, false, false)?;
+		let host = self.host.unwrap_or(core.host().unwrap_or(config.host.clone()));
+		let port = self.port.unwrap_or(core.port().unwrap_or(config.port));
+
+		if !server::is_port_free(&host, port) {
+			bail!(
+				"Port {} is already in use! Stop the running session or use a different port",
+				port.to_string().bold()
+			);
+		}
+
+		let core = Arc::new(core);
+		let queue = core.queue();
+
+		// Roblox Studio is not available on Linux so
+		// any client that connects to the server is used
+		#[cfg(not(target_os = "linux"))]
+		let studio = {
+			let place_path = std::env::temp_dir().join(format!("argon-{}.rbxl", uuid::Uuid::new_v4()));
+
+			core.build(&place_path, false)?;
+
+			trace!("Launching Roblox Studio with place: {}", place_path.display());
+
+			(crate::studio::launch(Some(place_path.clone()))?, place_path)
+		};
+
+		let token = server::generate_token();
+		let server = Server::new(core, &host, port, token.clone());
+
+		thread::spawn(move || server.start());
+
+		argon_info!(
+			"Waiting for client to connect to: {}{}",
+			server::format_address(&host, port).bold(),
+			token
+				.as_ref()
+				.map(|token| format!(", token: {}", token.bold()))
+				.unwrap_or_default()
+		);
+
+		let address = server::format_address(&host, port);
+		let result = Self::execute_standalone(code, &address, token.as_deref(), self.timeout, &queue);
+
+		#[cfg(not(target_os = "linux"))]
+		{
+			let (mut child, place_path) = studio;
+
+			child.kill().ok();
+			fs::remove_file(place_path).ok();
+		}
+
+		result
+	}
+
+	fn execute_standalone(
+		code: String,
+		address: &str,
+		token: Option<&str>,
+		timeout: Option<u64>,
+		queue: &Queue,
+	) -> Result<()> {
+		let start = Instant::now();
+
+		while queue.get_first_non_internal_listener_id().is_none() {
+			if start.elapsed() > STANDALONE_TIMEOUT {
+				bail!(
+					"No client connected within {} seconds",
+					STANDALONE_TIMEOUT.as_secs().to_string().bold()
+				);
+			}
+
+			thread::sleep(Duration::from_millis(100));
+		}
+
+		let id = queue.get_first_non_internal_listener_id().unwrap();
+
+		trace!("Client {id} connected, executing code");
+
+		let result = execute(address, token, code, false, timeout);
+
+		queue.disconnect("Standalone code execution finished", id)?;
+
+		// Give the client a chance to receive the disconnect message
+		let start = Instant::now();
+
+		while !queue.is_empty(id) && start.elapsed() < Duration::from_secs(5) {
+			thread::sleep(Duration::from_millis(100));
+		}
+
+		result
+	}
+}
+
+/// Sends the code to the server and waits for the execution result
+fn execute(address: &str, token: Option<&str>, code: String, focus: bool, timeout: Option<u64>) -> Result<()> {
+	let url = format!("{address}/exec");
+	let body = rmp_serde::to_vec(&Request { code, focus, timeout })?;
+
+	let request_timeout = timeout.map(Duration::from_secs).unwrap_or(EXEC_TIMEOUT) + Duration::from_secs(5);
+
+	let mut request = Client::default()
+		.post(url)
+		.header(CONTENT_TYPE, "application/msgpack")
+		.body(body)
+		.timeout(request_timeout);
+
+	if let Some(token) = token {
+		request = request.bearer_auth(token);
+	}
+
+	let response = request
+		.send()
+		.map_err(|err| anyhow!("Code execution failed: {}", err))?;
+
+	if !response.status().is_success() {
+		bail!("Code execution failed: {}", response.text()?);
+	}
+
+	let result: ExecutionResult = rmp_serde::from_slice(&response.bytes()?)?;
+
+	for log in result.logs {
+		match log.kind {
+			LogKind::Output | LogKind::Info => argon_info!("{}", log.message),
+			LogKind::Warning => argon_warn!("{}", log.message),
+			LogKind::Error => argon_error!("{}", log.message),
+		}
+	}
+
+	if let Some(error) = result.error {
+		if let Some(traceback) = result.traceback {
+			bail!("Code execution failed: {}\n{}", error, traceback.trim_end());
+		}
+
+		bail!("Code execution failed: {}", error);
+	}
+
+	if result.values.is_empty() {
+		argon_info!("Code executed successfully!");
+	} else {
+		argon_info!(
+			"Code executed successfully! Returned: {}",
+			result.values.join(", ").bold()
+		);
+	}
+
+	Ok(())
 }
 
 #[derive(Serialize)]
 struct Request {
 	code: String,
 	focus: bool,
+	timeout: Option<u64>,
 }
@@ -0,0 +1,75 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::{
+	argon_info, argon_warn,
+	config::Config,
+	core::history::{History as SyncbackHistory, Summary},
+	ext::PathExt,
+	logger::Table,
+	project,
+};
+
+/// List syncback changes that can be reverted with `argon undo`
+#[derive(Parser)]
+pub struct History {
+	/// Project path
+	#[arg()]
+	project: Option<PathBuf>,
+}
+
+impl History {
+	pub fn main(self) -> Result<()> {
+		let project_path = project::resolve(self.project.unwrap_or_default())?;
+
+		Config::load_workspace(project_path.get_parent());
+
+		if !project_path.exists() {
+			bail!(
+				"No project files found in {}",
+				project_path.get_parent().to_string().bold()
+			);
+		}
+
+		let summaries = SyncbackHistory::new(&project_path)?.list()?;
+
+		if summaries.is_empty() {
+			argon_warn!("There are no syncback changes in the history");
+			return Ok(());
+		}
+
+		argon_info!(
+			"Syncback history (most recent first):\n\n{}",
+			table(&summaries, project_path.get_parent())
+		);
+
+		Ok(())
+	}
+}
+
+pub fn table(summaries: &[Summary], workspace_dir: &Path) -> Table {
+	let mut table = Table::new();
+	table.set_header(vec!["ID", "Time", "Additions", "Updates", "Removals", "Files"]);
+
+	for summary in summaries {
+		let summary = summary.clone().relative_to(workspace_dir);
+
+		table.add_row(vec![
+			summary.id.to_string(),
+			summary.time(),
+			summary.additions.to_string(),
+			summary.updates.to_string(),
+			summary.removals.to_string(),
+			summary
+				.paths
+				.iter()
+				.map(|path| path.to_string())
+				.collect::<Vec<String>>()
+				.join(", "),
+		]);
+	}
+
+	table
+}
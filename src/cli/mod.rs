@@ -7,17 +7,21 @@ use std::env;
 
 use crate::util;
 
+mod assets;
 mod build;
 mod config;
 mod debug;
 mod doc;
 mod exec;
+mod history;
 mod init;
 mod plugin;
 mod serve;
 mod sourcemap;
 mod stop;
 mod studio;
+mod syncback;
+mod undo;
 mod update;
 
 macro_rules! about {
@@ -124,6 +128,10 @@ impl Cli {
 			Commands::Serve(command) => command.main(),
 			Commands::Build(command) => command.main(),
 			Commands::Sourcemap(command) => command.main(),
+			Commands::Syncback(command) => command.main(),
+			Commands::Assets(command) => command.main(),
+			Commands::History(command) => command.main(),
+			Commands::Undo(command) => command.main(),
 			Commands::Stop(command) => command.main(),
 			Commands::Studio(command) => command.main(),
 			Commands::Debug(command) => command.main(),
@@ -142,6 +150,10 @@ pub enum Commands {
 	Serve(serve::Serve),
 	Build(build::Build),
 	Sourcemap(sourcemap::Sourcemap),
+	Syncback(syncback::Syncback),
+	Assets(assets::Assets),
+	History(history::History),
+	Undo(undo::Undo),
 	Stop(stop::Stop),
 	Studio(studio::Studio),
 	Debug(debug::Debug),
@@ -107,7 +107,7 @@ impl Serve {
 			}
 		}
 
-		let core = Core::new(project, true)?;
+		let core = Core::new(project, true, true)?;
 		let host = self.host.unwrap_or(core.host().unwrap_or(config.host.clone()));
 		let mut port = self.port.unwrap_or(core.port().unwrap_or(config.port));
 
@@ -156,22 +156,29 @@ impl Serve {
 			});
 		}
 
+		let token = server::generate_token();
+
 		sessions::add(
 			self.session,
 			Some(host.clone()),
 			Some(port),
+			token.clone(),
 			process::id(),
 			config.run_async,
 		)?;
 
-		let server = Server::new(core, &host, port);
-
 		argon_info!(
-			"Serving on: {}, project: {}",
+			"Serving on: {}, project: {}{}",
 			server::format_address(&host, port).bold(),
-			project_path.to_string().bold()
+			project_path.to_string().bold(),
+			token
+				.as_ref()
+				.map(|token| format!(", token: {}", token.bold()))
+				.unwrap_or_default()
 		);
 
+		let server = Server::new(core, &host, port, token);
+
 		server.start()?;
 
 		Ok(())
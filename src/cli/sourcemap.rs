@@ -77,7 +77,7 @@ impl Sourcemap {
 		}
 
 		let project = Project::load(&project_path)?;
-		let core = Core::new(project, self.watch)?;
+		let core = Core::new(project, self.watch, false)?;
 
 		core.sourcemap(self.output.clone(), self.non_scripts)?;
 
@@ -90,7 +90,7 @@ impl Sourcemap {
 		}
 
 		if self.watch {
-			sessions::add(self.session, None, None, process::id(), config.run_async)?;
+			sessions::add(self.session, None, None, None, process::id(), config.run_async)?;
 
 			if self.output.is_some() {
 				argon_info!("Watching for changes..");
@@ -66,7 +66,7 @@ impl Stop {
 
 			for (_, session) in sessions {
 				if let Some(address) = session.get_address() {
-					Self::make_request(&address, session.pid);
+					Self::make_request(&address, session.token.as_deref(), session.pid);
 				} else {
 					Self::kill_process(session.pid);
 				}
@@ -78,7 +78,7 @@ impl Stop {
 		if self.session.is_empty() {
 			if let Some(session) = sessions::get(None, self.host, self.port)? {
 				if let Some(address) = session.get_address() {
-					Self::make_request(&address, session.pid);
+					Self::make_request(&address, session.token.as_deref(), session.pid);
 				} else {
 					Self::kill_process(session.pid);
 				}
@@ -95,7 +95,7 @@ impl Stop {
 			} else {
 				for session in sessions.values() {
 					if let Some(address) = session.get_address() {
-						Self::make_request(&address, session.pid);
+						Self::make_request(&address, session.token.as_deref(), session.pid);
 					} else {
 						Self::kill_process(session.pid);
 					}
@@ -108,12 +108,19 @@ impl Stop {
 		Ok(())
 	}
 
-	fn make_request(address: &String, pid: u32) {
+	fn make_request(address: &String, token: Option<&str>, pid: u32) {
 		let url = format!("{address}/stop");
+		let mut request = Client::new().post(url);
 
-		match Client::new().post(url).send() {
-			Ok(_) => argon_info!("Stopped Argon session with address: {}", address.bold()),
-			Err(_) => {
+		if let Some(token) = token {
+			request = request.bearer_auth(token);
+		}
+
+		match request.send() {
+			Ok(response) if response.status().is_success() => {
+				argon_info!("Stopped Argon session with address: {}", address.bold())
+			}
+			_ => {
 				Self::kill_process(pid);
 			}
 		}
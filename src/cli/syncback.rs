@@ -0,0 +1,102 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+use crate::{
+	argon_info, argon_warn,
+	config::Config,
+	core::{processor, Core},
+	ext::PathExt,
+	project::{self, Project},
+};
+
+/// Write differences between a place or model file and the project to the disk
+#[derive(Parser)]
+pub struct Syncback {
+	/// Place or model file path
+	#[arg()]
+	input: PathBuf,
+
+	/// Project path
+	#[arg()]
+	project: Option<PathBuf>,
+
+	/// Only print planned filesystem changes without applying them
+	#[arg(short, long)]
+	dry_run: bool,
+}
+
+impl Syncback {
+	pub fn main(self) -> Result<()> {
+		let project_path = project::resolve(self.project.clone().unwrap_or_default())?;
+
+		Config::load_workspace(project_path.get_parent());
+
+		if !project_path.exists() {
+			bail!(
+				"No project files found in {}",
+				project_path.get_parent().to_string().bold()
+			);
+		}
+
+		let input = self.input.resolve()?;
+
+		if !input.is_file() {
+			bail!("File {} does not exist", input.to_string().bold());
+		}
+
+		let project = Project::load(&project_path)?;
+		let ext = input.get_ext();
+
+		if !["rbxl", "rbxlx", "rbxm", "rbxmx"].contains(&ext) {
+			bail!(
+				"Invalid file extension: {}. Only {}, {}, {}, {} extensions are allowed",
+				ext.bold(),
+				"rbxl".bold(),
+				"rbxlx".bold(),
+				"rbxm".bold(),
+				"rbxmx".bold(),
+			);
+		} else if ext.starts_with("rbxm") && project.is_place() {
+			bail!("Cannot syncback model into place project");
+		} else if ext.starts_with("rbxl") && !project.is_place() {
+			bail!("Cannot syncback place into plugin or model project");
+		}
+
+		let core = Core::new(project, false, false)?;
+		let changes = core.diff(&input)?;
+
+		if changes.is_empty() {
+			argon_info!("Project is already up to date with {}", input.get_name().bold());
+			return Ok(());
+		}
+
+		let preview = core.preview(changes.clone())?;
+
+		if preview.plan.is_empty() {
+			argon_warn!("Changes found in {} do not affect any files", input.get_name().bold());
+			return Ok(());
+		}
+
+		argon_info!("Planned filesystem changes:\n\n{}", preview.plan);
+
+		if self.dry_run {
+			return Ok(());
+		}
+
+		if !processor::confirm_changes(&changes) {
+			return Ok(());
+		}
+
+		core.apply_preview(changes, preview)?;
+
+		argon_info!(
+			"Successfully synced back {} to the project, use {} to revert",
+			input.get_name().bold(),
+			"argon undo".bold()
+		);
+
+		Ok(())
+	}
+}
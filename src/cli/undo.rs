@@ -0,0 +1,56 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+use super::history;
+use crate::{argon_info, argon_warn, config::Config, core::history::History, ext::PathExt, project, vfs::Vfs};
+
+/// Revert the most recent syncback changes
+#[derive(Parser)]
+pub struct Undo {
+	/// Project path
+	#[arg()]
+	project: Option<PathBuf>,
+
+	/// Number of syncback changes to revert
+	#[arg(short, long, default_value_t = 1)]
+	count: usize,
+}
+
+impl Undo {
+	pub fn main(self) -> Result<()> {
+		let project_path = project::resolve(self.project.unwrap_or_default())?;
+
+		Config::load_workspace(project_path.get_parent());
+
+		if !project_path.exists() {
+			bail!(
+				"No project files found in {}",
+				project_path.get_parent().to_string().bold()
+			);
+		}
+
+		let history = History::new(&project_path)?;
+
+		if history.is_empty()? {
+			argon_warn!("There are no syncback changes to undo");
+			return Ok(());
+		}
+
+		let reverted = history.undo(self.count, &Vfs::new(false), true)?;
+
+		if reverted.is_empty() {
+			argon_warn!("No syncback changes were undone");
+			return Ok(());
+		}
+
+		argon_info!(
+			"Reverted {} syncback changes:\n\n{}",
+			reverted.len().to_string().bold(),
+			history::table(&reverted, project_path.get_parent())
+		);
+
+		Ok(())
+	}
+}
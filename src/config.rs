@@ -86,6 +86,10 @@ pub struct Config {
 	pub changes_threshold: usize,
 	/// Maximum number of unsynced changes before showing a warning
 	pub max_unsynced_changes: usize,
+	/// Number of applied syncback changes that can be undone (0 to disable)
+	pub history_size: usize,
+	/// Require clients to provide the session token printed by `argon serve`
+	pub require_token: bool,
 
 	/// Use .lua file extension instead of .luau when writing scripts
 	pub lua_extension: bool,
@@ -133,6 +137,8 @@ impl Default for Config {
 			move_to_bin: false,
 			changes_threshold: 5,
 			max_unsynced_changes: 10,
+			history_size: 20,
+			require_token: false,
 
 			lua_extension: false,
 			ignore_line_endings: true,
@@ -7,6 +7,13 @@ use crate::{core::meta::SyncRule, middleware::Middleware};
 // be able to set them in `sync_rules` or project `$path`
 pub const BLACKLISTED_PATHS: [&str; 1] = [".DS_Store"];
 
+/// Name of the gitignore-style file that ignores paths in its directory
+pub const IGNORE_FILE: &str = ".argonignore";
+
+/// Name of the file that maps asset files to their content IDs
+pub const ASSET_LOCK: &str = "assets.lock";
+pub const ASSET_LOCK_HEADER: &str = "# Generated by `argon assets`, maps asset files to their content IDs";
+
 // Current version of the project templates, this constant
 // should be manually bumped when there are any changes
 // made to the `assets/templates` directory
@@ -22,6 +29,26 @@ pub const MAX_PAYLOAD_SIZE: usize = 536_870_912;
 /// the client request and sending back an empty `Changes`
 pub const QUEUE_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Maximum number of messages and individual changes
+/// that can wait in a single client's queue, once it is
+/// exceeded the client is asked to resync the whole tree
+pub const QUEUE_CAPACITY: usize = 10_000;
+
+/// How long the server should wait for the client
+/// to return the result of the executed code
+/// when no timeout was specified in the request
+pub const EXEC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long `argon exec --standalone` should wait
+/// for the client to connect to the temporary server
+/// before giving up and shutting everything down
+pub const STANDALONE_TIMEOUT: Duration = Duration::from_secs(120);
+
+// `argon build --watch` waits this long after the
+// last change before rebuilding, so bursts of changes
+// (like saving many files at once) trigger one build
+pub const BUILD_DEBOUNCE_TIME: Duration = Duration::from_millis(300);
+
 // VFS events will be ignored for this amount of time
 // after the last change that has been made by the client,
 // this saves a lot of computing time
@@ -45,6 +72,15 @@ pub fn default_sync_rules() -> &'static Vec<SyncRule> {
 			SyncRule::new(Middleware::InstanceData)
 				.with_pattern("*.meta.json")
 				.with_child_pattern("init.meta.json"),
+			SyncRule::new(Middleware::InstanceData)
+				.with_pattern("*.data.toml")
+				.with_child_pattern(".data.toml"),
+			SyncRule::new(Middleware::InstanceData)
+				.with_pattern("*.data.yaml")
+				.with_child_pattern(".data.yaml"),
+			SyncRule::new(Middleware::InstanceData)
+				.with_pattern("*.data.yml")
+				.with_child_pattern(".data.yml"),
 			//////////////////////////////////////////////////////////////////////////////////////////
 			// Luau scripts
 			SyncRule::new(Middleware::ServerScript)
@@ -112,19 +148,40 @@ pub fn default_sync_rules() -> &'static Vec<SyncRule> {
 			SyncRule::new(Middleware::LocalizationTable)
 				.with_pattern("*.csv")
 				.with_child_pattern("init.csv"),
+			SyncRule::new(Middleware::NumberValue)
+				.with_pattern("*.number")
+				.with_child_pattern("init.number"),
+			SyncRule::new(Middleware::BoolValue)
+				.with_pattern("*.bool")
+				.with_child_pattern("init.bool"),
+			SyncRule::new(Middleware::IntValue)
+				.with_pattern("*.int")
+				.with_child_pattern("init.int"),
+			SyncRule::new(Middleware::Color3Value)
+				.with_pattern("*.color")
+				.with_child_pattern("init.color"),
 			SyncRule::new(Middleware::JsonModule)
 				.with_pattern("*.json")
 				.with_child_pattern("init.json")
 				.with_excludes(&["*.model.json", "*.data.json", "*.meta.json"]),
+			SyncRule::new(Middleware::JsonModule)
+				.with_pattern("*.jsonc")
+				.with_child_pattern("init.jsonc"),
+			SyncRule::new(Middleware::JsonModule)
+				.with_pattern("*.json5")
+				.with_child_pattern("init.json5"),
 			SyncRule::new(Middleware::TomlModule)
 				.with_pattern("*.toml")
-				.with_child_pattern("init.toml"),
+				.with_child_pattern("init.toml")
+				.with_exclude("*.data.toml"),
 			SyncRule::new(Middleware::YamlModule)
 				.with_pattern("*.yaml")
-				.with_child_pattern("init.yaml"),
+				.with_child_pattern("init.yaml")
+				.with_exclude("*.data.yaml"),
 			SyncRule::new(Middleware::YamlModule)
 				.with_pattern("*.yml")
-				.with_child_pattern("init.yml"),
+				.with_child_pattern("init.yml")
+				.with_exclude("*.data.yml"),
 			SyncRule::new(Middleware::MsgpackModule)
 				.with_pattern("*.msgpack")
 				.with_child_pattern("init.msgpack"),
@@ -142,3 +199,12 @@ pub fn default_sync_rules() -> &'static Vec<SyncRule> {
 		]
 	})
 }
+
+/// Sync rules for image and audio files, only
+/// used when `syncAssets` is enabled in the project
+pub fn asset_sync_rules() -> Vec<SyncRule> {
+	["png", "jpg", "jpeg", "bmp", "tga", "ogg", "mp3", "wav", "flac"]
+		.iter()
+		.map(|extension| SyncRule::new(Middleware::Asset).with_pattern(&format!("*.{extension}")))
+		.collect()
+}
@@ -1,7 +1,11 @@
-use rbx_dom_weak::types::Ref;
+use rbx_dom_weak::{types::Ref, Ustr};
 use serde::{Deserialize, Serialize};
 
-use super::snapshot::{AddedSnapshot, Snapshot, UpdatedSnapshot};
+use super::{
+	meta::Meta,
+	snapshot::{AddedSnapshot, Snapshot, UpdatedSnapshot},
+};
+use crate::Properties;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Changes {
@@ -45,6 +49,94 @@ impl Changes {
 		self.removals.extend(changes.removals);
 	}
 
+	/// Merges changes that happened after these ones, so the
+	/// result is equivalent to applying both sets in order:
+	/// updates are folded into pending additions or updates of
+	/// the same instance and removals cancel pending additions
+	/// and updates of the removed instance and everything
+	/// that was added inside it
+	pub fn merge(&mut self, changes: Self) {
+		self.additions.extend(changes.additions);
+
+		for update in changes.updates {
+			if let Some(addition) = self.additions.iter_mut().find(|addition| addition.id == update.id) {
+				apply_update(
+					&mut addition.name,
+					&mut addition.class,
+					&mut addition.properties,
+					&mut addition.meta,
+					update,
+				);
+			} else if let Some(snapshot) = self
+				.additions
+				.iter_mut()
+				.find_map(|addition| find_child(&mut addition.children, update.id))
+			{
+				apply_update(
+					&mut snapshot.name,
+					&mut snapshot.class,
+					&mut snapshot.properties,
+					&mut snapshot.meta,
+					update,
+				);
+			} else if let Some(existing) = self.updates.iter_mut().find(|existing| existing.id == update.id) {
+				existing.meta = update.meta.or(existing.meta.take());
+				existing.name = update.name.or(existing.name.take());
+				existing.class = update.class.or(existing.class.take());
+				existing.properties = update.properties.or(existing.properties.take());
+			} else {
+				self.updates.push(update);
+			}
+		}
+
+		for id in changes.removals {
+			let mut removed = Vec::new();
+			let mut was_added = false;
+
+			if let Some(index) = self.additions.iter().position(|addition| addition.id == id) {
+				collect_ids(&self.additions.remove(index).children, &mut removed);
+				was_added = true;
+			} else if let Some(child) = self
+				.additions
+				.iter_mut()
+				.find_map(|addition| take_child(&mut addition.children, id))
+			{
+				collect_ids(&child.children, &mut removed);
+				was_added = true;
+			}
+
+			removed.push(id);
+
+			// Additions inside the removed subtree would fail anyway as their parent
+			// is gone, they can be parented to other pending additions so the whole
+			// subtree is collected first, including descendants of these additions
+			let mut index = 0;
+
+			while index < removed.len() {
+				let parent = removed[index];
+
+				self.additions.retain(|addition| {
+					if addition.parent != parent {
+						return true;
+					}
+
+					removed.push(addition.id);
+					collect_ids(&addition.children, &mut removed);
+
+					false
+				});
+
+				index += 1;
+			}
+
+			self.updates.retain(|update| !removed.contains(&update.id));
+
+			if !was_added && !self.removals.contains(&id) {
+				self.removals.push(id);
+			}
+		}
+	}
+
 	pub fn is_empty(&self) -> bool {
 		self.additions.is_empty() && self.updates.is_empty() && self.removals.is_empty()
 	}
@@ -53,3 +145,58 @@ impl Changes {
 		self.additions.len() + self.updates.len() + self.removals.len()
 	}
 }
+
+fn apply_update(
+	name: &mut String,
+	class: &mut Ustr,
+	properties: &mut Properties,
+	meta: &mut Meta,
+	update: UpdatedSnapshot,
+) {
+	if let Some(new_name) = update.name {
+		*name = new_name;
+	}
+
+	if let Some(new_class) = update.class {
+		*class = new_class;
+	}
+
+	if let Some(new_properties) = update.properties {
+		*properties = new_properties;
+	}
+
+	if let Some(new_meta) = update.meta {
+		*meta = new_meta;
+	}
+}
+
+fn find_child(children: &mut [Snapshot], id: Ref) -> Option<&mut Snapshot> {
+	for child in children {
+		if child.id == id {
+			return Some(child);
+		}
+
+		if let Some(child) = find_child(&mut child.children, id) {
+			return Some(child);
+		}
+	}
+
+	None
+}
+
+fn take_child(children: &mut Vec<Snapshot>, id: Ref) -> Option<Snapshot> {
+	if let Some(index) = children.iter().position(|child| child.id == id) {
+		return Some(children.remove(index));
+	}
+
+	children
+		.iter_mut()
+		.find_map(|child| take_child(&mut child.children, id))
+}
+
+fn collect_ids(children: &[Snapshot], ids: &mut Vec<Ref>) {
+	for child in children {
+		ids.push(child.id);
+		collect_ids(&child.children, ids);
+	}
+}
@@ -0,0 +1,203 @@
+use rbx_dom_weak::{
+	types::{Ref, Variant},
+	Instance, WeakDom,
+};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+	core::{
+		changes::Changes,
+		meta::SyncbackFilter,
+		snapshot::{Snapshot, UpdatedSnapshot},
+		tree::Tree,
+	},
+	util, Properties,
+};
+
+/// Returns changes that make the tree match the given DOM (loaded from
+/// a place or model file), starting from the paired root instances.
+/// Children of the place root (services) are only compared when they
+/// already exist in the tree, so services unknown to the project are skipped
+pub fn diff(dom: &WeakDom, dom_root: Ref, tree: &Tree, is_place: bool) -> Changes {
+	let mut differ = Differ {
+		dom,
+		tree,
+		pairs: Vec::new(),
+		refs: HashMap::new(),
+		added: Vec::new(),
+		added_refs: HashSet::new(),
+		removed: Vec::new(),
+	};
+
+	differ.pair(dom_root, tree.root_ref(), is_place);
+
+	let mut changes = Changes::new();
+
+	for &(dom_id, tree_id) in &differ.pairs {
+		if is_place && tree_id == tree.root_ref() {
+			continue;
+		}
+
+		let filter = tree.get_meta(tree_id).unwrap().context.syncback_filter();
+		let instance = tree.get_instance(tree_id).unwrap();
+
+		let current = normalize_properties(instance, filter);
+		let properties = differ.dom_properties(dom.get_by_ref(dom_id).unwrap(), filter);
+
+		if current != properties {
+			let mut updated_snapshot = UpdatedSnapshot::new(tree_id);
+			updated_snapshot.properties = Some(properties);
+
+			changes.update(updated_snapshot);
+		}
+	}
+
+	for &(dom_id, parent) in &differ.added {
+		let filter = tree.get_meta(parent).unwrap().context.syncback_filter();
+
+		changes.add(differ.snapshot(dom_id, filter), parent);
+	}
+
+	for &id in &differ.removed {
+		changes.remove(id);
+	}
+
+	changes
+}
+
+struct Differ<'a> {
+	dom: &'a WeakDom,
+	tree: &'a Tree,
+	/// DOM instances paired with their tree counterparts
+	pairs: Vec<(Ref, Ref)>,
+	/// Same as `pairs`, used to convert DOM refs to tree refs
+	refs: HashMap<Ref, Ref>,
+	/// DOM instances missing in the tree, with their tree parents
+	added: Vec<(Ref, Ref)>,
+	/// Refs of added instances and their descendants that keep them in snapshots
+	added_refs: HashSet<Ref>,
+	/// Tree instances missing in the DOM
+	removed: Vec<Ref>,
+}
+
+impl Differ<'_> {
+	/// Pairs children of the given instances by their name and class
+	fn pair(&mut self, dom_id: Ref, tree_id: Ref, skip_unpaired: bool) {
+		self.pairs.push((dom_id, tree_id));
+		self.refs.insert(dom_id, tree_id);
+
+		let filter = self.tree.get_meta(tree_id).unwrap().context.syncback_filter();
+		let dom_children = self.children(dom_id, filter);
+
+		let mut hydrated = vec![false; dom_children.len()];
+
+		for &tree_child in self.tree.get_instance(tree_id).unwrap().children() {
+			let instance = self.tree.get_instance(tree_child).unwrap();
+
+			if is_filtered(instance, filter) {
+				continue;
+			}
+
+			let paired = dom_children.iter().enumerate().find(|(index, &dom_child)| {
+				let dom_instance = self.dom.get_by_ref(dom_child).unwrap();
+
+				!hydrated[*index] && dom_instance.name == instance.name && dom_instance.class == instance.class
+			});
+
+			if let Some((index, &dom_child)) = paired {
+				hydrated[index] = true;
+				self.pair(dom_child, tree_child, false);
+			} else if !skip_unpaired {
+				self.removed.push(tree_child);
+			}
+		}
+
+		if !skip_unpaired {
+			for (index, dom_child) in dom_children.into_iter().enumerate() {
+				if !hydrated[index] {
+					self.added.push((dom_child, tree_id));
+					self.insert_added(dom_child, filter);
+				}
+			}
+		}
+	}
+
+	/// Remembers refs of the added instance and its descendants
+	fn insert_added(&mut self, id: Ref, filter: &SyncbackFilter) {
+		self.added_refs.insert(id);
+
+		for child in self.children(id, filter) {
+			self.insert_added(child, filter);
+		}
+	}
+
+	/// Returns children of the DOM instance that are not filtered out
+	fn children(&self, id: Ref, filter: &SyncbackFilter) -> Vec<Ref> {
+		self.dom
+			.get_by_ref(id)
+			.unwrap()
+			.children()
+			.iter()
+			.filter(|&&child| !is_filtered(self.dom.get_by_ref(child).unwrap(), filter))
+			.copied()
+			.collect()
+	}
+
+	/// Normalized properties of the DOM instance with refs pointing to
+	/// paired instances converted to tree refs, refs to added instances
+	/// are kept and refs to instances that won't exist in the tree are dropped
+	fn dom_properties(&self, instance: &Instance, filter: &SyncbackFilter) -> Properties {
+		normalize_properties(instance, filter)
+			.into_iter()
+			.filter_map(|(property, value)| match value {
+				Variant::Ref(target) => match self.refs.get(&target) {
+					Some(id) => Some((property, Variant::Ref(*id))),
+					None if self.added_refs.contains(&target) => Some((property, value)),
+					None => None,
+				},
+				value => Some((property, value)),
+			})
+			.collect()
+	}
+
+	/// Creates snapshot of the DOM instance and all its descendants,
+	/// instances keep their DOM refs so refs between them stay valid
+	fn snapshot(&self, id: Ref, filter: &SyncbackFilter) -> Snapshot {
+		let instance = self.dom.get_by_ref(id).unwrap();
+
+		let children = self
+			.children(id, filter)
+			.into_iter()
+			.map(|child| self.snapshot(child, filter))
+			.collect();
+
+		Snapshot::new()
+			.with_id(id)
+			.with_name(&instance.name)
+			.with_class(&instance.class)
+			.with_properties(self.dom_properties(instance, filter))
+			.with_children(children)
+	}
+}
+
+fn is_filtered(instance: &Instance, filter: &SyncbackFilter) -> bool {
+	filter.matches_name(&instance.name) || filter.matches_class(&instance.class)
+}
+
+/// Removes properties that are filtered out, equal to their default
+/// values or can't be stored in files (unique IDs and shared strings)
+fn normalize_properties(instance: &Instance, filter: &SyncbackFilter) -> Properties {
+	let database = util::get_reflection_database();
+	let class = database.classes.get(instance.class.as_str());
+
+	instance
+		.properties
+		.iter()
+		.filter(|(property, value)| {
+			!filter.matches_property(property)
+				&& !matches!(value, Variant::UniqueId(_) | Variant::SharedString(_))
+				&& class.and_then(|class| database.find_default_property(class, property)) != Some(*value)
+		})
+		.map(|(property, value)| (*property, value.clone()))
+		.collect()
+}
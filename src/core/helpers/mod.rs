@@ -2,6 +2,8 @@ use crate::Properties;
 
 mod migrations;
 
+pub mod diff;
+pub mod refs;
 pub mod syncback;
 
 #[inline]
@@ -0,0 +1,125 @@
+use rbx_dom_weak::{
+	types::{Ref, Variant},
+	Ustr, UstrMap, WeakDom,
+};
+
+use crate::{resolution::is_ref_property, Properties};
+
+/// Removes ref properties that hold instance paths instead of
+/// actual refs from the given properties and returns them
+pub fn take_ref_paths(class: &str, properties: &mut Properties) -> UstrMap<String> {
+	let paths: Vec<Ustr> = properties
+		.iter()
+		.filter(|(property, value)| matches!(value, Variant::String(_)) && is_ref_property(class, property))
+		.map(|(property, _)| *property)
+		.collect();
+
+	paths
+		.into_iter()
+		.filter_map(|property| match properties.remove(&property) {
+			Some(Variant::String(path)) => Some((property, path)),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Finds instance with the given path, relative to `from` if it
+/// starts with `.` or `..`, otherwise relative to the DOM root,
+/// empty path resolves to `nil`
+pub fn find_by_path(dom: &WeakDom, from: Ref, path: &str) -> Option<Ref> {
+	if path.is_empty() {
+		return Some(Ref::none());
+	}
+
+	let mut current = if path == "." || path == ".." || path.starts_with("./") || path.starts_with("../") {
+		from
+	} else {
+		dom.root_ref()
+	};
+
+	for segment in path.split('/') {
+		let instance = dom.get_by_ref(current)?;
+
+		current = match segment {
+			"." | "" => current,
+			".." => instance.parent(),
+			name => *instance
+				.children()
+				.iter()
+				.find(|child| dom.get_by_ref(**child).is_some_and(|child| child.name == name))?,
+		};
+	}
+
+	if dom.get_by_ref(current).is_some() {
+		Some(current)
+	} else {
+		None
+	}
+}
+
+/// Returns path of the target relative to the DOM root,
+/// example: `Workspace/Map/SpawnLocation`
+pub fn absolute_path(dom: &WeakDom, target: Ref) -> Option<String> {
+	let mut ancestors = ancestors(dom, target)?;
+
+	// Remove DOM root
+	ancestors.pop();
+
+	if ancestors.is_empty() {
+		return None;
+	}
+
+	join_names(dom, ancestors.into_iter().rev())
+}
+
+/// Returns path of the target relative to `from`,
+/// example: `../Handle` for a sibling instance
+pub fn relative_path(dom: &WeakDom, from: Ref, target: Ref) -> Option<String> {
+	let from_ancestors = ancestors(dom, from)?;
+	let target_ancestors = ancestors(dom, target)?;
+
+	let (ups, common) = from_ancestors
+		.iter()
+		.enumerate()
+		.find(|(_, id)| target_ancestors.contains(id))?;
+
+	let position = target_ancestors.iter().position(|id| id == common)?;
+	let downs = join_names(dom, target_ancestors[..position].iter().rev().copied())?;
+
+	let mut path = if ups == 0 {
+		String::from(".")
+	} else {
+		vec![".."; ups].join("/")
+	};
+
+	if !downs.is_empty() {
+		path.push('/');
+		path.push_str(&downs);
+	}
+
+	Some(path)
+}
+
+/// Returns the given instance and all its ancestors, ending with the DOM root
+fn ancestors(dom: &WeakDom, id: Ref) -> Option<Vec<Ref>> {
+	dom.get_by_ref(id)?;
+
+	Some(dom.ancestors_of(id).map(|instance| instance.referent()).collect())
+}
+
+fn join_names(dom: &WeakDom, ids: impl Iterator<Item = Ref>) -> Option<String> {
+	let mut names = Vec::new();
+
+	for id in ids {
+		let name = &dom.get_by_ref(id)?.name;
+
+		// Such instances can't be addressed with paths
+		if name.contains('/') || name == "." || name == ".." || name.is_empty() {
+			return None;
+		}
+
+		names.push(name.as_str());
+	}
+
+	Some(names.join("/"))
+}
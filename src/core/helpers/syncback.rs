@@ -1,14 +1,28 @@
 use colored::Colorize;
-use rbx_dom_weak::{ustr, HashMapExt, UstrMap};
-use std::path::{Path, PathBuf};
+use rbx_dom_weak::{
+	types::{Enum, Ref, Variant},
+	ustr, HashMapExt, UstrMap,
+};
+use std::{
+	collections::HashMap,
+	mem,
+	path::{Path, PathBuf},
+};
 use uuid::Uuid;
 
 use crate::{
 	argon_error, argon_warn,
 	config::Config,
-	core::meta::{Meta, SyncbackFilter},
+	core::{
+		helpers::refs,
+		meta::{Meta, SourceKind, SyncbackFilter},
+		snapshot::Snapshot,
+		tree::Tree,
+	},
 	ext::PathExt,
+	middleware::Middleware,
 	resolution::UnresolvedValue,
+	util,
 	vfs::Vfs,
 	Properties,
 };
@@ -193,6 +207,88 @@ pub fn serialize_properties(class: &str, properties: Properties) -> UstrMap<Unre
 		.collect()
 }
 
+/// Converts ref properties to instance paths so they can be stored in files,
+/// refs pointing to instances that are not part of the tree are skipped
+pub fn serialize_refs(properties: Properties, tree: &Tree) -> Properties {
+	properties
+		.into_iter()
+		.filter_map(|(property, value)| match value {
+			Variant::Ref(target) => {
+				refs::absolute_path(tree.inner(), target).map(|path| (property, Variant::String(path)))
+			}
+			value => Some((property, value)),
+		})
+		.collect()
+}
+
+/// Converts ref properties of the newly added snapshot and its descendants
+/// to instance paths, refs can point to the tree or to the snapshot itself
+pub fn serialize_snapshot_refs(snapshot: &mut Snapshot, parent: Ref, tree: &Tree) {
+	fn collect_paths(snapshot: &Snapshot, path: String, paths: &mut HashMap<Ref, String>) {
+		for child in &snapshot.children {
+			collect_paths(child, format!("{}/{}", path, child.name), paths);
+		}
+
+		paths.insert(snapshot.id, path);
+	}
+
+	fn convert(snapshot: &mut Snapshot, paths: &HashMap<Ref, String>, tree: &Tree) {
+		snapshot.properties = mem::take(&mut snapshot.properties)
+			.into_iter()
+			.filter_map(|(property, value)| match value {
+				Variant::Ref(target) => paths
+					.get(&target)
+					.cloned()
+					.or_else(|| refs::absolute_path(tree.inner(), target))
+					.map(|path| (property, Variant::String(path))),
+				value => Some((property, value)),
+			})
+			.collect();
+
+		for child in &mut snapshot.children {
+			convert(child, paths, tree);
+		}
+	}
+
+	let path = match refs::absolute_path(tree.inner(), parent) {
+		Some(parent_path) => format!("{}/{}", parent_path, snapshot.name),
+		None => snapshot.name.clone(),
+	};
+
+	let mut paths = HashMap::new();
+
+	collect_paths(snapshot, path, &mut paths);
+	convert(snapshot, &paths, tree);
+}
+
+/// Converts ref properties of the model snapshot to instance paths,
+/// relative for instances inside the model so it stays self-contained
+pub fn serialize_model_refs(snapshot: &mut Snapshot, model: Ref, tree: &Tree) {
+	let dom = tree.inner();
+
+	snapshot.properties = mem::take(&mut snapshot.properties)
+		.into_iter()
+		.filter_map(|(property, value)| match value {
+			Variant::Ref(target) => {
+				let is_inside = dom.get_by_ref(target).is_some()
+					&& dom.ancestors_of(target).any(|ancestor| ancestor.referent() == model);
+
+				if is_inside {
+					refs::relative_path(dom, snapshot.id, target)
+				} else {
+					refs::absolute_path(dom, target)
+				}
+				.map(|path| (property, Variant::String(path)))
+			}
+			value => Some((property, value)),
+		})
+		.collect();
+
+	for child in &mut snapshot.children {
+		serialize_model_refs(child, model, tree);
+	}
+}
+
 pub fn rename_path(path: &Path, from: &str, to: &str) -> PathBuf {
 	path.with_file_name(format!(
 		"{}{}",
@@ -200,3 +296,107 @@ pub fn rename_path(path: &Path, from: &str, to: &str) -> PathBuf {
 		path.get_name().strip_prefix(from).unwrap_or_default()
 	))
 }
+
+/// Returns the middleware that was used to read the instance's source file
+pub fn get_file_middleware(meta: &Meta) -> Option<Middleware> {
+	let path = meta.source.get_file()?.path();
+
+	meta.context
+		.sync_rules()
+		.iter()
+		.find_map(|rule| rule.resolve(path).or_else(|| rule.resolve_child(path)))
+		.map(|resolved| resolved.middleware)
+}
+
+/// Returns the middleware that should be used to write the instance,
+/// preferring the one that was used to read its current source file
+/// so the original file format is preserved
+pub fn get_middleware(class: &str, properties: &mut Properties, meta: &Meta) -> Option<Middleware> {
+	// Non-script instance stored in a module file
+	// must have been read from Luau data table
+	if let Some(current) = get_file_middleware(meta) {
+		if current == Middleware::LuauData || (current == Middleware::ModuleScript && !util::is_script(class)) {
+			return Some(Middleware::LuauData);
+		}
+
+		// Asset files are read-only, only their instance data is written
+		if current == Middleware::Asset {
+			return Some(current);
+		}
+	}
+
+	// Legacy scripts are read with `Legacy` run context by default
+	if meta.context.use_legacy_scripts()
+		&& class == "Script"
+		&& properties.get(&ustr("RunContext")) == Some(&Variant::Enum(Enum::from_u32(0)))
+	{
+		properties.remove(&ustr("RunContext"));
+	}
+
+	let middleware = Middleware::from_class(
+		class,
+		if !meta.context.use_legacy_scripts() {
+			Some(properties)
+		} else {
+			None
+		},
+	)?;
+
+	if let Some(current) = get_file_middleware(meta) {
+		if current.is_variant_of(&middleware) {
+			if current.can_write(properties) {
+				return Some(current);
+			}
+
+			argon_warn!(
+				"Instance with source: {} can no longer be written with {} middleware, using {} instead!",
+				meta.source.get_file().unwrap().path().to_string().bold(),
+				current.to_string().bold(),
+				middleware.to_string().bold()
+			);
+		}
+	}
+
+	Some(middleware)
+}
+
+/// Returns the closest instance (including the given one)
+/// whose whole subtree is stored in a single model file
+pub fn locate_model(id: Ref, tree: &Tree) -> Option<Ref> {
+	let mut current = id;
+
+	loop {
+		let meta = tree.get_meta(current)?;
+
+		if let SourceKind::None = meta.source.get() {
+			current = tree.get_instance(current)?.parent();
+			continue;
+		}
+
+		return get_file_middleware(meta)
+			.filter(|middleware| middleware.is_model())
+			.map(|_| current);
+	}
+}
+
+/// Creates snapshot of the instance and all its descendants
+pub fn snapshot_from_tree(id: Ref, tree: &Tree) -> Option<Snapshot> {
+	let instance = tree.get_instance(id)?;
+	let meta = tree.get_meta(id).cloned().unwrap_or_default();
+
+	let children = instance
+		.children()
+		.iter()
+		.filter_map(|&child| snapshot_from_tree(child, tree))
+		.collect();
+
+	Some(
+		Snapshot::new()
+			.with_id(id)
+			.with_meta(meta)
+			.with_name(&instance.name)
+			.with_class(&instance.class)
+			.with_properties(instance.properties.clone())
+			.with_children(children),
+	)
+}
@@ -0,0 +1,262 @@
+//! Bounded on-disk history of applied syncback changes
+//! that keeps everything needed to revert them later
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use super::{
+	changes::Changes,
+	processor::conflict::{self, Resolution},
+};
+use crate::{
+	argon_warn, assets,
+	config::Config,
+	ext::{PathExt, ResultExt},
+	util,
+	vfs::{journal::Journal, Vfs},
+};
+
+/// Client changes applied in a single syncback transaction
+/// together with the journal of filesystem changes they made
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transaction {
+	pub timestamp: i64,
+	pub changes: Changes,
+	pub journal: Journal,
+	/// Hashes of files affected by the transaction right after
+	/// it was applied, `None` for paths that did not exist
+	pub hashes: BTreeMap<PathBuf, Option<String>>,
+}
+
+impl Transaction {
+	/// Creates transaction from the journal of changes that were just applied
+	pub fn new(changes: Changes, journal: Journal, vfs: &Vfs) -> Self {
+		Self {
+			timestamp: Utc::now().timestamp(),
+			changes,
+			hashes: hash_files(journal.reverted_paths(), vfs),
+			journal,
+		}
+	}
+
+	/// Returns files that were modified since the transaction was applied
+	pub fn modified_files(&self, vfs: &Vfs) -> Vec<PathBuf> {
+		let current = hash_files(self.journal.reverted_paths(), vfs);
+
+		let mut paths = self
+			.hashes
+			.keys()
+			.chain(current.keys())
+			.filter(|path| self.hashes.get(*path).cloned().flatten() != current.get(*path).cloned().flatten())
+			.cloned()
+			.collect::<Vec<PathBuf>>();
+
+		paths.sort();
+		paths.dedup();
+
+		paths
+	}
+
+	pub fn summary(&self, id: u64) -> Summary {
+		let mut paths = self.journal.paths();
+		paths.sort();
+		paths.dedup();
+
+		Summary {
+			id,
+			timestamp: self.timestamp,
+			additions: self.changes.additions.len(),
+			updates: self.changes.updates.len(),
+			removals: self.changes.removals.len(),
+			paths,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+	pub id: u64,
+	pub timestamp: i64,
+	pub additions: usize,
+	pub updates: usize,
+	pub removals: usize,
+	pub paths: Vec<PathBuf>,
+}
+
+impl Summary {
+	/// Local time when the transaction was applied
+	pub fn time(&self) -> String {
+		DateTime::from_timestamp(self.timestamp, 0)
+			.map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+			.unwrap_or_default()
+	}
+
+	pub fn relative_to(mut self, dir: &Path) -> Self {
+		self.paths = self
+			.paths
+			.into_iter()
+			.map(|path| path.strip_prefix(dir).map(Path::to_path_buf).unwrap_or(path))
+			.collect();
+
+		self
+	}
+}
+
+/// History of a single project, stored in the Argon directory
+/// so it is neither watched nor committed with the project
+pub struct History {
+	dir: PathBuf,
+	workspace_dir: PathBuf,
+}
+
+impl History {
+	pub fn new(project_path: &Path) -> Result<Self> {
+		let hash = format!("{:x}", Sha256::digest(project_path.to_string().as_bytes()));
+
+		Ok(Self {
+			dir: util::get_argon_dir()?.join("history").join(&hash[..16]),
+			workspace_dir: project_path.get_parent().to_owned(),
+		})
+	}
+
+	/// Saves applied transaction and drops the oldest
+	/// ones that no longer fit within `history_size`
+	pub fn push(&self, changes: Changes, journal: Journal, vfs: &Vfs) -> Result<()> {
+		let size = Config::new().history_size;
+
+		if size == 0 || journal.is_empty() {
+			return Ok(());
+		}
+
+		let ids = self.ids()?;
+		let id = ids.last().map(|id| id + 1).unwrap_or(1);
+
+		let transaction = Transaction::new(changes, journal, vfs);
+
+		fs::create_dir_all(&self.dir)?;
+		fs::write(self.path(id), rmp_serde::to_vec_named(&transaction)?)?;
+
+		for id in ids.iter().take((ids.len() + 1).saturating_sub(size)) {
+			fs::remove_file(self.path(*id))?;
+		}
+
+		Ok(())
+	}
+
+	/// Returns summaries of all transactions, starting with the most recent one
+	pub fn list(&self) -> Result<Vec<Summary>> {
+		self.ids()?
+			.into_iter()
+			.rev()
+			.map(|id| Ok(self.load(id)?.summary(id)))
+			.collect()
+	}
+
+	pub fn is_empty(&self) -> Result<bool> {
+		Ok(self.ids()?.is_empty())
+	}
+
+	/// Reverts the given number of the most recent transactions
+	/// and removes them from the history, returns their summaries.
+	/// Stops at the first transaction whose files were modified
+	/// since it was applied, unless the user decides to revert it anyway.
+	/// Without `interactive` such transactions are refused right away
+	pub fn undo(&self, count: usize, vfs: &Vfs, interactive: bool) -> Result<Vec<Summary>> {
+		let mut reverted = Vec::new();
+
+		for id in self.ids()?.into_iter().rev().take(count) {
+			let transaction = self.load(id)?;
+			let summary = transaction.summary(id);
+			let modified = transaction.modified_files(vfs);
+
+			let resolution = if !modified.is_empty() {
+				let message = format!(
+					"Files changed by syncback applied at {} were modified since then:\n\n{}",
+					summary.time(),
+					conflict::format_paths(&modified, &self.workspace_dir)
+				);
+
+				if !interactive {
+					bail!("{message}\n\nUndo these changes manually or with `argon undo`");
+				}
+
+				argon_warn!("{message}");
+
+				Some(Resolution::prompt_with(
+					"Keep current disk version",
+					"Restore state before syncback",
+				))
+			} else {
+				None
+			};
+
+			match resolution {
+				Some(Resolution::KeepDisk) => break,
+				Some(Resolution::KeepBoth) => conflict::save_disk_versions(&modified, vfs)?,
+				_ => {}
+			}
+
+			vfs.revert(transaction.journal)
+				.with_desc(|| format!("Failed to undo changes applied at {}", summary.time()))?;
+
+			// Entry is kept when reverting fails so it can be retried,
+			// files that did get reverted will then show up as modified
+			fs::remove_file(self.path(id))?;
+
+			reverted.push(summary);
+		}
+
+		Ok(reverted)
+	}
+
+	/// Returns IDs of all stored transactions in ascending order
+	fn ids(&self) -> Result<Vec<u64>> {
+		if !self.dir.exists() {
+			return Ok(Vec::new());
+		}
+
+		let mut ids = fs::read_dir(&self.dir)?
+			.filter_map(|entry| entry.ok()?.path().get_stem().parse().ok())
+			.collect::<Vec<u64>>();
+
+		ids.sort();
+
+		Ok(ids)
+	}
+
+	fn load(&self, id: u64) -> Result<Transaction> {
+		let path = self.path(id);
+
+		rmp_serde::from_slice(&fs::read(&path)?)
+			.with_desc(|| format!("Failed to read syncback history entry: {}", path.to_string()))
+	}
+
+	fn path(&self, id: u64) -> PathBuf {
+		self.dir.join(format!("{id}.msgpack"))
+	}
+}
+
+/// Hashes files at the given paths and inside them if they are directories
+fn hash_files(paths: Vec<PathBuf>, vfs: &Vfs) -> BTreeMap<PathBuf, Option<String>> {
+	let mut hashes = BTreeMap::new();
+	let mut paths = paths;
+
+	while let Some(path) = paths.pop() {
+		if vfs.is_dir(&path) {
+			paths.extend(vfs.read_dir(&path).unwrap_or_default());
+		} else {
+			let hash = vfs.read(&path).ok().map(|contents| assets::hash(&contents));
+			hashes.insert(path, hash);
+		}
+	}
+
+	hashes
+}
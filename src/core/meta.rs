@@ -1,16 +1,21 @@
+use log::warn;
 use serde::{Deserialize, Serialize};
 use std::{
+	collections::HashMap,
 	fmt::Display,
+	hash::{DefaultHasher, Hash, Hasher},
 	path::{Path, PathBuf},
 };
 
 use crate::{
+	assets::SharedAssetLock,
 	config::Config,
-	constants::default_sync_rules,
+	constants::{asset_sync_rules, default_sync_rules, ASSET_LOCK},
 	ext::PathExt,
-	glob::Glob,
+	glob::{Glob, GlobSet},
 	middleware::Middleware,
 	project::{Project, ProjectNode},
+	vfs::Vfs,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -230,7 +235,7 @@ pub struct SyncRule {
 	pub pattern: Option<Glob>,
 	pub child_pattern: Option<Glob>,
 	#[serde(default)]
-	pub exclude: Vec<Glob>,
+	pub exclude: GlobSet,
 
 	pub suffix: Option<String>,
 }
@@ -241,7 +246,7 @@ impl SyncRule {
 			middleware,
 			pattern: None,
 			child_pattern: None,
-			exclude: Vec::new(),
+			exclude: GlobSet::default(),
 			suffix: None,
 		}
 	}
@@ -257,12 +262,12 @@ impl SyncRule {
 	}
 
 	pub fn with_exclude(mut self, exclude: &str) -> Self {
-		self.exclude = vec![Glob::new(exclude).unwrap()];
+		self.exclude = GlobSet::new(vec![Glob::new(exclude).unwrap()]);
 		self
 	}
 
 	pub fn with_excludes(mut self, excludes: &[&str]) -> Self {
-		self.exclude = excludes.iter().map(|exclude| Glob::new(exclude).unwrap()).collect();
+		self.exclude = GlobSet::new(excludes.iter().map(|exclude| Glob::new(exclude).unwrap()).collect());
 		self
 	}
 
@@ -272,7 +277,7 @@ impl SyncRule {
 	}
 
 	pub fn is_excluded(&self, path: &Path) -> bool {
-		self.exclude.iter().any(|exclude| exclude.matches_path(path))
+		self.exclude.matches_path(path)
 	}
 
 	pub fn get_name(&self, path: &Path) -> String {
@@ -340,33 +345,57 @@ impl SyncRule {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct IgnoreRule {
-	pattern: Glob,
+	globs: GlobSet,
 	path: PathBuf,
 }
 
 impl IgnoreRule {
-	pub fn matches(&self, path: &Path) -> bool {
-		match path.strip_prefix(&self.path) {
-			Ok(suffix) => self.pattern.matches_path(suffix),
-			Err(_) => false,
-		}
+	/// Returns whether the path is ignored by this rule,
+	/// `None` if none of the rule globs matches it
+	pub fn evaluate(&self, path: &Path, is_dir: bool) -> Option<bool> {
+		let suffix = path.strip_prefix(&self.path).ok()?;
+		self.globs.evaluate(suffix, is_dir)
 	}
 
 	pub fn matches_with_dir(&self, path: &Path) -> bool {
 		match path.strip_prefix(&self.path) {
-			Ok(suffix) => self.pattern.matches_path_with_dir(suffix),
+			Ok(suffix) => self.globs.matches_path_with_dir(suffix),
 			Err(_) => false,
 		}
 	}
 
-	pub fn from_globs(globs: Vec<Glob>, path: PathBuf) -> Vec<Self> {
-		globs
-			.into_iter()
-			.map(|glob| IgnoreRule {
-				pattern: glob,
-				path: path.clone(),
+	pub fn from_globs(globs: Vec<Glob>, path: PathBuf) -> Self {
+		Self {
+			globs: GlobSet::new(globs),
+			path,
+		}
+	}
+
+	/// Parses gitignore-style `.argonignore` file located in the given directory
+	pub fn from_ignore_file(contents: &str, path: &Path) -> Self {
+		let globs = contents
+			.lines()
+			.filter_map(|line| {
+				let line = line.trim_end();
+
+				if line.is_empty() || line.starts_with('#') {
+					return None;
+				}
+
+				// Escaped `#` at the start of a pattern is not a comment
+				let line = if line.starts_with("\\#") { &line[1..] } else { line };
+
+				match Glob::gitignore(line) {
+					Ok(glob) => Some(glob),
+					Err(err) => {
+						warn!("Invalid pattern {} in {}: {}", line, path.display(), err);
+						None
+					}
+				}
 			})
-			.collect()
+			.collect();
+
+		Self::from_globs(globs, path.to_owned())
 	}
 }
 
@@ -404,8 +433,12 @@ pub struct Context {
 	ignore_rules: Vec<IgnoreRule>,
 	/// Filter which ignores specific instances and properties
 	syncback_filter: SyncbackFilter,
+	/// Model middleware used to write new instances of specific classes
+	model_classes: HashMap<String, Middleware>,
 	/// Whether to use legacy script context
 	legacy_scripts: bool,
+	/// Asset lock file of the project, present only if assets are synced
+	asset_lock: Option<SharedAssetLock>,
 }
 
 impl Context {
@@ -414,7 +447,9 @@ impl Context {
 			sync_rules: Vec::new(),
 			ignore_rules: Vec::new(),
 			syncback_filter: SyncbackFilter::default(),
+			model_classes: HashMap::new(),
 			legacy_scripts: true,
+			asset_lock: None,
 		}
 	}
 
@@ -457,13 +492,47 @@ impl Context {
 		&self.ignore_rules
 	}
 
+	/// Extends ignore rules with the ones that apply to the nested directory
+	pub fn add_ignore_rule(&mut self, rule: IgnoreRule) {
+		self.ignore_rules.push(rule)
+	}
+
+	/// Returns whether the given path is ignored,
+	/// the last matching rule decides so negated rules can include paths back
+	pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+		self.ignore_rules
+			.iter()
+			.rev()
+			.find_map(|rule| rule.evaluate(path, is_dir))
+			.unwrap_or_default()
+	}
+
 	pub fn syncback_filter(&self) -> &SyncbackFilter {
 		&self.syncback_filter
 	}
 
+	/// Returns model middleware that new instances of the given class
+	/// should be written with, `*` matches every class except `Folder`
+	pub fn model_middleware(&self, class: &str) -> Option<Middleware> {
+		self.model_classes
+			.get(class)
+			.or_else(|| {
+				if class != "Folder" {
+					self.model_classes.get("*")
+				} else {
+					None
+				}
+			})
+			.cloned()
+	}
+
 	pub fn use_legacy_scripts(&self) -> bool {
 		self.legacy_scripts
 	}
+
+	pub fn asset_lock(&self) -> Option<&SharedAssetLock> {
+		self.asset_lock.as_ref()
+	}
 }
 
 impl Default for Context {
@@ -472,7 +541,7 @@ impl Default for Context {
 	}
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Meta {
 	/// Instance source that is guaranteed to exist
@@ -488,6 +557,22 @@ pub struct Meta {
 	pub original_name: Option<String>,
 	/// Custom Mesh Part source path
 	pub mesh_source: Option<String>,
+	/// Content hashes of source and data files from the last sync,
+	/// used to detect files that were modified outside of Argon
+	#[serde(skip)]
+	pub hashes: HashMap<PathBuf, u64>,
+}
+
+// Hashes only track file contents and are not part of instance metadata,
+// so they are skipped, otherwise any file edit would count as a meta change
+impl PartialEq for Meta {
+	fn eq(&self, other: &Self) -> bool {
+		self.source == other.source
+			&& self.context == other.context
+			&& self.keep_unknowns == other.keep_unknowns
+			&& self.original_name == other.original_name
+			&& self.mesh_source == other.mesh_source
+	}
 }
 
 impl Meta {
@@ -500,13 +585,17 @@ impl Meta {
 			keep_unknowns: false,
 			original_name: None,
 			mesh_source: None,
+			hashes: HashMap::new(),
 		}
 	}
 
 	pub fn from_project(project: &Project) -> Self {
 		let syncback_filter = if let Some(syncback) = &project.syncback {
 			SyncbackFilter {
-				ignore_rules: IgnoreRule::from_globs(syncback.ignore_globs.clone(), project.workspace_dir.clone()),
+				ignore_rules: vec![IgnoreRule::from_globs(
+					syncback.ignore_globs.clone(),
+					project.workspace_dir.clone(),
+				)],
 				ignore_names: syncback.ignore_names.clone(),
 				ignore_classes: syncback.ignore_classes.clone(),
 				ignore_properties: syncback.ignore_properties.clone(),
@@ -515,11 +604,38 @@ impl Meta {
 			SyncbackFilter::default()
 		};
 
+		let model_classes = if let Some(syncback) = &project.syncback {
+			syncback.model_classes.clone()
+		} else {
+			HashMap::new()
+		};
+
+		let mut sync_rules = project.sync_rules.clone();
+
+		if project.sync_assets.unwrap_or_default() {
+			if sync_rules.is_empty() {
+				sync_rules = default_sync_rules().clone();
+			}
+
+			sync_rules.extend(asset_sync_rules());
+		}
+
+		// Lock file is only needed when some files are synced as assets
+		let asset_lock = sync_rules
+			.iter()
+			.any(|rule| rule.middleware == Middleware::Asset)
+			.then(|| SharedAssetLock::new(&project.workspace_dir.join(ASSET_LOCK)));
+
 		let context = Context {
-			sync_rules: project.sync_rules.clone(),
-			ignore_rules: IgnoreRule::from_globs(project.ignore_globs.clone(), project.workspace_dir.clone()),
+			sync_rules,
+			ignore_rules: vec![IgnoreRule::from_globs(
+				project.ignore_globs.clone(),
+				project.workspace_dir.clone(),
+			)],
 			syncback_filter,
+			model_classes,
 			legacy_scripts: project.legacy_scripts.unwrap_or(true),
+			asset_lock,
 		};
 
 		Self {
@@ -574,4 +690,41 @@ impl Meta {
 	pub fn set_mesh_source(&mut self, mesh_source: Option<String>) {
 		self.mesh_source = mesh_source;
 	}
+
+	// Tracking file contents
+
+	/// Hashes current contents of all source and data files,
+	/// does nothing if the VFS does not track hashes
+	pub fn update_hashes(&mut self, vfs: &Vfs) {
+		if !vfs.tracks_hashes() {
+			return;
+		}
+
+		self.hashes = self
+			.source
+			.relevant()
+			.iter()
+			.filter(|entry| matches!(entry, SourceEntry::File(_) | SourceEntry::Data(_)))
+			.filter_map(|entry| {
+				let contents = vfs.read(entry.path()).ok()?;
+				Some((entry.path().to_owned(), hash(&contents)))
+			})
+			.collect();
+	}
+
+	/// Returns source and data files whose contents
+	/// changed (or that were removed) since the last sync
+	pub fn modified_files(&self, vfs: &Vfs) -> Vec<PathBuf> {
+		self.hashes
+			.iter()
+			.filter(|(path, last)| !vfs.read(path).is_ok_and(|contents| hash(&contents) == **last))
+			.map(|(path, _)| path.to_owned())
+			.collect()
+	}
+}
+
+fn hash(contents: &[u8]) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	contents.hash(&mut hasher);
+	hasher.finish()
 }
@@ -4,22 +4,29 @@ use rbx_dom_weak::{types::Ref, Ustr};
 use serde::Serialize;
 use snapshot::AddedSnapshot;
 use std::{
-	fs::File,
-	io::BufWriter,
+	fs::{self, File},
+	io::{BufWriter, Write},
 	path::{Path, PathBuf},
 	sync::{Arc, Mutex, MutexGuard},
 };
 
 use self::{
+	changes::Changes,
+	helpers::diff,
+	history::{History, Summary},
 	meta::{Meta, SourceEntry},
-	processor::Processor,
+	processor::{write::Preview, Processor},
 	queue::Queue,
 	tree::Tree,
 };
-use crate::{core::snapshot::Snapshot, lock, middleware::new_snapshot, project::Project, stats, util, vfs::Vfs};
+use crate::{
+	core::snapshot::Snapshot, ext::PathExt, lock, middleware::new_snapshot, project::Project, server, stats, util,
+	vfs::Vfs,
+};
 
 pub mod changes;
 pub mod helpers;
+pub mod history;
 pub mod meta;
 pub mod processor;
 pub mod queue;
@@ -31,17 +38,24 @@ pub struct Core {
 	tree: Arc<Mutex<Tree>>,
 	queue: Arc<Queue>,
 	processor: Arc<Processor>,
-	_vfs: Arc<Vfs>,
+	history: Arc<History>,
+	vfs: Arc<Vfs>,
 }
 
 impl Core {
 	#[profiling::function]
-	pub fn new(project: Project, watch: bool) -> Result<Self> {
+	/// Hashes of synced files are only tracked with `two_way`,
+	/// as they are needed to detect conflicts of client changes
+	pub fn new(project: Project, watch: bool, two_way: bool) -> Result<Self> {
 		profiling::start_frame!();
 
 		trace!("Initializing VFS");
 
-		let vfs = Vfs::new(watch);
+		let vfs = if two_way {
+			Vfs::new(watch).with_hash_tracking()
+		} else {
+			Vfs::new(watch)
+		};
 
 		trace!("Snapshotting root project");
 
@@ -58,6 +72,7 @@ impl Core {
 		let vfs = Arc::new(vfs);
 		let tree = Arc::new(Mutex::new(Tree::new(snapshot)));
 		let queue = Arc::new(Queue::new());
+		let history = Arc::new(History::new(&project.path)?);
 
 		trace!("Starting Processor");
 
@@ -67,6 +82,7 @@ impl Core {
 			tree.clone(),
 			vfs.clone(),
 			project.clone(),
+			history.clone(),
 		));
 
 		trace!("Core initialized successfully!");
@@ -76,7 +92,8 @@ impl Core {
 			tree,
 			queue,
 			processor,
-			_vfs: vfs,
+			history,
+			vfs,
 		})
 	}
 
@@ -154,7 +171,27 @@ impl Core {
 
 	/// Build the tree into a file, either XML or binary
 	pub fn build(&self, path: &Path, xml: bool) -> Result<()> {
-		let writer = BufWriter::new(File::create(path)?);
+		// Write to a temporary file first so other programs
+		// never read partially written place or model
+		let temp_path = path.with_file_name(format!(".{}.tmp", path.get_name()));
+
+		let result = self
+			.write_build(&temp_path, xml)
+			.and_then(|()| Ok(fs::rename(&temp_path, path)?));
+
+		if result.is_err() {
+			fs::remove_file(&temp_path).ok();
+		}
+
+		result?;
+
+		stats::projects_built(1);
+
+		Ok(())
+	}
+
+	fn write_build(&self, path: &Path, xml: bool) -> Result<()> {
+		let mut writer = BufWriter::new(File::create(path)?);
 		let tree = lock!(&self.tree);
 
 		let root_refs = if self.project().is_place() {
@@ -164,16 +201,142 @@ impl Core {
 		};
 
 		if xml {
-			rbx_xml::to_writer_default(writer, tree.inner(), &root_refs)?;
+			rbx_xml::to_writer_default(&mut writer, tree.inner(), &root_refs)?;
 		} else {
-			rbx_binary::to_writer(writer, tree.inner(), &root_refs)?;
+			rbx_binary::to_writer(&mut writer, tree.inner(), &root_refs)?;
 		}
 
-		stats::projects_built(1);
+		writer.flush()?;
 
 		Ok(())
 	}
 
+	/// Whether the given changes modify anything that ends up
+	/// in the built file, meta-only updates and updates of the
+	/// place's root (which is not serialized) are irrelevant
+	pub fn affects_build(&self, changes: &Changes) -> bool {
+		if !changes.additions.is_empty() || !changes.removals.is_empty() {
+			return true;
+		}
+
+		let root_ref = lock!(&self.tree).root_ref();
+		let is_place = self.project().is_place();
+
+		changes.updates.iter().any(|update| {
+			let modified = update.name.is_some() || update.class.is_some() || update.properties.is_some();
+
+			modified && !(is_place && update.id == root_ref)
+		})
+	}
+
+	/// Filesystem changes that applying client changes would make,
+	/// with plan paths relative to the workspace directory
+	pub fn preview(&self, changes: Changes) -> Result<Preview> {
+		let mut preview = processor::write::preview_changes(changes, &self.tree())?;
+		preview.plan = preview.plan.relative_to(&self.project().workspace_dir);
+
+		Ok(preview)
+	}
+
+	/// Changes that make the tree match the given place or model file
+	pub fn diff(&self, path: &Path) -> Result<Changes> {
+		let contents = fs::read(path)?;
+
+		let dom = match path.get_ext() {
+			"rbxl" | "rbxm" => rbx_binary::from_reader(contents.as_slice())?,
+			"rbxlx" | "rbxmx" => rbx_xml::from_reader_default(contents.as_slice())?,
+			ext => bail!("Unsupported file extension: {}", ext),
+		};
+
+		let tree = self.tree();
+
+		if self.project().is_place() {
+			Ok(diff::diff(&dom, dom.root_ref(), &tree, true))
+		} else {
+			match dom.root().children() {
+				[root] => Ok(diff::diff(&dom, *root, &tree, false)),
+				_ => bail!("Model file must contain exactly one root instance"),
+			}
+		}
+	}
+
+	/// Writes the given changes to the disk as a single transaction
+	/// and saves them to the syncback history so they can be undone
+	pub fn apply(&self, changes: Changes) -> Result<()> {
+		let mut tree = self.tree();
+
+		let mut paths = processor::read::source_paths(&changes, &tree);
+		self.vfs.begin();
+
+		if let Err(err) = processor::write::apply_changes(changes.clone(), &mut tree, &self.vfs) {
+			paths.extend(self.vfs.pending_paths());
+			self.vfs.rollback()?;
+
+			let changes = processor::read::reload_paths(&paths, &mut tree, &self.vfs);
+
+			if !changes.is_empty() {
+				self.queue.push(server::SyncChanges(changes), None).ok();
+			}
+
+			return Err(err);
+		}
+
+		let journal = self.vfs.commit();
+		tree.resolve_refs();
+
+		self.history.push(changes, journal, &self.vfs)
+	}
+
+	/// Writes previewed changes to the disk as a single transaction without
+	/// applying them again, the tree must not change since the preview
+	pub fn apply_preview(&self, changes: Changes, preview: Preview) -> Result<()> {
+		let mut tree = self.tree();
+
+		self.vfs.begin();
+
+		if let Err(err) = self.vfs.replay(&preview.operations) {
+			self.vfs.rollback()?;
+			return Err(err.into());
+		}
+
+		let journal = self.vfs.commit();
+
+		*tree = preview.tree;
+		tree.resolve_refs();
+
+		self.history.push(changes, journal, &self.vfs)
+	}
+
+	/// Syncback changes that can be undone, starting with the
+	/// most recent one, with paths relative to the workspace directory
+	pub fn history(&self) -> Result<Vec<Summary>> {
+		let workspace_dir = &self.project().workspace_dir;
+
+		Ok(self
+			.history
+			.list()?
+			.into_iter()
+			.map(|summary| summary.relative_to(workspace_dir))
+			.collect())
+	}
+
+	/// Reverts the given number of the most recent syncback changes,
+	/// reverted files are then picked up by the processor like any other.
+	/// Changes whose files were modified since are refused, as there is
+	/// nobody to ask how to resolve them
+	pub fn undo(&self, count: usize) -> Result<Vec<Summary>> {
+		// Holding the tree makes sure no client changes are applied meanwhile
+		let _tree = self.tree();
+		let workspace_dir = &self.project().workspace_dir;
+
+		Ok(self
+			.history
+			.undo(count, &self.vfs, false)?
+			.into_iter()
+			.map(|summary| summary.relative_to(workspace_dir))
+			.collect())
+	}
+
 	/// Write sourcemap of the tree
 	pub fn sourcemap(&self, path: Option<PathBuf>, non_scripts: bool) -> Result<()> {
 		let tree = lock!(&self.tree);
@@ -0,0 +1,219 @@
+use anyhow::Result;
+use colored::Colorize;
+use rbx_dom_weak::types::Ref;
+use std::path::{Path, PathBuf};
+
+use super::read;
+use crate::{
+	core::{
+		changes::Changes,
+		helpers::syncback::{locate_model, snapshot_from_tree},
+		meta::SourceKind,
+		snapshot::UpdatedSnapshot,
+		tree::Tree,
+	},
+	ext::PathExt,
+	logger,
+	vfs::Vfs,
+};
+
+/// Instance whose source files were modified outside of Argon
+/// since the last sync and that is also targeted by client changes
+#[derive(Debug, Clone)]
+pub struct Conflict {
+	pub id: Ref,
+	pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+	/// Drop conflicting changes and sync disk version back to the client
+	KeepDisk,
+	/// Overwrite files with the client version
+	KeepStudio,
+	/// Overwrite files with the client version but save
+	/// the disk version next to them as `.conflict` files
+	KeepBoth,
+}
+
+impl Resolution {
+	/// Asks how to resolve conflicts with the incoming
+	/// version, e.g. `Studio` when applying client changes
+	pub fn prompt(incoming: &str) -> Self {
+		Self::prompt_with("Keep disk version", &format!("Keep {incoming} version"))
+	}
+
+	/// Asks how to resolve conflicts, with custom labels
+	/// for keeping the disk and the incoming version
+	pub fn prompt_with(keep_disk: &str, keep_incoming: &str) -> Self {
+		let items = [
+			keep_disk,
+			keep_incoming,
+			"Keep both (disk version is saved as .conflict file)",
+		];
+
+		match logger::select("How do you want to resolve these conflicts?", &items, 2) {
+			0 => Resolution::KeepDisk,
+			1 => Resolution::KeepStudio,
+			_ => Resolution::KeepBoth,
+		}
+	}
+}
+
+/// Returns conflicts between the given changes and files modified on disk
+pub fn find_conflicts(changes: &Changes, tree: &Tree, vfs: &Vfs) -> Vec<Conflict> {
+	let mut owners = Vec::new();
+
+	let ids = changes
+		.updates
+		.iter()
+		.map(|snapshot| snapshot.id)
+		.chain(changes.removals.iter().copied())
+		.filter_map(|id| locate_owner(id, tree))
+		.chain(
+			changes
+				.additions
+				.iter()
+				.filter_map(|snapshot| locate_model(snapshot.parent, tree)),
+		);
+
+	for id in ids {
+		if !owners.contains(&id) {
+			owners.push(id);
+		}
+	}
+
+	owners
+		.into_iter()
+		.filter_map(|id| {
+			let mut paths = tree.get_meta(id)?.modified_files(vfs);
+			paths.sort();
+
+			if paths.is_empty() {
+				None
+			} else {
+				Some(Conflict { id, paths })
+			}
+		})
+		.collect()
+}
+
+/// Splits changes into ones that can be applied
+/// and ones that are affected by the given conflicts
+pub fn split_changes(changes: Changes, conflicts: &[Conflict], tree: &Tree) -> (Changes, Changes) {
+	let is_conflicting = |id: Option<Ref>| id.is_some_and(|id| conflicts.iter().any(|conflict| conflict.id == id));
+
+	let mut kept = Changes::new();
+	let mut dropped = Changes::new();
+
+	for snapshot in changes.additions {
+		if is_conflicting(locate_model(snapshot.parent, tree)) {
+			dropped.additions.push(snapshot);
+		} else {
+			kept.additions.push(snapshot);
+		}
+	}
+
+	for snapshot in changes.updates {
+		if is_conflicting(locate_owner(snapshot.id, tree)) {
+			dropped.updates.push(snapshot);
+		} else {
+			kept.updates.push(snapshot);
+		}
+	}
+
+	for id in changes.removals {
+		if is_conflicting(locate_owner(id, tree)) {
+			dropped.removals.push(id);
+		} else {
+			kept.removals.push(id);
+		}
+	}
+
+	(kept, dropped)
+}
+
+/// Re-reads conflicting instances from disk and returns changes
+/// that bring the client back in sync with the tree, undoing
+/// the dropped changes that were already made by the client
+pub fn revert_changes(dropped: Changes, conflicts: &[Conflict], tree: &mut Tree, vfs: &Vfs) -> Changes {
+	let mut changes = Changes::new();
+
+	for conflict in conflicts {
+		if let Some(processed) = read::process_changes(conflict.id, tree, vfs) {
+			changes.extend(processed);
+		}
+	}
+
+	let mut reverted = Changes::new();
+
+	for snapshot in dropped.additions {
+		reverted.remove(snapshot.id);
+	}
+
+	for snapshot in dropped.updates {
+		if let Some(instance) = tree.get_instance(snapshot.id) {
+			let mut updated_snapshot = UpdatedSnapshot::new(snapshot.id);
+
+			updated_snapshot.name = Some(instance.name.clone());
+			updated_snapshot.class = Some(instance.class);
+			updated_snapshot.properties = Some(instance.properties.clone());
+
+			reverted.update(updated_snapshot);
+		}
+	}
+
+	for id in dropped.removals {
+		if let Some(snapshot) = snapshot_from_tree(id, tree) {
+			reverted.add(snapshot, tree.get_instance(id).unwrap().parent());
+		}
+	}
+
+	changes.merge(reverted);
+	changes.extend(read::process_refs(tree));
+
+	changes
+}
+
+/// Lists conflicting paths relative to the workspace directory
+pub fn format_paths<'a>(paths: impl IntoIterator<Item = &'a PathBuf>, workspace_dir: &Path) -> String {
+	paths
+		.into_iter()
+		.map(|path| {
+			format!(
+				"  {}",
+				path.strip_prefix(workspace_dir).unwrap_or(path).to_string().bold()
+			)
+		})
+		.collect::<Vec<String>>()
+		.join("\n")
+}
+
+/// Saves current contents of conflicting files as `.conflict` files
+pub fn save_disk_versions<'a>(paths: impl IntoIterator<Item = &'a PathBuf>, vfs: &Vfs) -> Result<()> {
+	for path in paths {
+		if vfs.is_file(path) {
+			vfs.write(&conflict_path(path), &vfs.read(path)?)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn conflict_path(path: &Path) -> PathBuf {
+	path.with_file_name(format!("{}.conflict", path.get_name()))
+}
+
+/// Returns the closest instance (including the given one) that has its own source
+fn locate_owner(id: Ref, tree: &Tree) -> Option<Ref> {
+	let mut current = id;
+
+	loop {
+		if let SourceKind::None = tree.get_meta(current)?.source.get() {
+			current = tree.get_instance(current)?.parent();
+			continue;
+		}
+
+		return Some(current);
+	}
+}
@@ -8,17 +8,20 @@ use std::{
 	thread::Builder,
 };
 
-use super::{changes::Changes, queue::Queue, tree::Tree};
+use self::conflict::Resolution;
+use super::{changes::Changes, helpers::syncback::get_file_middleware, history::History, queue::Queue, tree::Tree};
 use crate::{
-	argon_error,
+	argon_error, argon_info, argon_warn,
 	config::Config,
 	constants::BLACKLISTED_PATHS,
 	lock, logger,
+	middleware::Middleware,
 	project::{Project, ProjectDetails},
 	server, stats,
 	vfs::{Vfs, VfsEvent},
 };
 
+pub mod conflict;
 pub mod read;
 pub mod write;
 
@@ -34,12 +37,19 @@ pub struct Processor {
 }
 
 impl Processor {
-	pub fn new(queue: Arc<Queue>, tree: Arc<Mutex<Tree>>, vfs: Arc<Vfs>, project: Arc<Mutex<Project>>) -> Self {
+	pub fn new(
+		queue: Arc<Queue>,
+		tree: Arc<Mutex<Tree>>,
+		vfs: Arc<Vfs>,
+		project: Arc<Mutex<Project>>,
+		history: Arc<History>,
+	) -> Self {
 		let handler = Arc::new(Handler {
 			queue,
 			tree,
 			vfs: vfs.clone(),
 			project,
+			history,
 		});
 
 		let handler = handler.clone();
@@ -79,6 +89,7 @@ struct Handler {
 	tree: Arc<Mutex<Tree>>,
 	vfs: Arc<Vfs>,
 	project: Arc<Mutex<Project>>,
+	history: Arc<History>,
 }
 
 impl Handler {
@@ -97,20 +108,27 @@ impl Handler {
 				return;
 			}
 
-			let ids = {
-				let mut current_path = path;
-
-				loop {
-					if let Some(ids) = tree.get_ids(current_path) {
-						break ids.to_owned();
-					}
-
-					match current_path.parent() {
-						Some(parent) => current_path = parent,
-						None => {
-							trace!("No ID found for path {path:?}");
-							return;
-						}
+			let asset_lock = tree
+				.get_meta(tree.root_ref())
+				.and_then(|meta| meta.context.asset_lock())
+				.filter(|lock| lock.path() == path)
+				.cloned();
+
+			// Content IDs of all assets might have changed
+			let ids = if let Some(asset_lock) = asset_lock {
+				asset_lock.invalidate();
+
+				tree.meta_map()
+					.iter()
+					.filter(|(_, meta)| get_file_middleware(meta) == Some(Middleware::Asset))
+					.map(|(id, _)| *id)
+					.collect()
+			} else {
+				match read::get_ids(path, &tree) {
+					Some(ids) => ids,
+					None => {
+						trace!("No ID found for path {path:?}");
+						return;
 					}
 				}
 			};
@@ -123,6 +141,8 @@ impl Handler {
 				}
 			}
 
+			changes.extend(read::process_refs(&mut tree));
+
 			changes
 		};
 
@@ -176,23 +196,26 @@ impl Handler {
 	fn on_client_event(&self, request: WriteRequest) {
 		profiling::start_frame!();
 
-		let changes = request.changes;
+		let mut changes = request.changes;
 		let client_id = request.client_id;
 
 		trace!("Received client event: {:?} changes", changes.total());
 
 		if changes.total() > Config::new().changes_threshold {
-			let accept = logger::prompt(
-				&format!(
-					"You are about to apply {}, {} and {}. Do you want to continue?",
-					format!("{} additions", changes.additions.len()).bold().green(),
-					format!("{} updates", changes.updates.len()).bold().blue(),
-					format!("{} removals", changes.removals.len()).bold().red(),
-				),
-				true,
-			);
+			let workspace_dir = lock!(self.project).workspace_dir.clone();
+
+			match write::preview_changes(changes.clone(), &lock!(self.tree)) {
+				Ok(preview) if !preview.plan.is_empty() => {
+					argon_info!(
+						"Planned filesystem changes:\n\n{}",
+						preview.plan.relative_to(&workspace_dir)
+					)
+				}
+				Ok(_) => {}
+				Err(err) => warn!("Failed to preview changes: {err}"),
+			}
 
-			if !accept {
+			if !prompt_changes(&changes) {
 				trace!(
 					"Aborted applying client event! {} changes were not applied",
 					changes.total()
@@ -207,29 +230,117 @@ impl Handler {
 			}
 		}
 
+		let conflicts = conflict::find_conflicts(&changes, &lock!(self.tree), &self.vfs);
+
+		let resolution = if !conflicts.is_empty() {
+			let workspace_dir = lock!(self.project).workspace_dir.clone();
+
+			argon_warn!(
+				"Changes made in Studio conflict with files that were modified outside of Argon since the last sync:\n\n{}",
+				conflict::format_paths(conflicts.iter().flat_map(|conflict| &conflict.paths), &workspace_dir)
+			);
+
+			Some(Resolution::prompt("Studio"))
+		} else {
+			None
+		};
+
 		let mut tree = lock!(self.tree);
 
-		let result = || -> Result<()> {
-			for snapshot in changes.additions {
-				write::apply_addition(snapshot, &mut tree, &self.vfs)?;
+		let dropped = if resolution == Some(Resolution::KeepDisk) {
+			let (kept, dropped) = conflict::split_changes(changes, &conflicts, &tree);
+			changes = kept;
+
+			Some(dropped)
+		} else {
+			None
+		};
+
+		// Both filesystem and tree are reverted if any change fails
+		// so they never end up in a partially applied state
+		let source_paths = read::source_paths(&changes, &tree);
+		let applied = changes.clone();
+		self.vfs.begin();
+
+		let result = match resolution {
+			Some(Resolution::KeepBoth) => {
+				conflict::save_disk_versions(conflicts.iter().flat_map(|conflict| &conflict.paths), &self.vfs)
 			}
+			_ => Ok(()),
+		}
+		.and_then(|_| write::apply_changes(changes, &mut tree, &self.vfs));
 
-			for snapshot in changes.updates {
-				write::apply_update(snapshot, &mut tree, &self.vfs)?;
+		match result {
+			Ok(()) => {
+				let journal = self.vfs.commit();
+				tree.resolve_refs();
+				tree.update_hashes(&journal.written_paths(), &self.vfs);
+
+				if let Err(err) = self.history.push(applied, journal, &self.vfs) {
+					warn!("Failed to save changes to the syncback history: {err}");
+				}
+
+				trace!("Changes applied successfully");
 			}
+			Err(err) => {
+				let mut paths = self.vfs.pending_paths();
+				paths.extend(source_paths);
+
+				if let Err(err) = self.vfs.rollback() {
+					error!("Failed to revert applied changes: {err}");
+				}
 
-			for id in changes.removals {
-				write::apply_removal(id, &mut tree, &self.vfs)?;
+				let changes = read::reload_paths(&paths, &mut tree, &self.vfs);
+
+				// Instances restored from the disk get new IDs that other clients need
+				if !changes.is_empty() {
+					self.queue.push(server::SyncChanges(changes), None).ok();
+				}
+
+				argon_error!("Failed to apply changes: {}! All changes were reverted", err);
+
+				match self.queue.disconnect(
+					&format!("Failed to apply changes: {err}. All changes were reverted!"),
+					client_id,
+				) {
+					Ok(()) => trace!("Client {client_id} disconnected"),
+					Err(err) => warn!("Failed to disconnect client: {err}"),
+				}
+
+				return;
 			}
+		}
 
-			Ok(())
-		}();
+		if let Some(dropped) = dropped {
+			let changes = conflict::revert_changes(dropped, &conflicts, &mut tree, &self.vfs);
 
-		match result {
-			Ok(()) => trace!("Changes applied successfully"),
-			Err(err) => error!("Failed to apply changes: {err}"),
+			if !changes.is_empty() {
+				match self.queue.push(server::SyncChanges(changes), None) {
+					Ok(()) => trace!("Disk version of conflicting files synced"),
+					Err(err) => warn!("Failed to sync disk version of conflicting files: {err}"),
+				}
+			}
 		}
 
 		self.queue.push(server::SyncbackChanges(), Some(0)).ok();
 	}
 }
+
+/// Asks the user to confirm applying the given changes, only
+/// if there are more of them than `changes_threshold` allows
+pub fn confirm_changes(changes: &Changes) -> bool {
+	changes.total() <= Config::new().changes_threshold || prompt_changes(changes)
+}
+
+/// Asks the user to confirm applying the given changes
+pub fn prompt_changes(changes: &Changes) -> bool {
+	logger::prompt(
+		&format!(
+			"You are about to apply {}, {} and {}. Do you want to continue?",
+			format!("{} additions", changes.additions.len()).bold().green(),
+			format!("{} updates", changes.updates.len()).bold().blue(),
+			format!("{} removals", changes.removals.len()).bold().red(),
+		),
+		true,
+	)
+}
@@ -1,4 +1,7 @@
-use std::path::Path;
+use std::{
+	mem,
+	path::{Path, PathBuf},
+};
 
 use log::{error, trace};
 use rbx_dom_weak::types::Ref;
@@ -65,6 +68,63 @@ pub fn process_changes(id: Ref, tree: &mut Tree, vfs: &Vfs) -> Option<Changes> {
 	Some(changes)
 }
 
+/// Returns IDs of instances synced from the given path
+/// or from its closest parent that is synced
+pub fn get_ids(path: &Path, tree: &Tree) -> Option<Vec<Ref>> {
+	path.ancestors().find_map(|path| tree.get_ids(path)).cloned()
+}
+
+/// Returns source paths of all instances the given changes touch,
+/// for additions these are the paths of their parents
+pub fn source_paths(changes: &Changes, tree: &Tree) -> Vec<PathBuf> {
+	changes
+		.additions
+		.iter()
+		.map(|addition| addition.parent)
+		.chain(changes.updates.iter().map(|update| update.id))
+		.chain(changes.removals.iter().copied())
+		.filter_map(|id| tree.get_meta(id)?.source.get().path().map(Path::to_path_buf))
+		.collect()
+}
+
+/// Snapshots the given paths again, so the tree matches the disk after
+/// changes that were partially applied to both of them got reverted
+pub fn reload_paths(paths: &[PathBuf], tree: &mut Tree, vfs: &Vfs) -> Changes {
+	let mut ids = Vec::new();
+
+	for id in paths.iter().filter_map(|path| get_ids(path, tree)).flatten() {
+		if !ids.contains(&id) {
+			ids.push(id);
+		}
+	}
+
+	let mut changes = Changes::new();
+
+	for id in ids {
+		if let Some(processed) = process_changes(id, tree, vfs) {
+			changes.extend(processed);
+		}
+	}
+
+	changes.extend(process_refs(tree));
+
+	changes
+}
+
+/// Resolves pending ref properties whose targets were added in the meantime
+pub fn process_refs(tree: &mut Tree) -> Changes {
+	let mut changes = Changes::new();
+
+	for id in tree.resolve_refs() {
+		let mut updated_snapshot = UpdatedSnapshot::new(id);
+		updated_snapshot.properties = Some(tree.get_instance(id).unwrap().properties.clone());
+
+		changes.update(updated_snapshot);
+	}
+
+	changes
+}
+
 fn process_child_changes(id: Ref, mut snapshot: Snapshot, changes: &mut Changes, tree: &mut Tree) {
 	// Process instance changes
 	let mut updated_snapshot = UpdatedSnapshot::new(id);
@@ -73,9 +133,16 @@ fn process_child_changes(id: Ref, mut snapshot: Snapshot, changes: &mut Changes,
 		tree.update_meta(id, snapshot.meta.clone());
 		Some(snapshot.meta)
 	} else {
+		// Hashes are not part of the comparison but must follow the disk
+		if let Some(meta) = tree.get_meta_mut(id) {
+			meta.hashes = mem::take(&mut snapshot.meta.hashes);
+		}
+
 		None
 	};
 
+	tree.update_refs(id, &mut snapshot.properties);
+
 	let instance = tree.get_instance_mut(id).unwrap();
 
 	updated_snapshot.name = if snapshot.name != instance.name {
@@ -160,6 +227,9 @@ fn insert_children(snapshot: &mut Snapshot, parent: Ref, tree: &mut Tree) {
 
 	snapshot.set_id(id);
 
+	// Ref properties are sent once resolved, see `process_refs`
+	snapshot.properties = tree.get_instance(id).unwrap().properties.clone();
+
 	for child in snapshot.children.iter_mut() {
 		insert_children(child, id, tree);
 	}
@@ -1,13 +1,20 @@
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 use log::{error, trace, warn};
 use path_clean::PathClean;
-use rbx_dom_weak::{types::Ref, HashMapExt, Instance, Ustr, UstrMap};
-use std::path::{Path, PathBuf};
+use rbx_dom_weak::{types::Ref, HashMapExt, Ustr, UstrMap};
+use std::{
+	mem,
+	path::{Path, PathBuf},
+};
 
 use crate::{
 	config::Config,
 	core::{
-		helpers::syncback::{rename_path, serialize_properties, validate_properties, verify_name, verify_path},
+		changes::Changes,
+		helpers::syncback::{
+			get_file_middleware, get_middleware, locate_model, rename_path, serialize_model_refs, serialize_properties,
+			serialize_refs, serialize_snapshot_refs, snapshot_from_tree, validate_properties, verify_name, verify_path,
+		},
 		meta::{Meta, NodePath, Source, SourceEntry, SourceKind},
 		snapshot::{AddedSnapshot, Snapshot, UpdatedSnapshot},
 		tree::Tree,
@@ -18,7 +25,10 @@ use crate::{
 		dir, Middleware,
 	},
 	project::{Project, ProjectNode},
-	vfs::Vfs,
+	vfs::{
+		preview::{Operation, Plan},
+		Vfs,
+	},
 	Properties,
 };
 
@@ -35,6 +45,66 @@ macro_rules! filter_warn {
 	};
 }
 
+/// Rewrites the whole model file of the given model root instance
+fn write_model(id: Ref, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
+	// Newly inserted instances may reference each other
+	tree.resolve_refs();
+
+	let meta = tree.get_meta(id).unwrap();
+
+	let (Some(file), Some(middleware)) = (meta.source.get_file(), get_file_middleware(meta)) else {
+		warn!("Failed to locate model file of instance: {id:?}");
+		return Ok(());
+	};
+
+	if meta.context.syncback_filter().matches_path(file.path()) {
+		filter_warn!(id, file.path());
+		return Ok(());
+	}
+
+	let mut snapshot = snapshot_from_tree(id, tree).unwrap();
+	serialize_model_refs(&mut snapshot, id, tree);
+
+	middleware.write(snapshot, file.path(), vfs)?;
+
+	Ok(())
+}
+
+fn insert_model_instances(snapshot: Snapshot, parent: Ref, tree: &mut Tree) {
+	let children = snapshot.children.clone();
+	let id = snapshot.id;
+
+	tree.insert_instance_with_ref(snapshot.with_meta(Meta::new()).with_children(Vec::new()), parent);
+
+	for child in children {
+		insert_model_instances(child, id, tree);
+	}
+}
+
+/// Locates new path of the instance data file when its instance
+/// is moved, keeping the current file format (e.g. `.data.toml`)
+fn locate_moved_data(current: &Path, path: &Path, name: &str, is_dir: bool, meta: &Meta) -> Option<PathBuf> {
+	let paths: Vec<PathBuf> = meta
+		.context
+		.sync_rules_of_type(&Middleware::InstanceData, true)
+		.iter()
+		.filter_map(|rule| rule.locate(path, name, is_dir))
+		.collect();
+
+	let current_name = current.get_name();
+
+	paths
+		.iter()
+		.find(|path| {
+			let file_name = path.get_name();
+			let suffix = file_name.strip_prefix(name).unwrap_or(file_name);
+
+			current_name.ends_with(suffix)
+		})
+		.or(paths.first())
+		.cloned()
+}
+
 pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
 	trace!("Adding {:?} with parent {:?}", snapshot.id, snapshot.parent);
 
@@ -58,6 +128,14 @@ pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 
 	snapshot.properties = validate_properties(snapshot.properties, filter);
 
+	// Instances added to models are written directly to the model file
+	if let Some(model_id) = locate_model(parent_id, tree) {
+		insert_model_instances(snapshot, parent_id, tree);
+		return write_model(model_id, tree, vfs);
+	}
+
+	serialize_snapshot_refs(&mut snapshot, parent_id, tree);
+
 	fn locate_instance_data(is_dir: bool, path: &Path, snapshot: &Snapshot, parent_meta: &Meta) -> Result<PathBuf> {
 		parent_meta
 			.context
@@ -71,21 +149,15 @@ pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 		has_children: bool,
 		path: &mut PathBuf,
 		snapshot: &mut Snapshot,
+		middleware: Option<Middleware>,
+		properties: Properties,
 		parent_meta: &Meta,
 		vfs: &Vfs,
 	) -> Result<Option<Meta>> {
 		let mut meta = snapshot.meta.clone().with_context(&parent_meta.context);
 		let filter = parent_meta.context.syncback_filter();
-		let mut properties = snapshot.properties.clone();
 
-		if let Some(middleware) = Middleware::from_class(
-			&snapshot.class,
-			if !parent_meta.context.use_legacy_scripts() {
-				Some(&mut properties)
-			} else {
-				None
-			},
-		) {
+		if let Some(middleware) = middleware {
 			let mut file_path = parent_meta
 				.context
 				.sync_rules_of_type(&middleware, true)
@@ -119,7 +191,13 @@ pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 				return Ok(None);
 			}
 
-			let properties = middleware.write(properties, &file_path, vfs)?;
+			let instance = Snapshot::new()
+				.with_name(&snapshot.name)
+				.with_class(&snapshot.class)
+				.with_properties(properties)
+				.with_children(snapshot.children.clone());
+
+			let properties = middleware.write(instance, &file_path, vfs)?;
 			let data_path = locate_instance_data(has_children, path, snapshot, parent_meta)?;
 
 			if filter.matches_path(&data_path) {
@@ -197,11 +275,7 @@ pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 				.with_context(|| format!("Failed to locate file path for parent: {}", folder_path.display()))?;
 
 			let data_paths = if let Some(data) = parent_meta.source.get_data() {
-				let new_path = parent_meta
-					.context
-					.sync_rules_of_type(&Middleware::InstanceData, true)
-					.iter()
-					.find_map(|rule| rule.locate(&folder_path, &name, true))
+				let new_path = locate_moved_data(data.path(), &folder_path, &name, true, parent_meta)
 					.with_context(|| format!("Failed to locate data path for parent: {}", folder_path.display()))?;
 
 				Some((data.path().to_owned(), new_path))
@@ -231,14 +305,52 @@ pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 		}
 
 		let mut path = parent_path.join(&snapshot.name);
+		let mut properties = snapshot.properties.clone();
 
-		if snapshot.children.is_empty() {
-			if let Some(meta) = write_instance(false, &mut path, &mut snapshot, parent_meta, vfs)? {
+		let middleware = get_middleware(
+			&snapshot.class,
+			&mut properties,
+			&snapshot.meta.clone().with_context(&parent_meta.context),
+		)
+		.or_else(|| parent_meta.context.model_middleware(&snapshot.class));
+
+		if middleware.as_ref().is_some_and(|middleware| middleware.is_model()) {
+			// Models store the whole subtree in a single file
+			if let Some(meta) = write_instance(
+				false,
+				&mut path,
+				&mut snapshot,
+				middleware,
+				properties,
+				parent_meta,
+				vfs,
+			)? {
+				let children = mem::take(&mut snapshot.children);
+				let id = snapshot.id;
+
+				tree.insert_instance_with_ref(snapshot.with_meta(meta), parent_id);
+
+				for child in children {
+					insert_model_instances(child, id, tree);
+				}
+			}
+		} else if snapshot.children.is_empty() {
+			if let Some(meta) = write_instance(
+				false,
+				&mut path,
+				&mut snapshot,
+				middleware,
+				properties,
+				parent_meta,
+				vfs,
+			)? {
 				let snapshot = snapshot.with_meta(meta);
 
 				tree.insert_instance_with_ref(snapshot, parent_id);
 			}
-		} else if let Some(mut meta) = write_instance(true, &mut path, &mut snapshot, parent_meta, vfs)? {
+		} else if let Some(mut meta) =
+			write_instance(true, &mut path, &mut snapshot, middleware, properties, parent_meta, vfs)?
+		{
 			let snapshot = snapshot.with_meta(meta.clone());
 
 			tree.insert_instance_with_ref(snapshot.clone(), parent_id);
@@ -312,7 +424,7 @@ pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 				parent_meta.set_source(parent_source);
 				tree.update_meta(parent_id, parent_meta);
 			} else {
-				let mut project = Project::load(&path)?;
+				let mut project = Project::load_vfs(&path, vfs)?;
 
 				let node = project
 					.find_node_by_path(&node_path)
@@ -320,7 +432,7 @@ pub fn apply_addition(snapshot: AddedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 
 				add_project_instances(parent_id, &path, node_path.clone(), snapshot, node, &parent_meta, tree);
 
-				project.save(&path)?;
+				project.save_vfs(&path, vfs)?;
 			}
 		}
 		SourceKind::None => panic!(
@@ -357,6 +469,22 @@ pub fn apply_update(snapshot: UpdatedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 		return Ok(());
 	}
 
+	// Instances that are part of a model are updated by rewriting the model file
+	if let Some(model_id) = locate_model(snapshot.id, tree).filter(|&model_id| model_id != snapshot.id) {
+		let filter = tree.get_meta(model_id).unwrap().context.syncback_filter().clone();
+		let instance = tree.get_instance_mut(snapshot.id).unwrap();
+
+		if let Some(name) = snapshot.name {
+			instance.name = name;
+		}
+
+		if let Some(properties) = snapshot.properties {
+			tree.set_properties(snapshot.id, validate_properties(properties, &filter));
+		}
+
+		return write_model(model_id, tree, vfs);
+	}
+
 	let mut meta = tree.get_meta(snapshot.id).unwrap().clone();
 	let instance = tree.get_instance_mut(snapshot.id).unwrap();
 
@@ -380,33 +508,39 @@ pub fn apply_update(snapshot: UpdatedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 	fn update_non_project_properties(
 		path: &Path,
 		properties: Properties,
-		instance: &mut Instance,
+		id: Ref,
+		tree: &mut Tree,
 		meta: &mut Meta,
 		vfs: &Vfs,
 	) -> Result<()> {
 		let filter = meta.context.syncback_filter();
 
 		if filter.matches_path(path) {
-			filter_warn!(instance.referent(), path);
+			filter_warn!(id, path);
 			return Ok(());
 		}
 
 		let mut properties = validate_properties(properties, filter);
 
-		if let Some(middleware) = Middleware::from_class(
-			&instance.class,
-			if !meta.context.use_legacy_scripts() {
-				Some(&mut properties)
-			} else {
-				None
-			},
-		) {
+		// Model files contain the whole instance so we just need to rewrite them
+		if get_file_middleware(meta).is_some_and(|middleware| middleware.is_model()) {
+			tree.set_properties(id, properties);
+			tree.update_meta(id, meta.clone());
+
+			return write_model(id, tree, vfs);
+		}
+
+		let instance = tree.get_instance(id).unwrap();
+		let name = instance.name.clone();
+		let class = instance.class;
+
+		if let Some(middleware) = get_middleware(&class, &mut properties, meta) {
 			let new_path = {
 				let mut paths = meta
 					.context
 					.sync_rules_of_type(&middleware, true)
 					.iter()
-					.filter_map(|rule| rule.locate(path, &instance.name, vfs.is_dir(path)))
+					.filter_map(|rule| rule.locate(path, &name, vfs.is_dir(path)))
 					.collect::<Vec<PathBuf>>();
 
 				paths.sort_by_key(|path| !path.exists());
@@ -435,29 +569,35 @@ pub fn apply_update(snapshot: UpdatedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 			};
 
 			if let Some(file_path) = file_path {
-				let properties = middleware.write(properties.clone(), &file_path, vfs)?;
+				let instance = Snapshot::new()
+					.with_name(&name)
+					.with_class(&class)
+					.with_properties(serialize_refs(properties.clone(), tree));
+
+				let properties = middleware.write(instance, &file_path, vfs)?;
 
-				if let Some(data_path) = locate_instance_data(&instance.name, path, meta, vfs) {
+				if let Some(data_path) = locate_instance_data(&name, path, meta, vfs) {
 					if filter.matches_path(&data_path) {
-						filter_warn!(instance.referent(), &data_path);
+						filter_warn!(id, &data_path);
 					} else {
-						let data_path = data::write_data(true, &instance.class, properties, &data_path, meta, vfs)?;
+						let data_path = data::write_data(true, &class, properties, &data_path, meta, vfs)?;
 						meta.source.set_data(data_path)
 					}
 				}
 			} else {
 				error!("Failed to locate file for path {:?}", path.display());
 			}
-		} else if let Some(data_path) = locate_instance_data(&instance.name, path, meta, vfs) {
+		} else if let Some(data_path) = locate_instance_data(&name, path, meta, vfs) {
 			if filter.matches_path(&data_path) {
-				filter_warn!(instance.referent(), &data_path);
+				filter_warn!(id, &data_path);
 			} else {
-				let data_path = data::write_data(false, &instance.class, properties.clone(), &data_path, meta, vfs)?;
+				let properties = serialize_refs(properties.clone(), tree);
+				let data_path = data::write_data(false, &class, properties, &data_path, meta, vfs)?;
 				meta.source.set_data(data_path)
 			}
 		}
 
-		instance.properties = properties;
+		tree.set_properties(id, properties);
 
 		Ok(())
 	}
@@ -532,7 +672,7 @@ pub fn apply_update(snapshot: UpdatedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 			}
 
 			if let Some(properties) = snapshot.properties {
-				update_non_project_properties(&path, properties, instance, &mut meta, vfs)?;
+				update_non_project_properties(&path, properties, snapshot.id, tree, &mut meta, vfs)?;
 			}
 
 			tree.update_meta(snapshot.id, meta);
@@ -548,13 +688,13 @@ pub fn apply_update(snapshot: UpdatedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 			}
 		}
 		SourceKind::Project(name, path, node, node_path) => {
-			let mut project = Project::load(&path)?;
+			let mut project = Project::load_vfs(&path, vfs)?;
 
 			if let Some(properties) = snapshot.properties {
 				if let Some(custom_path) = node.path {
 					let custom_path = path.with_file_name(custom_path.path()).clean();
 
-					update_non_project_properties(&custom_path, properties, instance, &mut meta, vfs)?;
+					update_non_project_properties(&custom_path, properties, snapshot.id, tree, &mut meta, vfs)?;
 
 					let node = project
 						.find_node_by_path(&node_path)
@@ -572,11 +712,11 @@ pub fn apply_update(snapshot: UpdatedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 					let class = node.class_name.unwrap_or(Ustr::from(&name));
 					let properties = validate_properties(properties, meta.context.syncback_filter());
 
-					node.properties = serialize_properties(&class, properties.clone());
+					node.properties = serialize_properties(&class, serialize_refs(properties.clone(), tree));
 					node.tags = Vec::new();
 					node.keep_unknowns = None;
 
-					instance.properties = properties;
+					tree.set_properties(snapshot.id, properties);
 				}
 			}
 
@@ -597,11 +737,11 @@ pub fn apply_update(snapshot: UpdatedSnapshot, tree: &mut Tree, vfs: &Vfs) -> Re
 
 				*meta.source.get_mut() = SourceKind::Project(new_name.clone(), path.clone(), Box::new(node), node_path);
 
-				instance.name = new_name;
+				tree.get_instance_mut(snapshot.id).unwrap().name = new_name;
 			}
 
 			tree.update_meta(snapshot.id, meta);
-			project.save(&path)?;
+			project.save_vfs(&path, vfs)?;
 
 			if let Some(_class) = snapshot.class {
 				// You can't change the class of an instance inside Roblox Studio
@@ -634,6 +774,12 @@ pub fn apply_removal(id: Ref, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
 		return Ok(());
 	}
 
+	// Instances that are part of a model are removed by rewriting the model file
+	if let Some(model_id) = locate_model(id, tree).filter(|&model_id| model_id != id) {
+		tree.remove_instance(id);
+		return write_model(model_id, tree, vfs);
+	}
+
 	let meta = tree.get_meta(id).unwrap().clone();
 
 	fn remove_non_project_instances(id: Ref, meta: &Meta, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
@@ -686,11 +832,7 @@ pub fn apply_removal(id: Ref, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
 					let mut source = Source::file(&new_path);
 
 					if let Some(data) = meta.source.get_data() {
-						let data_path = meta
-							.context
-							.sync_rules_of_type(&Middleware::InstanceData, true)
-							.iter()
-							.find_map(|rule| rule.locate(folder_path, name, false));
+						let data_path = locate_moved_data(data.path(), folder_path, name, false, meta);
 
 						if let Some(new_path) = data_path {
 							vfs.rename(data.path(), &new_path)?;
@@ -710,7 +852,7 @@ pub fn apply_removal(id: Ref, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
 	match meta.source.get() {
 		SourceKind::Path(_) => remove_non_project_instances(id, &meta, tree, vfs)?,
 		SourceKind::Project(name, path, node, node_path) => {
-			let mut project = Project::load(path)?;
+			let mut project = Project::load_vfs(path, vfs)?;
 			let parent_node = project.find_node_by_path(&node_path.parent());
 
 			parent_node.and_then(|node| node.tree.remove(name)).ok_or(anyhow!(
@@ -723,7 +865,7 @@ pub fn apply_removal(id: Ref, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
 				remove_non_project_instances(id, &meta, tree, vfs)?;
 			}
 
-			project.save(path)?;
+			project.save_vfs(path, vfs)?;
 		}
 		SourceKind::None => panic!("Attempted to remove instance with no source: {id:?}"),
 	}
@@ -732,3 +874,46 @@ pub fn apply_removal(id: Ref, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
 
 	Ok(())
 }
+
+/// Applies all client changes in order, stops at the first failure
+pub fn apply_changes(changes: Changes, tree: &mut Tree, vfs: &Vfs) -> Result<()> {
+	for snapshot in changes.additions {
+		apply_addition(snapshot, tree, vfs)?;
+	}
+
+	for snapshot in changes.updates {
+		apply_update(snapshot, tree, vfs)?;
+	}
+
+	for id in changes.removals {
+		apply_removal(id, tree, vfs)?;
+	}
+
+	Ok(())
+}
+
+/// Client changes applied to a copy of the tree, so they
+/// can be written to the disk without applying them again
+pub struct Preview {
+	/// Filesystem changes that would be made
+	pub plan: Plan,
+	/// Tree with the changes applied
+	pub tree: Tree,
+	/// Filesystem operations to make, in order
+	pub operations: Vec<Operation>,
+}
+
+/// Returns filesystem changes that applying client changes would make,
+/// neither the disk nor the given tree is modified
+pub fn preview_changes(changes: Changes, tree: &Tree) -> Result<Preview> {
+	let mut tree = tree.clone();
+	let vfs = Vfs::new_preview();
+
+	apply_changes(changes, &mut tree, &vfs)?;
+
+	Ok(Preview {
+		plan: vfs.plan().unwrap_or_default(),
+		tree,
+		operations: vfs.operations(),
+	})
+}
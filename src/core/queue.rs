@@ -1,13 +1,18 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
 use crossbeam_channel::{Receiver, Sender};
-use std::{collections::HashMap, sync::RwLock};
+use log::{info, warn};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{Arc, Condvar, Mutex, RwLock},
+	time::{Duration, Instant},
+};
 
 use crate::{
 	argon_warn,
 	config::Config,
-	constants::QUEUE_TIMEOUT,
-	server::{self, Message},
+	constants::{QUEUE_CAPACITY, QUEUE_TIMEOUT},
+	server::{self, ExecutionResult, Message},
 };
 
 macro_rules! read {
@@ -29,17 +34,117 @@ struct Listener {
 	pub is_internal: bool,
 }
 
+/// Bounded message buffer of a single listener, consecutive
+/// `SyncChanges` are merged into one and when the buffer
+/// overflows pending changes are replaced with `Resync`
+#[derive(Debug)]
+struct Buffer {
+	state: Mutex<BufferState>,
+	condvar: Condvar,
+}
+
 #[derive(Debug)]
-struct Channel {
-	sender: Sender<Message>,
-	receiver: Receiver<Message>,
+struct BufferState {
+	messages: VecDeque<Message>,
+	closed: bool,
+}
+
+impl Buffer {
+	fn new() -> Self {
+		Self {
+			state: Mutex::new(BufferState {
+				messages: VecDeque::new(),
+				closed: false,
+			}),
+			condvar: Condvar::new(),
+		}
+	}
+
+	fn push(&self, message: Message) -> Result<()> {
+		let mut state = self.state.lock().unwrap();
+
+		if state.closed {
+			bail!("Not subscribed")
+		}
+
+		let messages = &mut state.messages;
+		let is_resyncing = messages.iter().any(|message| matches!(message, Message::Resync(_)));
+
+		match message {
+			// Client will fetch the whole tree anyway
+			_ if is_resyncing && message.is_change() => {}
+			Message::SyncChanges(changes) => match messages.back_mut() {
+				Some(Message::SyncChanges(last)) => last.0.merge(changes.0),
+				_ => messages.push_back(Message::SyncChanges(changes)),
+			},
+			message => messages.push_back(message),
+		}
+
+		let size: usize = messages
+			.iter()
+			.map(|message| match message {
+				Message::SyncChanges(changes) => changes.0.total().max(1),
+				_ => 1,
+			})
+			.sum();
+
+		if size > QUEUE_CAPACITY {
+			warn!("Client queue overflowed, requesting full resync");
+
+			messages.retain(|message| !message.is_change());
+			messages.push_back(Message::Resync(server::Resync {
+				message: String::from("Too many changes to sync, fetch the whole tree"),
+			}));
+		}
+
+		drop(state);
+		self.condvar.notify_one();
+
+		Ok(())
+	}
+
+	/// Waits for the next message, returns `None` when
+	/// the timeout elapses or the listener unsubscribes
+	fn pop(&self, timeout: Option<Duration>) -> Option<Message> {
+		let deadline = timeout.map(|timeout| Instant::now() + timeout);
+		let mut state = self.state.lock().unwrap();
+
+		loop {
+			if let Some(message) = state.messages.pop_front() {
+				return Some(message);
+			}
+
+			if state.closed {
+				return None;
+			}
+
+			state = match deadline {
+				Some(deadline) => {
+					let timeout = deadline.checked_duration_since(Instant::now())?;
+					self.condvar.wait_timeout(state, timeout).unwrap().0
+				}
+				None => self.condvar.wait(state).unwrap(),
+			};
+		}
+	}
+
+	fn is_empty(&self) -> bool {
+		self.state.lock().unwrap().messages.is_empty()
+	}
+
+	fn close(&self) {
+		self.state.lock().unwrap().closed = true;
+		self.condvar.notify_all();
+	}
 }
 
 #[derive(Debug)]
 pub struct Queue {
-	queues: RwLock<HashMap<u32, Channel>>,
+	queues: RwLock<HashMap<u32, Arc<Buffer>>>,
 	listeners: RwLock<Vec<Listener>>,
 	unsynced_changes: RwLock<usize>,
+	/// Pending code executions with IDs of clients that run them
+	executions: RwLock<HashMap<String, (u32, Sender<ExecutionResult>)>>,
 }
 
 impl Queue {
@@ -48,6 +153,7 @@ impl Queue {
 			queues: RwLock::new(HashMap::new()),
 			listeners: RwLock::new(Vec::new()),
 			unsynced_changes: RwLock::new(0),
+			executions: RwLock::new(HashMap::new()),
 		}
 	}
 
@@ -61,9 +167,7 @@ impl Queue {
 			}
 
 			let queues = read!(self.queues);
-			let sender = queues.get(&id).unwrap().sender.clone();
-
-			sender.send(message.into())?;
+			queues.get(&id).unwrap().push(message.into())?;
 
 			return Ok(());
 		}
@@ -71,16 +175,20 @@ impl Queue {
 		let message: Message = message.into();
 		let mut did_push = false;
 
-		for listener in read!(self.listeners).iter() {
-			let queues = read!(self.queues);
-			let sender = queues.get(&listener.id).unwrap().sender.clone();
+		let listeners = read!(self.listeners);
 
-			sender.send(message.clone())?;
+		for listener in listeners.iter() {
+			let queues = read!(self.queues);
+			queues.get(&listener.id).unwrap().push(message.clone())?;
 
-			did_push = true;
+			if !listener.is_internal {
+				did_push = true;
+			}
 		}
 
-		if !did_push {
+		// Count changes so the first client that subscribes
+		// knows that its tree is outdated, see `subscribe`
+		if !did_push && message.is_change() {
 			let max_unsynced_changes = Config::new().max_unsynced_changes;
 			let mut unsynced_changes = write!(self.unsynced_changes);
 
@@ -102,12 +210,9 @@ impl Queue {
 			bail!("Not subscribed")
 		}
 
-		let queues = read!(self.queues);
-		let receiver = queues.get(&id).unwrap().receiver.clone();
-
-		drop(queues);
+		let buffer = read!(self.queues).get(&id).unwrap().clone();
 
-		Ok(receiver.recv().ok())
+		Ok(buffer.pop(None))
 	}
 
 	pub fn get_timeout(&self, id: u32) -> Result<Option<Message>> {
@@ -115,12 +220,9 @@ impl Queue {
 			bail!("Not subscribed")
 		}
 
-		let queues = read!(self.queues);
-		let receiver = queues.get(&id).unwrap().receiver.clone();
-
-		drop(queues);
+		let buffer = read!(self.queues).get(&id).unwrap().clone();
 
-		Ok(receiver.recv_timeout(QUEUE_TIMEOUT).ok())
+		Ok(buffer.pop(Some(QUEUE_TIMEOUT)))
 	}
 
 	pub fn get_change(&self, id: u32) -> Result<Message> {
@@ -128,36 +230,78 @@ impl Queue {
 			bail!("Not subscribed")
 		}
 
-		let queues = read!(self.queues);
-		let receiver = queues.get(&id).unwrap().receiver.clone();
-
-		drop(queues);
+		let buffer = read!(self.queues).get(&id).unwrap().clone();
 
 		Ok(loop {
-			if let Ok(message) = receiver.recv() {
-				if message.is_change() {
-					break message;
-				}
+			let Some(message) = buffer.pop(None) else {
+				bail!("Not subscribed")
+			};
+
+			if message.is_change() {
+				break message;
 			}
 		})
 	}
 
+	/// Like `get_change` but gives up after the timeout
+	pub fn get_change_timeout(&self, id: u32, timeout: Duration) -> Result<Option<Message>> {
+		if !self.is_subscribed(id) {
+			bail!("Not subscribed")
+		}
+
+		let buffer = read!(self.queues).get(&id).unwrap().clone();
+		let deadline = Instant::now() + timeout;
+
+		loop {
+			let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+				return Ok(None);
+			};
+
+			match buffer.pop(Some(remaining)) {
+				Some(message) if message.is_change() => return Ok(Some(message)),
+				Some(_) => {}
+				None => return Ok(None),
+			}
+		}
+	}
+
 	pub fn subscribe(&self, id: u32, name: &str) -> Result<()> {
 		if self.is_subscribed(id) {
 			bail!("Already subscribed")
 		}
 
-		let (sender, receiver) = crossbeam_channel::unbounded();
-		let channel = Channel { sender, receiver };
-
 		let listener = Listener {
 			id,
 			name: name.to_owned(),
 			is_internal: false,
 		};
 
-		write!(self.listeners).push(listener);
-		write!(self.queues).insert(id.to_owned(), channel);
+		// Listeners lock is held for the whole time so
+		// no change can slip between the check and the new queue
+		let mut listeners = write!(self.listeners);
+		let buffer = Buffer::new();
+
+		// Replaying changes made while no client was connected could
+		// duplicate instances of a freshly fetched tree, so the client
+		// is asked to fetch the whole tree again instead
+		if !listeners.iter().any(|listener| !listener.is_internal) {
+			let mut unsynced_changes = write!(self.unsynced_changes);
+
+			if *unsynced_changes > 0 {
+				info!("Requesting full resync from client {id} after {unsynced_changes} unsynced changes");
+
+				buffer.push(Message::Resync(server::Resync {
+					message: format!(
+						"{unsynced_changes} changes were made while no client was connected, fetch the whole tree"
+					),
+				}))?;
+			}
+
+			*unsynced_changes = 0;
+		}
+
+		write!(self.queues).insert(id.to_owned(), Arc::new(buffer));
+		listeners.push(listener);
 
 		Ok(())
 	}
@@ -173,9 +317,6 @@ impl Queue {
 			id += 1;
 		}
 
-		let (sender, receiver) = crossbeam_channel::unbounded();
-		let channel = Channel { sender, receiver };
-
 		let listener = Listener {
 			id,
 			name: format!("Internal listener #{id}"),
@@ -183,7 +324,7 @@ impl Queue {
 		};
 
 		write!(self.listeners).push(listener);
-		write!(self.queues).insert(id.to_owned(), channel);
+		write!(self.queues).insert(id.to_owned(), Arc::new(Buffer::new()));
 
 		Ok(())
 	}
@@ -194,7 +335,10 @@ impl Queue {
 		}
 
 		write!(self.listeners).retain(|listener| listener.id != id);
-		write!(self.queues).remove(&id);
+
+		if let Some(buffer) = write!(self.queues).remove(&id) {
+			buffer.close();
+		}
 
 		Ok(())
 	}
@@ -214,14 +358,71 @@ impl Queue {
 		Ok(())
 	}
 
+	/// Registers code execution and returns receiver that will
+	/// receive its result once the given client sends it
+	pub fn expect_result(&self, id: &str, client_id: u32) -> Receiver<ExecutionResult> {
+		let (sender, receiver) = crossbeam_channel::bounded(1);
+
+		write!(self.executions).insert(id.to_owned(), (client_id, sender));
+
+		receiver
+	}
+
+	pub fn push_result(&self, id: &str, client_id: u32, result: ExecutionResult) -> Result<()> {
+		let mut executions = write!(self.executions);
+
+		match executions.get(id) {
+			Some((expected, _)) if *expected != client_id => bail!("Execution belongs to another client"),
+			None => bail!("Unknown execution"),
+			_ => {}
+		}
+
+		let (_, sender) = executions.remove(id).unwrap();
+		sender.send(result)?;
+
+		Ok(())
+	}
+
+	pub fn forget_result(&self, id: &str) {
+		write!(self.executions).remove(id);
+	}
+
 	pub fn is_subscribed(&self, id: u32) -> bool {
 		read!(self.listeners).iter().any(|listener| listener.id == id)
 	}
 
+	pub fn is_empty(&self, id: u32) -> bool {
+		read!(self.queues).get(&id).is_none_or(|buffer| buffer.is_empty())
+	}
+
+	pub fn get_first_non_internal_listener_id(&self) -> Option<u32> {
+		read!(self.listeners)
+			.iter()
+			.find(|listener| !listener.is_internal)
+			.map(|listener| listener.id)
+	}
+
 	pub fn get_first_non_internal_listener_name(&self) -> Option<String> {
 		read!(self.listeners)
 			.iter()
 			.find(|listener| !listener.is_internal)
 			.map(|listener| listener.name.to_owned())
 	}
+
+	/// Returns ID of the most recently subscribed client
+	pub fn get_last_non_internal_listener_id(&self) -> Option<u32> {
+		read!(self.listeners)
+			.iter()
+			.rfind(|listener| !listener.is_internal)
+			.map(|listener| listener.id)
+	}
+
+	/// Returns name of the client with the given ID,
+	/// `None` if there is no such client or it is internal
+	pub fn get_non_internal_listener_name(&self, id: u32) -> Option<String> {
+		read!(self.listeners)
+			.iter()
+			.find(|listener| listener.id == id && !listener.is_internal)
+			.map(|listener| listener.name.to_owned())
+	}
 }
@@ -1,5 +1,5 @@
 use rbx_dom_weak::{
-	types::{Ref, Variant},
+	types::{Ref, Tags, Variant},
 	ustr, HashMapExt, Ustr, UstrMap,
 };
 use serde::{Deserialize, Serialize};
@@ -114,7 +114,34 @@ impl Snapshot {
 			self.meta.set_mesh_source(Some(mesh_source));
 		}
 
-		self.extend_properties(data.properties);
+		let mut properties = data.properties;
+
+		// Attributes and tags can also come from the source file itself
+		// (e.g. script directives) so they are merged instead of replaced
+		if let Some(Variant::Attributes(current)) = self.properties.get(&ustr("Attributes")) {
+			if let Some(Variant::Attributes(attributes)) = properties.get_mut(&ustr("Attributes")) {
+				for (name, value) in current.iter() {
+					if attributes.get(name.as_str()).is_none() {
+						attributes.insert(name.to_owned(), value.clone());
+					}
+				}
+			}
+		}
+
+		if let Some(Variant::Tags(current)) = self.properties.get(&ustr("Tags")) {
+			if let Some(Variant::Tags(tags)) = properties.get_mut(&ustr("Tags")) {
+				let mut merged: Vec<String> = current.iter().map(String::from).collect();
+				merged.extend(
+					tags.iter()
+						.filter(|tag| !current.iter().any(|current| current == *tag))
+						.map(String::from),
+				);
+
+				*tags = Tags::from(merged);
+			}
+		}
+
+		self.extend_properties(properties);
 		self.meta.source.add_data(&data.path);
 	}
 
@@ -1,21 +1,29 @@
 use multimap::MultiMap;
-use rbx_dom_weak::{types::Ref, Instance, InstanceBuilder, WeakDom};
+use rbx_dom_weak::{
+	types::{Ref, Variant},
+	Instance, InstanceBuilder, UstrMap, WeakDom,
+};
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	path::{Path, PathBuf},
 };
 
-use super::{meta::Meta, snapshot::Snapshot};
+use super::{helpers::refs, meta::Meta, snapshot::Snapshot};
+use crate::{vfs::Vfs, Properties};
 
 #[derive(Debug)]
 pub struct Tree {
 	dom: WeakDom,
 	path_to_ids: MultiMap<PathBuf, Ref>,
 	id_to_meta: HashMap<Ref, Meta>,
+	/// Ref properties whose instance paths could not be resolved yet
+	pending_refs: HashMap<Ref, UstrMap<String>>,
 }
 
 impl Tree {
-	pub fn new(snapshot: Snapshot) -> Self {
+	pub fn new(mut snapshot: Snapshot) -> Self {
+		let paths = refs::take_ref_paths(&snapshot.class, &mut snapshot.properties);
+
 		let builder = InstanceBuilder::new(snapshot.class)
 			.with_name(snapshot.name)
 			.with_properties(snapshot.properties);
@@ -24,20 +32,26 @@ impl Tree {
 			dom: WeakDom::new(builder),
 			id_to_meta: HashMap::new(),
 			path_to_ids: MultiMap::new(),
+			pending_refs: HashMap::new(),
 		};
 
 		let root_ref = tree.dom.root_ref();
 
 		tree.insert_meta(root_ref, snapshot.meta);
+		tree.insert_pending_refs(root_ref, paths);
 
 		for child in snapshot.children {
 			tree.insert_instance_recursive(child, root_ref);
 		}
 
+		tree.resolve_refs();
+
 		tree
 	}
 
-	pub fn insert_instance(&mut self, snapshot: Snapshot, parent: Ref) -> Ref {
+	pub fn insert_instance(&mut self, mut snapshot: Snapshot, parent: Ref) -> Ref {
+		let paths = refs::take_ref_paths(&snapshot.class, &mut snapshot.properties);
+
 		let builder = InstanceBuilder::new(snapshot.class)
 			.with_name(snapshot.meta.original_name.as_ref().unwrap_or(&snapshot.name))
 			.with_properties(snapshot.properties);
@@ -45,11 +59,14 @@ impl Tree {
 		let id = self.dom.insert(parent, builder);
 
 		self.insert_meta(id, snapshot.meta);
+		self.insert_pending_refs(id, paths);
 
 		id
 	}
 
-	pub fn insert_instance_recursive(&mut self, snapshot: Snapshot, parent: Ref) -> Ref {
+	pub fn insert_instance_recursive(&mut self, mut snapshot: Snapshot, parent: Ref) -> Ref {
+		let paths = refs::take_ref_paths(&snapshot.class, &mut snapshot.properties);
+
 		let builder = InstanceBuilder::new(snapshot.class)
 			.with_name(snapshot.meta.original_name.as_ref().unwrap_or(&snapshot.name))
 			.with_properties(snapshot.properties);
@@ -57,6 +74,7 @@ impl Tree {
 		let id = self.dom.insert(parent, builder);
 
 		self.insert_meta(id, snapshot.meta);
+		self.insert_pending_refs(id, paths);
 
 		for child in snapshot.children {
 			self.insert_instance_recursive(child, id);
@@ -65,7 +83,9 @@ impl Tree {
 		id
 	}
 
-	pub fn insert_instance_with_ref(&mut self, snapshot: Snapshot, parent: Ref) {
+	pub fn insert_instance_with_ref(&mut self, mut snapshot: Snapshot, parent: Ref) {
+		let paths = refs::take_ref_paths(&snapshot.class, &mut snapshot.properties);
+
 		let builder = InstanceBuilder::new(snapshot.class)
 			.with_name(snapshot.meta.original_name.as_ref().unwrap_or(&snapshot.name))
 			.with_referent(snapshot.id)
@@ -74,6 +94,7 @@ impl Tree {
 		let id = self.dom.insert(parent, builder);
 
 		self.insert_meta(id, snapshot.meta);
+		self.insert_pending_refs(id, paths);
 	}
 
 	pub fn remove_instance(&mut self, id: Ref) {
@@ -92,11 +113,77 @@ impl Tree {
 
 		for id in to_remove {
 			self.remove_meta(id);
+			self.pending_refs.remove(&id);
 		}
 
 		self.dom.destroy(id);
 	}
 
+	/// Replaces properties of the instance, resolving
+	/// instance paths of ref properties where possible
+	pub fn set_properties(&mut self, id: Ref, mut properties: Properties) {
+		self.update_refs(id, &mut properties);
+
+		if let Some(instance) = self.dom.get_by_ref_mut(id) {
+			instance.properties = properties;
+		}
+	}
+
+	/// Resolves instance paths of ref properties in the given properties
+	/// of an existing instance, unresolved ones are kept pending
+	pub fn update_refs(&mut self, id: Ref, properties: &mut Properties) {
+		let Some(instance) = self.dom.get_by_ref(id) else {
+			return;
+		};
+
+		let mut paths = refs::take_ref_paths(&instance.class, properties);
+
+		paths.retain(|property, path| match refs::find_by_path(&self.dom, id, path) {
+			Some(target) => {
+				properties.insert(*property, Variant::Ref(target));
+				false
+			}
+			None => true,
+		});
+
+		self.pending_refs.remove(&id);
+		self.insert_pending_refs(id, paths);
+	}
+
+	/// Resolves pending ref properties whose targets exist now,
+	/// returns IDs of the instances that got updated
+	pub fn resolve_refs(&mut self) -> Vec<Ref> {
+		let mut updated = Vec::new();
+
+		for (id, paths) in self.pending_refs.iter_mut() {
+			paths.retain(|property, path| {
+				let Some(target) = refs::find_by_path(&self.dom, *id, path) else {
+					return true;
+				};
+
+				if let Some(instance) = self.dom.get_by_ref_mut(*id) {
+					instance.properties.insert(*property, Variant::Ref(target));
+
+					if !updated.contains(id) {
+						updated.push(*id);
+					}
+				}
+
+				false
+			});
+		}
+
+		self.pending_refs.retain(|_, paths| !paths.is_empty());
+
+		updated
+	}
+
+	fn insert_pending_refs(&mut self, id: Ref, paths: UstrMap<String>) {
+		if !paths.is_empty() {
+			self.pending_refs.insert(id, paths);
+		}
+	}
+
 	pub fn get_instance(&self, id: Ref) -> Option<&Instance> {
 		self.dom.get_by_ref(id)
 	}
@@ -165,6 +252,30 @@ impl Tree {
 		self.id_to_meta.get_mut(&id)
 	}
 
+	/// Updates content hashes of instances whose source files are
+	/// any of the given paths or are located in the given directories
+	pub fn update_hashes(&mut self, paths: &[PathBuf], vfs: &Vfs) {
+		let mut ids = HashSet::new();
+		let mut paths = paths.to_vec();
+
+		while let Some(path) = paths.pop() {
+			// Renamed directories carry their children with them
+			if vfs.is_dir(&path) {
+				paths.extend(vfs.read_dir(&path).unwrap_or_default());
+			}
+
+			if let Some(path_ids) = self.path_to_ids.get_vec(&path) {
+				ids.extend(path_ids.iter().copied());
+			}
+		}
+
+		for id in ids {
+			if let Some(meta) = self.id_to_meta.get_mut(&id) {
+				meta.update_hashes(vfs);
+			}
+		}
+	}
+
 	pub fn get_ids(&self, path: &Path) -> Option<&Vec<Ref>> {
 		self.path_to_ids.get_vec(path)
 	}
@@ -197,3 +308,25 @@ impl Tree {
 		self.dom.root().children()
 	}
 }
+
+impl Clone for Tree {
+	/// Creates a deep copy of the tree that keeps all instance refs
+	fn clone(&self) -> Self {
+		fn builder(dom: &WeakDom, id: Ref) -> InstanceBuilder {
+			let instance = dom.get_by_ref(id).unwrap();
+
+			InstanceBuilder::new(instance.class)
+				.with_referent(id)
+				.with_name(&instance.name)
+				.with_properties(instance.properties.clone())
+				.with_children(instance.children().iter().map(|&child| builder(dom, child)))
+		}
+
+		Self {
+			dom: WeakDom::new(builder(&self.dom, self.dom.root_ref())),
+			path_to_ids: self.path_to_ids.clone(),
+			id_to_meta: self.id_to_meta.clone(),
+			pending_refs: self.pending_refs.clone(),
+		}
+	}
+}
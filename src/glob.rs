@@ -1,76 +1,214 @@
-use glob::{glob, Paths, Pattern, PatternError};
+use glob::{glob, MatchOptions, Pattern, PatternError};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
 	fmt::{self, Debug, Formatter},
 	path::{Path, PathBuf},
 };
 
+/// Options used by gitignore-style globs, `*` does not match across directories
+const GITIGNORE_OPTIONS: MatchOptions = MatchOptions {
+	case_sensitive: true,
+	require_literal_separator: true,
+	require_literal_leading_dot: false,
+};
+
+/// Single glob pattern that supports `!` negation and `{a,b}` brace expansion,
+/// regular globs are matched against the whole path, gitignore-style ones
+/// follow `.gitignore` rules (see [`Glob::gitignore`])
 #[derive(Clone, PartialEq)]
 pub struct Glob {
-	pattern: Pattern,
+	source: String,
+	// Brace-expanded sources without negation, used to search the filesystem
+	expanded: Vec<String>,
+	patterns: Vec<Pattern>,
+	// Patterns with `/**` suffix stripped, so they also match the directory itself
+	dir_patterns: Vec<Pattern>,
+	negated: bool,
+	gitignore: bool,
+	anchored: bool,
+	dir_only: bool,
 }
 
 impl Glob {
 	pub fn new(pattern: &str) -> Result<Self, PatternError> {
-		#[cfg(not(target_os = "windows"))]
-		let pattern = pattern.replace('\\', "/");
-
-		#[cfg(target_os = "windows")]
-		let pattern = pattern.replace('/', "\\");
+		Self::parse(pattern, false)
+	}
 
-		Ok(Self {
-			pattern: Pattern::new(&pattern)?,
-		})
+	/// Creates gitignore-style glob, patterns without a slash match file names
+	/// at any depth, other ones are anchored and their `*` does not match `/`,
+	/// patterns with a trailing `/` only match directories
+	pub fn gitignore(pattern: &str) -> Result<Self, PatternError> {
+		Self::parse(pattern, true)
 	}
 
 	pub fn from_path(path: &Path) -> Result<Self, PatternError> {
 		Self::new(path.to_str().unwrap_or_default())
 	}
 
+	fn parse(pattern: &str, gitignore: bool) -> Result<Self, PatternError> {
+		let source = pattern.to_owned();
+		let pattern = pattern.replace('\\', "/");
+
+		let (negated, pattern) = match pattern.strip_prefix('!') {
+			Some(pattern) => (true, pattern),
+			None => (false, pattern.as_str()),
+		};
+
+		let (dir_only, anchored, pattern) = if gitignore {
+			let (dir_only, pattern) = match pattern.strip_suffix('/') {
+				Some(pattern) if !pattern.is_empty() => (true, pattern),
+				_ => (false, pattern),
+			};
+
+			(
+				dir_only,
+				pattern.contains('/'),
+				pattern.strip_prefix('/').unwrap_or(pattern),
+			)
+		} else {
+			(false, true, pattern)
+		};
+
+		let expanded = expand_braces(pattern);
+
+		let mut patterns = Vec::new();
+		let mut dir_patterns = Vec::new();
+
+		for pattern in &expanded {
+			if let Some(stripped) = pattern.strip_suffix("/**") {
+				dir_patterns.push(compile(stripped)?);
+			}
+
+			patterns.push(compile(pattern)?);
+		}
+
+		Ok(Self {
+			source,
+			expanded,
+			patterns,
+			dir_patterns,
+			negated,
+			gitignore,
+			anchored,
+			dir_only,
+		})
+	}
+
 	pub fn matches(&self, str: &str) -> bool {
-		self.pattern.matches(str)
+		self.patterns
+			.iter()
+			.any(|pattern| pattern.matches_with(str, self.options()))
 	}
 
+	/// Matches the given file path, ignoring negation
 	pub fn matches_path(&self, path: &Path) -> bool {
-		self.pattern.matches_path(path)
+		self.matches_entry(path, false)
 	}
 
-	pub fn matches_path_with_dir(&self, path: &Path) -> bool {
-		let matches = self.pattern.matches_path(path);
+	/// Matches the given file or directory path, ignoring negation
+	pub fn matches_entry(&self, path: &Path, is_dir: bool) -> bool {
+		if self.dir_only && !is_dir {
+			return false;
+		}
 
-		if !matches && self.pattern.as_str().ends_with("/**") {
-			if let Ok(pattern) = Pattern::new(self.pattern.as_str().strip_suffix("/**").unwrap()) {
-				return pattern.matches_path(path);
-			} else {
-				return false;
-			}
+		if self.anchored {
+			self.patterns
+				.iter()
+				.any(|pattern| pattern.matches_path_with(path, self.options()))
+		} else if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+			self.patterns
+				.iter()
+				.any(|pattern| pattern.matches_with(name, self.options()))
+		} else {
+			false
 		}
+	}
 
-		matches
+	/// Matches the given directory path, patterns ending with `/**`
+	/// match the directory itself in addition to its contents
+	pub fn matches_dir(&self, path: &Path) -> bool {
+		self.matches_entry(path, true)
+			|| self
+				.dir_patterns
+				.iter()
+				.any(|pattern| pattern.matches_path_with(path, self.options()))
 	}
 
-	pub fn first(&self) -> Option<PathBuf> {
-		let path = glob(self.pattern.as_str()).unwrap().next();
+	/// Matches any parent directory of the given path
+	pub fn matches_parent(&self, path: &Path) -> bool {
+		path.ancestors()
+			.skip(1)
+			.filter(|ancestor| !ancestor.as_os_str().is_empty())
+			.any(|ancestor| self.matches_dir(ancestor))
+	}
 
-		if let Some(path) = path {
-			return Some(path.unwrap_or_default());
+	/// Checks whether this glob could match any path inside the given directory
+	pub fn matches_inside(&self, dir: &Path) -> bool {
+		if !self.anchored {
+			return true;
 		}
 
-		None
+		let dir = dir
+			.components()
+			.map(|component| component.as_os_str().to_string_lossy())
+			.collect::<Vec<_>>();
+
+		self.expanded.iter().any(|pattern| {
+			let mut components = pattern.split('/').filter(|component| !component.is_empty());
+
+			for name in &dir {
+				match components.next() {
+					Some("**") => return true,
+					// Without literal separators wildcards can match across directories
+					Some(component) if !self.gitignore && component.contains(['*', '?', '[']) => return true,
+					Some(component) => {
+						let matches = Pattern::new(component)
+							.map(|component| component.matches_with(name, self.options()))
+							.unwrap_or_default();
+
+						if !matches {
+							return false;
+						}
+					}
+					None => return false,
+				}
+			}
+
+			components.next().is_some()
+		})
+	}
+
+	/// Returns the first existing path matching this glob
+	pub fn first(&self) -> Option<PathBuf> {
+		self.iter().next()
 	}
 
-	pub fn iter(&self) -> Paths {
-		glob(self.pattern.as_str()).unwrap()
+	/// Returns all existing paths matching this glob,
+	/// negated globs do not match any paths on their own
+	pub fn iter(&self) -> impl Iterator<Item = PathBuf> + '_ {
+		self.expanded
+			.iter()
+			.filter(|_| !self.negated)
+			.filter_map(|pattern| glob(pattern).ok())
+			.flat_map(|paths| paths.filter_map(|path| path.ok()))
 	}
 
 	pub fn as_str(&self) -> &str {
-		self.pattern.as_str()
+		&self.source
+	}
+
+	fn options(&self) -> MatchOptions {
+		if self.gitignore {
+			GITIGNORE_OPTIONS
+		} else {
+			MatchOptions::new()
+		}
 	}
 }
 
 impl Serialize for Glob {
 	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-		serializer.serialize_str(self.pattern.as_str())
+		serializer.serialize_str(&self.source)
 	}
 }
 
@@ -83,6 +221,135 @@ impl<'de> Deserialize<'de> for Glob {
 
 impl Debug for Glob {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		write!(f, "{}", self.pattern.as_str())
+		write!(f, "{}", self.source)
+	}
+}
+
+/// Ordered set of globs where the last matching glob decides,
+/// so negated globs can match paths back in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlobSet {
+	globs: Vec<Glob>,
+}
+
+impl GlobSet {
+	pub fn new(globs: Vec<Glob>) -> Self {
+		Self { globs }
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.globs.is_empty()
+	}
+
+	/// Returns `Some(true)` if the last glob matching the path or one of its
+	/// parent directories is regular, `Some(false)` if it is negated and `None`
+	/// if no glob matches. Directories are never excluded while a later
+	/// negated glob could still match something inside them
+	pub fn evaluate(&self, path: &Path, is_dir: bool) -> Option<bool> {
+		let (index, glob) = self.globs.iter().enumerate().rev().find(|(_, glob)| {
+			let matches = if is_dir {
+				glob.matches_dir(path)
+			} else {
+				glob.matches_entry(path, false)
+			};
+
+			matches || glob.matches_parent(path)
+		})?;
+
+		if glob.negated {
+			return Some(false);
+		}
+
+		if is_dir
+			&& self.globs[index + 1..]
+				.iter()
+				.any(|glob| glob.negated && glob.matches_inside(path))
+		{
+			return Some(false);
+		}
+
+		Some(true)
+	}
+
+	pub fn matches_path(&self, path: &Path) -> bool {
+		self.evaluate(path, false) == Some(true)
 	}
+
+	/// Matches the given path or any of its parent directories
+	pub fn matches_path_with_dir(&self, path: &Path) -> bool {
+		path.ancestors()
+			.filter(|ancestor| !ancestor.as_os_str().is_empty())
+			.any(|ancestor| {
+				self.globs
+					.iter()
+					.rev()
+					.find(|glob| glob.matches_dir(ancestor))
+					.is_some_and(|glob| !glob.negated)
+			})
+	}
+}
+
+impl Serialize for GlobSet {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.globs.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for GlobSet {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(Self::new(Vec::deserialize(deserializer)?))
+	}
+}
+
+fn compile(pattern: &str) -> Result<Pattern, PatternError> {
+	#[cfg(target_os = "windows")]
+	let pattern = &pattern.replace('/', "\\");
+
+	Pattern::new(pattern)
+}
+
+/// Expands `{a,b}` alternatives, including nested ones, into separate patterns
+fn expand_braces(pattern: &str) -> Vec<String> {
+	let mut depth = 0;
+	let mut start = 0;
+	let mut bounds = Vec::new();
+	let mut in_class = false;
+
+	for (index, char) in pattern.char_indices() {
+		match char {
+			'[' if !in_class => in_class = true,
+			']' if in_class => in_class = false,
+			_ if in_class => {}
+			'{' => {
+				if depth == 0 {
+					start = index;
+					bounds = vec![index];
+				}
+
+				depth += 1;
+			}
+			',' if depth == 1 => bounds.push(index),
+			'}' if depth > 0 => {
+				depth -= 1;
+
+				if depth == 0 {
+					bounds.push(index);
+
+					let prefix = &pattern[..start];
+					let suffix = &pattern[index + 1..];
+
+					return bounds
+						.windows(2)
+						.flat_map(|bounds| {
+							let alternative = &pattern[bounds[0] + 1..bounds[1]];
+							expand_braces(&format!("{prefix}{alternative}{suffix}"))
+						})
+						.collect();
+				}
+			}
+			_ => {}
+		}
+	}
+
+	vec![pattern.to_owned()]
 }
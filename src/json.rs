@@ -0,0 +1,276 @@
+//! Lenient JSON reader that accepts JSONC and JSON5 syntax
+//! (comments, trailing commas, single quoted strings, unquoted
+//! keys, hexadecimal numbers) by converting it to strict JSON
+
+use anyhow::bail;
+use serde::de::{DeserializeOwned, Error};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::{ext::PathExt, vfs::Vfs};
+
+/// Deserializes lenient JSON, reported error lines
+/// match the lines of the original input
+pub fn from_str<T>(input: &str) -> serde_json::Result<T>
+where
+	T: DeserializeOwned,
+{
+	serde_json::from_str(&normalize(input)?)
+}
+
+/// Writes strict JSON to the file unless its current contents
+/// describe the same value, so their formatting is kept. Files with
+/// comments are never overwritten as the comments would be lost
+pub fn write(path: &Path, json: &[u8], vfs: &Vfs) -> anyhow::Result<()> {
+	if let Ok(current) = vfs.read_to_string(path) {
+		if from_str::<Value>(&current).ok() == serde_json::from_slice::<Value>(json).ok() {
+			return Ok(());
+		}
+
+		if has_comments(&current) {
+			bail!(
+				"File {} contains comments that would be lost, apply this change manually",
+				path.to_string()
+			);
+		}
+	}
+
+	vfs.write(path, json)?;
+
+	Ok(())
+}
+
+/// Checks whether lenient JSON contains any comments
+pub fn has_comments(input: &str) -> bool {
+	let mut chars = input.chars().peekable();
+	let mut quote = None;
+
+	while let Some(char) = chars.next() {
+		match quote {
+			Some(_) if char == '\\' => {
+				chars.next();
+			}
+			Some(end) if char == end => quote = None,
+			Some(_) => {}
+			None => match char {
+				'"' | '\'' => quote = Some(char),
+				'/' if matches!(chars.peek(), Some('/') | Some('*')) => return true,
+				_ => {}
+			},
+		}
+	}
+
+	false
+}
+
+/// Converts lenient JSON to strict JSON, line breaks are always
+/// preserved so errors still point at the right line
+pub fn normalize(input: &str) -> serde_json::Result<String> {
+	let chars: Vec<char> = input.trim_start_matches('\u{feff}').chars().collect();
+	let mut output = String::with_capacity(chars.len());
+	let mut index = 0;
+
+	while index < chars.len() {
+		let char = chars[index];
+		let next = chars.get(index + 1).copied();
+
+		match char {
+			'"' | '\'' => index = copy_string(&chars, index, &mut output),
+			'/' if next == Some('/') => {
+				while index < chars.len() && chars[index] != '\n' {
+					index += 1;
+				}
+			}
+			'/' if next == Some('*') => {
+				index += 2;
+
+				while index < chars.len() && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/')) {
+					output.push(if chars[index] == '\n' { '\n' } else { ' ' });
+					index += 1;
+				}
+
+				index += 2;
+			}
+			',' => {
+				if !matches!(next_significant(&chars, index + 1), Some('}') | Some(']')) {
+					output.push(',');
+				}
+
+				index += 1;
+			}
+			'+' if next.is_some_and(|next| next.is_ascii_digit() || next == '.') => index += 1,
+			'.' if next.is_some_and(|next| next.is_ascii_digit()) => {
+				output.push('0');
+				index = copy_number(&chars, index, &mut output);
+			}
+			char if char.is_ascii_digit() => index = copy_number(&chars, index, &mut output),
+			char if char.is_alphabetic() || char == '_' || char == '$' => {
+				let start = index;
+
+				while index < chars.len()
+					&& (chars[index].is_alphanumeric() || chars[index] == '_' || chars[index] == '$')
+				{
+					index += 1;
+				}
+
+				let word: String = chars[start..index].iter().collect();
+
+				if next_significant(&chars, index) == Some(':') {
+					// Unquoted object key
+					output.push('"');
+					output.push_str(&word);
+					output.push('"');
+				} else if matches!(word.as_str(), "Infinity" | "NaN") {
+					let line = output.matches('\n').count() + 1;
+
+					return Err(serde_json::Error::custom(format!(
+						"`{word}` can't be represented in JSON at line {line}"
+					)));
+				} else {
+					// Anything other than `true`, `false`
+					// and `null` is rejected by the parser
+					output.push_str(&word);
+				}
+			}
+			char => {
+				output.push(char);
+				index += 1;
+			}
+		}
+	}
+
+	Ok(output)
+}
+
+/// Copies single or double quoted string as a double
+/// quoted one and returns the index after its end
+fn copy_string(chars: &[char], mut index: usize, output: &mut String) -> usize {
+	let quote = chars[index];
+	let mut newlines = 0;
+
+	output.push('"');
+	index += 1;
+
+	while index < chars.len() {
+		let char = chars[index];
+
+		if char == '\\' {
+			match chars.get(index + 1) {
+				Some('\'') => output.push('\''),
+				// JSON5 line continuation
+				Some('\n') => newlines += 1,
+				Some('\r') => {
+					newlines += 1;
+
+					if chars.get(index + 2) == Some(&'\n') {
+						index += 1;
+					}
+				}
+				Some(escaped) => {
+					output.push('\\');
+					output.push(*escaped);
+				}
+				None => output.push('\\'),
+			}
+
+			index += 2;
+			continue;
+		}
+
+		index += 1;
+
+		if char == quote {
+			break;
+		}
+
+		match char {
+			'"' => output.push_str("\\\""),
+			char => output.push(char),
+		}
+	}
+
+	output.push('"');
+	output.extend(std::iter::repeat_n('\n', newlines));
+
+	index
+}
+
+/// Copies number literal converting hexadecimal
+/// numbers and trailing decimal points
+fn copy_number(chars: &[char], mut index: usize, output: &mut String) -> usize {
+	if chars[index] == '0' && matches!(chars.get(index + 1), Some('x') | Some('X')) {
+		let start = index + 2;
+		index = start;
+
+		while index < chars.len() && chars[index].is_ascii_hexdigit() {
+			index += 1;
+		}
+
+		let digits: String = chars[start..index].iter().collect();
+
+		match u64::from_str_radix(&digits, 16) {
+			Ok(number) => output.push_str(&number.to_string()),
+			Err(_) => output.push_str(&format!("0x{digits}")),
+		}
+
+		return index;
+	}
+
+	let mut last = ' ';
+
+	while index < chars.len() {
+		let char = chars[index];
+
+		let is_number = char.is_ascii_digit()
+			|| char == '.'
+			|| char == 'e'
+			|| char == 'E'
+			|| ((char == '+' || char == '-') && (last == 'e' || last == 'E'));
+
+		if !is_number {
+			break;
+		}
+
+		// `5.e3` is not valid in strict JSON
+		if (char == 'e' || char == 'E') && last == '.' {
+			output.push('0');
+		}
+
+		output.push(char);
+		last = char;
+		index += 1;
+	}
+
+	if last == '.' {
+		output.push('0');
+	}
+
+	index
+}
+
+/// Returns the next character that is not
+/// a whitespace or part of a comment
+fn next_significant(chars: &[char], mut index: usize) -> Option<char> {
+	while index < chars.len() {
+		match chars[index] {
+			char if char.is_whitespace() => index += 1,
+			'/' if chars.get(index + 1) == Some(&'/') => {
+				while index < chars.len() && chars[index] != '\n' {
+					index += 1;
+				}
+			}
+			'/' if chars.get(index + 1) == Some(&'*') => {
+				index += 2;
+
+				while index < chars.len() && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/')) {
+					index += 1;
+				}
+
+				index += 2;
+			}
+			char => return Some(char),
+		}
+	}
+
+	None
+}
@@ -2,6 +2,7 @@
 
 use rbx_dom_weak::{types::Variant, UstrMap};
 
+pub mod assets;
 pub mod cli;
 pub mod config;
 pub mod constants;
@@ -11,6 +12,7 @@ pub mod ext;
 pub mod glob;
 pub mod installer;
 pub mod integration;
+pub mod json;
 pub mod logger;
 pub mod middleware;
 pub mod program;
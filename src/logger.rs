@@ -1,7 +1,7 @@
 use colored::{Color, Colorize};
 use dialoguer::console::{style, Style, StyledObject};
 use dialoguer::theme::Theme;
-use dialoguer::Confirm;
+use dialoguer::{Confirm, Select};
 use env_logger::{Builder, WriteStyle};
 use log::{Level, LevelFilter};
 use std::fmt::{Display, Formatter};
@@ -104,6 +104,25 @@ pub fn prompt(prompt: &str, default: bool) -> bool {
 	result.unwrap_or(default)
 }
 
+pub fn select(prompt: &str, items: &[&str], default: usize) -> usize {
+	if util::env_yes() {
+		return default;
+	}
+
+	let theme = match util::env_log_style() {
+		WriteStyle::Always => PromptTheme::color(),
+		_ => PromptTheme::no_color(),
+	};
+
+	let result = Select::with_theme(&theme)
+		.with_prompt(prompt)
+		.items(items)
+		.default(default)
+		.interact();
+
+	result.unwrap_or(default)
+}
+
 pub struct Table {
 	rows: Vec<Vec<String>>,
 	columns: Vec<usize>,
@@ -203,6 +222,28 @@ impl Theme for PromptTheme {
 			}
 		}
 	}
+
+	fn format_select_prompt(&self, f: &mut dyn fmt::Write, prompt: &str) -> fmt::Result {
+		write!(f, "{}: {}", &self.prompt_prefix, self.prompt_style.apply_to(prompt))
+	}
+
+	fn format_select_prompt_selection(&self, f: &mut dyn fmt::Write, prompt: &str, selection: &str) -> fmt::Result {
+		write!(
+			f,
+			"{}: {} {} {}",
+			&self.prompt_prefix,
+			self.prompt_style.apply_to(prompt),
+			&self.prompt_suffix,
+			self.none_style.apply_to(selection)
+		)
+	}
+
+	fn format_select_prompt_item(&self, f: &mut dyn fmt::Write, text: &str, active: bool) -> fmt::Result {
+		match active {
+			true => write!(f, "{} {}", &self.prompt_suffix, self.none_style.apply_to(text)),
+			false => write!(f, "  {}", self.hint_style.apply_to(text)),
+		}
+	}
 }
 
 impl PromptTheme {
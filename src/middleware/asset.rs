@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Result};
+use log::warn;
+use rbx_dom_weak::{ustr, HashMapExt, UstrMap};
+use std::path::Path;
+
+use crate::{
+	assets::AssetKind,
+	core::{meta::Context, snapshot::Snapshot},
+	resolution::{AmbiguousValue, UnresolvedValue},
+	vfs::Vfs,
+	Properties,
+};
+
+/// Classes that can be created from asset files and their content properties
+const CONTENT_PROPERTIES: [(&str, &str); 6] = [
+	("Decal", "Texture"),
+	("Texture", "Texture"),
+	("ImageLabel", "Image"),
+	("ImageButton", "Image"),
+	("Sound", "SoundId"),
+	("AudioPlayer", "Asset"),
+];
+
+/// Reads image or audio file as an instance whose
+/// content ID is taken from the asset lock file
+#[profiling::function]
+pub fn read_asset(path: &Path, context: &Context, vfs: &Vfs) -> Result<Snapshot> {
+	let kind = AssetKind::from_path(path).ok_or_else(|| anyhow!("Unsupported asset type"))?;
+	let class = kind.default_class();
+
+	let mut properties = UstrMap::new();
+
+	let locked = match context.asset_lock() {
+		Some(lock) => lock.get_id(path, vfs)?,
+		None => None,
+	};
+
+	if let Some(id) = locked {
+		let property = content_property(class).unwrap();
+		let value = UnresolvedValue::Ambiguous(AmbiguousValue::String(id)).resolve(class, property)?;
+
+		properties.insert(ustr(property), value);
+	} else {
+		warn!(
+			"Asset {} has no content ID yet, run `argon assets` to upload it",
+			path.display()
+		);
+	}
+
+	Ok(Snapshot::new().with_class(class).with_properties(properties))
+}
+
+/// Asset files are never modified, the content ID is owned by
+/// the asset lock file so only the remaining properties are returned
+#[profiling::function]
+pub fn write_asset(snapshot: Snapshot) -> Result<Properties> {
+	let mut properties = snapshot.properties;
+
+	if let Some(property) = content_property(&snapshot.class) {
+		properties.remove(&ustr(property));
+		// Migrated `Content` variant of the property, e.g. `ImageContent`
+		properties.remove(&ustr(&format!("{property}Content")));
+	}
+
+	Ok(properties)
+}
+
+/// Moves the content ID to the right property
+/// after instance data changed the snapshot class
+pub fn retarget(snapshot: &mut Snapshot) {
+	let Some(target) = content_property(&snapshot.class) else {
+		return;
+	};
+
+	for (class, property) in CONTENT_PROPERTIES {
+		if property == target {
+			continue;
+		}
+
+		if let Some(value) = snapshot.properties.remove(&ustr(property)) {
+			let value = UnresolvedValue::from_variant(value, class, property).resolve(&snapshot.class, target);
+
+			match value {
+				Ok(value) => snapshot.add_property(target, value),
+				Err(err) => warn!("Failed to set content ID of {}: {}", snapshot.name, err),
+			}
+		}
+	}
+}
+
+fn content_property(class: &str) -> Option<&'static str> {
+	CONTENT_PROPERTIES
+		.iter()
+		.find(|(asset_class, _)| *asset_class == class)
+		.map(|(_, property)| *property)
+}
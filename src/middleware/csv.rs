@@ -1,12 +1,14 @@
 use anyhow::Result;
-use csv::{ReaderBuilder, WriterBuilder};
+use csv::{ReaderBuilder, Terminator, WriterBuilder};
 use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, path::Path};
 
 use crate::{core::snapshot::Snapshot, vfs::Vfs, Properties};
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+const COLUMNS: [&str; 4] = ["Key", "Source", "Context", "Example"];
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 struct LocalizationEntry {
 	key: Option<String>,
 	context: Option<String>,
@@ -15,6 +17,18 @@ struct LocalizationEntry {
 	values: HashMap<String, String>,
 }
 
+impl LocalizationEntry {
+	fn get(&self, column: &str) -> Option<&String> {
+		match column {
+			"Key" => self.key.as_ref(),
+			"Source" => self.source.as_ref(),
+			"Context" => self.context.as_ref(),
+			"Example" => self.example.as_ref(),
+			locale => self.values.get(locale),
+		}
+	}
+}
+
 #[profiling::function]
 pub fn read_csv(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 	let contents = vfs.read(path)?;
@@ -23,10 +37,99 @@ pub fn read_csv(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 		return Ok(Snapshot::new().with_class("LocalizationTable"));
 	}
 
+	let (_, entries) = parse_csv(&contents)?;
+	let contents = serde_json::to_string(&entries)?;
+
+	let mut properties = UstrMap::new();
+	properties.insert(ustr("Contents"), Variant::String(contents));
+
+	Ok(Snapshot::new()
+		.with_class("LocalizationTable")
+		.with_properties(properties))
+}
+
+#[profiling::function]
+pub fn write_csv(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	if let Some(Variant::String(contents)) = properties.remove(&ustr("Contents")) {
+		let entries: Vec<LocalizationEntry> = serde_json::from_str(&contents)?;
+
+		let existing = if vfs.exists(path) { vfs.read(path)? } else { Vec::new() };
+		let (headers, existing_entries) = parse_csv(&existing)?;
+
+		// Keep the file untouched so its formatting is preserved
+		if !existing.is_empty() && entries == existing_entries {
+			return Ok(properties);
+		}
+
+		// Preserve column order of the existing file, missing
+		// columns are appended, new locales in alphabetical order
+		let mut columns: Vec<String> = if headers.is_empty() {
+			COLUMNS.iter().map(|column| column.to_string()).collect()
+		} else {
+			headers
+		};
+
+		for column in COLUMNS {
+			if !columns.iter().any(|existing| existing == column)
+				&& entries.iter().any(|entry| entry.get(column).is_some())
+			{
+				columns.push(column.to_owned());
+			}
+		}
+
+		let mut locales: Vec<&String> = entries
+			.iter()
+			.flat_map(|entry| entry.values.keys())
+			.filter(|locale| !columns.contains(locale))
+			.collect();
+
+		locales.sort();
+		locales.dedup();
+
+		columns.extend(locales.into_iter().cloned());
+
+		let terminator = if existing.windows(2).any(|bytes| bytes == b"\r\n") {
+			Terminator::CRLF
+		} else {
+			Terminator::Any(b'\n')
+		};
+
+		let mut contents = Vec::new();
+
+		let mut writer = WriterBuilder::new()
+			.has_headers(true)
+			.terminator(terminator)
+			.from_writer(&mut contents);
+
+		writer.write_record(&columns)?;
+
+		for entry in entries {
+			let record = columns
+				.iter()
+				.map(|column| entry.get(column).map(|value| value.as_str()).unwrap_or_default());
+
+			writer.write_record(record)?;
+		}
+
+		writer.flush()?;
+		drop(writer);
+
+		vfs.write(path, &contents)?;
+	}
+
+	Ok(properties)
+}
+
+/// Returns header columns and entries of the CSV file
+fn parse_csv(contents: &[u8]) -> Result<(Vec<String>, Vec<LocalizationEntry>)> {
+	if contents.is_empty() {
+		return Ok((Vec::new(), Vec::new()));
+	}
+
 	let mut reader = ReaderBuilder::new()
 		.has_headers(true)
 		.flexible(true)
-		.from_reader(contents.as_slice());
+		.from_reader(contents);
 
 	let headers = reader.headers()?.clone();
 	let mut entries = Vec::new();
@@ -59,49 +162,5 @@ pub fn read_csv(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 		}
 	}
 
-	let contents = serde_json::to_string(&entries)?;
-
-	let mut properties = UstrMap::new();
-	properties.insert(ustr("Contents"), Variant::String(contents));
-
-	Ok(Snapshot::new()
-		.with_class("LocalizationTable")
-		.with_properties(properties))
-}
-
-#[profiling::function]
-pub fn write_csv(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
-	if let Some(Variant::String(contents)) = properties.remove(&ustr("Contents")) {
-		let entries: Vec<LocalizationEntry> = serde_json::from_str(&contents)?;
-		let mut contents = Vec::new();
-
-		let mut writer = WriterBuilder::new()
-			.has_headers(true)
-			.flexible(true)
-			.from_writer(&mut contents);
-
-		writer.write_record(["Key", "Source", "Context", "Example"])?;
-
-		for entry in entries {
-			let mut record = vec![
-				entry.key.unwrap_or_default(),
-				entry.source.unwrap_or_default(),
-				entry.context.unwrap_or_default(),
-				entry.example.unwrap_or_default(),
-			];
-
-			for value in entry.values.values() {
-				record.push(value.to_owned());
-			}
-
-			writer.write_record(&record)?;
-		}
-
-		writer.flush()?;
-		drop(writer);
-
-		vfs.write(path, &contents)?;
-	}
-
-	Ok(properties)
+	Ok((headers.iter().map(|header| header.to_owned()).collect(), entries))
 }
@@ -11,6 +11,7 @@ use std::{
 use crate::{
 	core::meta::Meta,
 	ext::PathExt,
+	json,
 	middleware::helpers,
 	resolution::UnresolvedValue,
 	util::{self, get_json_formatter},
@@ -53,7 +54,7 @@ pub fn read_data(path: &Path, class: Option<&str>, vfs: &Vfs) -> Result<DataSnap
 		return Ok(DataSnapshot::default());
 	}
 
-	let data: Data = serde_json::from_str(&data)?;
+	let data = parse_data(path, &data)?;
 
 	let mut properties = UstrMap::new();
 
@@ -182,11 +183,7 @@ pub fn write_data<'a>(
 		return Ok(None);
 	}
 
-	let mut writer = Vec::new();
-	let mut serializer = Serializer::with_formatter(&mut writer, get_json_formatter());
-
-	data.serialize(&mut serializer)?;
-	vfs.write(path, &writer)?;
+	save_data(path, &data, vfs)?;
 
 	Ok(Some(path))
 }
@@ -200,7 +197,7 @@ pub fn write_original_name(path: &Path, meta: &Meta, vfs: &Vfs) -> Result<()> {
 			return Ok(());
 		}
 
-		let data: Data = serde_json::from_str(&data)?;
+		let data = parse_data(path, &data)?;
 
 		if data.original_name == meta.original_name {
 			return Ok(());
@@ -232,11 +229,34 @@ pub fn write_original_name(path: &Path, meta: &Meta, vfs: &Vfs) -> Result<()> {
 		data
 	};
 
-	let mut writer = Vec::new();
-	let mut serializer = Serializer::with_formatter(&mut writer, get_json_formatter());
+	save_data(path, &data, vfs)?;
+
+	Ok(())
+}
 
-	data.serialize(&mut serializer)?;
-	vfs.write(path, &writer)?;
+/// Parses data file in the format matching its extension,
+/// `.toml`, `.yaml` or `.yml`, anything else is read as JSON
+fn parse_data(path: &Path, data: &str) -> Result<Data> {
+	Ok(match path.get_ext() {
+		"toml" => toml::from_str(data)?,
+		"yaml" | "yml" => serde_json::from_value(serde_json::to_value(helpers::parse_yaml(data)?)?)?,
+		_ => json::from_str(data)?,
+	})
+}
+
+fn save_data(path: &Path, data: &WritableData, vfs: &Vfs) -> Result<()> {
+	match path.get_ext() {
+		"toml" => vfs.write(path, toml::to_string_pretty(data)?.as_bytes())?,
+		"yaml" | "yml" => vfs.write(path, helpers::yaml_to_string(&serde_json::to_value(data)?).as_bytes())?,
+		_ => {
+			let mut writer = Vec::new();
+			let mut serializer = Serializer::with_formatter(&mut writer, get_json_formatter());
+
+			data.serialize(&mut serializer)?;
+
+			json::write(path, &writer, vfs)?;
+		}
+	}
 
 	Ok(())
 }
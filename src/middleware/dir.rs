@@ -1,10 +1,11 @@
 use anyhow::Result;
-use std::path::Path;
+use std::{borrow::Cow, path::Path};
 
 use super::new_snapshot;
 use crate::{
+	constants::IGNORE_FILE,
 	core::{
-		meta::{Context, Meta, Source},
+		meta::{Context, IgnoreRule, Meta, Source},
 		snapshot::Snapshot,
 	},
 	ext::PathExt,
@@ -19,8 +20,10 @@ pub fn read_dir(path: &Path, context: &Context, vfs: &Vfs) -> Result<Snapshot> {
 		.with_name(name)
 		.with_meta(Meta::new().with_context(context).with_source(Source::directory(path)));
 
+	let child_context = child_context(path, context, vfs)?;
+
 	for path in vfs.read_dir(path)? {
-		if let Some(child_snapshot) = new_snapshot(&path, context, vfs)? {
+		if let Some(child_snapshot) = new_snapshot(&path, &child_context, vfs)? {
 			snapshot.add_child(child_snapshot);
 		}
 	}
@@ -34,3 +37,20 @@ pub fn write_dir(path: &Path, vfs: &Vfs) -> Result<()> {
 
 	Ok(())
 }
+
+/// Returns context for children of the given directory,
+/// extended with rules from its `.argonignore` file if there is one
+pub fn child_context<'a>(path: &Path, context: &'a Context, vfs: &Vfs) -> Result<Cow<'a, Context>> {
+	let ignore_path = path.join(IGNORE_FILE);
+
+	if !vfs.is_file(&ignore_path) {
+		return Ok(Cow::Borrowed(context));
+	}
+
+	let rule = IgnoreRule::from_ignore_file(&vfs.read_to_string(&ignore_path)?, path);
+
+	let mut context = context.clone();
+	context.add_ignore_rule(rule);
+
+	Ok(Cow::Owned(context))
+}
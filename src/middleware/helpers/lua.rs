@@ -0,0 +1,585 @@
+use anyhow::{bail, Result};
+use rmpv::Value;
+use serde::{
+	ser::{SerializeMap, SerializeSeq},
+	Serialize, Serializer,
+};
+use std::{iter::Peekable, str::Chars};
+
+/// Literal Luau value that can be converted to other data formats
+#[derive(Debug, Clone, PartialEq)]
+pub enum LuaValue {
+	Nil,
+	Bool(bool),
+	Integer(i64),
+	Number(f64),
+	String(String),
+	Table(Vec<(Option<LuaValue>, LuaValue)>),
+}
+
+impl LuaValue {
+	/// Checks if the table contains only positional entries,
+	/// empty tables are not arrays as they are more often objects
+	pub fn is_array(&self) -> bool {
+		match self {
+			LuaValue::Table(entries) if entries.is_empty() => false,
+			LuaValue::Table(entries) => entries.iter().enumerate().all(|(index, (key, _))| match key {
+				None => true,
+				Some(LuaValue::Integer(key)) => *key == index as i64 + 1,
+				_ => false,
+			}),
+			_ => false,
+		}
+	}
+
+	/// Returns table entries with keys of positional entries assigned
+	/// the same way Luau does, counting only positional entries
+	pub fn entries(&self) -> Vec<(LuaValue, &LuaValue)> {
+		let mut position = 0;
+
+		match self {
+			LuaValue::Table(entries) => entries
+				.iter()
+				.map(|(key, value)| match key {
+					Some(key) => (key.clone(), value),
+					None => {
+						position += 1;
+						(LuaValue::Integer(position), value)
+					}
+				})
+				.collect(),
+			_ => Vec::new(),
+		}
+	}
+
+	pub fn to_key(&self) -> String {
+		match self {
+			LuaValue::Nil => String::from("nil"),
+			LuaValue::Bool(bool) => bool.to_string(),
+			LuaValue::Integer(int) => int.to_string(),
+			LuaValue::Number(num) => num.to_string(),
+			LuaValue::String(string) => string.to_owned(),
+			LuaValue::Table(_) => String::from("table"),
+		}
+	}
+
+	pub fn to_msgpack(&self) -> Value {
+		match self {
+			LuaValue::Nil => Value::Nil,
+			LuaValue::Bool(bool) => Value::Boolean(*bool),
+			LuaValue::Integer(int) => Value::from(*int),
+			LuaValue::Number(num) => Value::F64(*num),
+			LuaValue::String(string) => Value::from(string.as_str()),
+			LuaValue::Table(entries) => {
+				if self.is_array() {
+					Value::Array(entries.iter().map(|(_, value)| value.to_msgpack()).collect())
+				} else {
+					Value::Map(
+						self.entries()
+							.into_iter()
+							.map(|(key, value)| (key.to_msgpack(), value.to_msgpack()))
+							.collect(),
+					)
+				}
+			}
+		}
+	}
+}
+
+impl Serialize for LuaValue {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			LuaValue::Nil => serializer.serialize_none(),
+			LuaValue::Bool(bool) => serializer.serialize_bool(*bool),
+			LuaValue::Integer(int) => serializer.serialize_i64(*int),
+			LuaValue::Number(num) => serializer.serialize_f64(*num),
+			LuaValue::String(string) => serializer.serialize_str(string),
+			LuaValue::Table(entries) => {
+				if self.is_array() {
+					let mut seq = serializer.serialize_seq(Some(entries.len()))?;
+
+					for (_, value) in entries {
+						seq.serialize_element(value)?;
+					}
+
+					seq.end()
+				} else {
+					// Nil values can't be represented in some formats (like TOML)
+					// and they don't exist in Luau tables anyway
+					let entries = self
+						.entries()
+						.into_iter()
+						.filter(|(_, value)| **value != LuaValue::Nil)
+						.collect::<Vec<_>>();
+
+					let mut map = serializer.serialize_map(Some(entries.len()))?;
+
+					for (key, value) in entries {
+						map.serialize_entry(&key.to_key(), value)?;
+					}
+
+					map.end()
+				}
+			}
+		}
+	}
+}
+
+/// Parses Luau source that returns a single literal value,
+/// example: `return { foo = "bar", 1, 2, 3 }`
+pub fn parse(source: &str) -> Result<LuaValue> {
+	Parser::new(source).parse()
+}
+
+/// Checks whether Luau source contains any comments,
+/// sources that can't be parsed are assumed to have them
+pub fn has_comments(source: &str) -> bool {
+	let mut parser = Parser::new(source);
+
+	match parser.parse() {
+		Ok(_) => parser.comments > 0,
+		Err(_) => true,
+	}
+}
+
+struct Parser<'a> {
+	chars: Peekable<Chars<'a>>,
+	line: usize,
+	comments: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn new(source: &'a str) -> Self {
+		Self {
+			chars: source.chars().peekable(),
+			line: 1,
+			comments: 0,
+		}
+	}
+
+	fn parse(&mut self) -> Result<LuaValue> {
+		self.skip_trivia()?;
+
+		if self.eat_word("return") {
+			self.skip_trivia()?;
+		}
+
+		let value = self.parse_value()?;
+
+		self.skip_trivia()?;
+		self.eat(';');
+		self.skip_trivia()?;
+
+		if self.peek().is_some() {
+			self.error("expected end of file")?;
+		}
+
+		Ok(value)
+	}
+
+	fn error<T>(&mut self, message: &str) -> Result<T> {
+		match self.peek() {
+			Some(char) => bail!("{} at line {}, found `{}`", message, self.line, char),
+			None => bail!("{} at line {}, found end of file", message, self.line),
+		}
+	}
+
+	fn peek(&mut self) -> Option<char> {
+		self.chars.peek().copied()
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let char = self.chars.next();
+
+		if char == Some('\n') {
+			self.line += 1;
+		}
+
+		char
+	}
+
+	fn eat(&mut self, expected: char) -> bool {
+		if self.peek() == Some(expected) {
+			self.next();
+			true
+		} else {
+			false
+		}
+	}
+
+	fn eat_word(&mut self, word: &str) -> bool {
+		let mut lookahead = self.chars.clone();
+
+		for expected in word.chars() {
+			if lookahead.next() != Some(expected) {
+				return false;
+			}
+		}
+
+		if lookahead.peek().is_some_and(|char| is_identifier(*char)) {
+			return false;
+		}
+
+		for _ in word.chars() {
+			self.next();
+		}
+
+		true
+	}
+
+	fn skip_trivia(&mut self) -> Result<()> {
+		loop {
+			match self.peek() {
+				Some(char) if char.is_whitespace() => {
+					self.next();
+				}
+				Some('-') => {
+					let mut lookahead = self.chars.clone();
+					lookahead.next();
+
+					if lookahead.next() != Some('-') {
+						return Ok(());
+					}
+
+					self.next();
+					self.next();
+					self.comments += 1;
+
+					if self.peek() == Some('[') {
+						if let Some(level) = self.long_bracket_level() {
+							self.read_long_string(level)?;
+							continue;
+						}
+					}
+
+					while self.peek().is_some_and(|char| char != '\n') {
+						self.next();
+					}
+				}
+				_ => return Ok(()),
+			}
+		}
+	}
+
+	fn parse_value(&mut self) -> Result<LuaValue> {
+		match self.peek() {
+			Some('{') => self.parse_table(),
+			Some('"') | Some('\'') | Some('`') => Ok(LuaValue::String(self.parse_string()?)),
+			Some('[') => match self.long_bracket_level() {
+				Some(level) => Ok(LuaValue::String(self.read_long_string(level)?)),
+				None => self.error("expected value"),
+			},
+			Some('-') => {
+				self.next();
+				self.skip_trivia()?;
+
+				match self.parse_value()? {
+					LuaValue::Integer(int) => Ok(LuaValue::Integer(-int)),
+					LuaValue::Number(num) => Ok(LuaValue::Number(-num)),
+					_ => self.error("expected number after `-`"),
+				}
+			}
+			Some(char) if char.is_ascii_digit() || char == '.' => self.parse_number(),
+			Some(char) if is_identifier(char) => {
+				if self.eat_word("nil") {
+					Ok(LuaValue::Nil)
+				} else if self.eat_word("true") {
+					Ok(LuaValue::Bool(true))
+				} else if self.eat_word("false") {
+					Ok(LuaValue::Bool(false))
+				} else if self.eat_word("math") {
+					self.skip_trivia()?;
+
+					if self.eat('.') {
+						self.skip_trivia()?;
+
+						if self.eat_word("huge") {
+							return Ok(LuaValue::Number(f64::INFINITY));
+						} else if self.eat_word("pi") {
+							return Ok(LuaValue::Number(std::f64::consts::PI));
+						}
+					}
+
+					self.error("unsupported `math` member")
+				} else {
+					self.error("only literal values are supported")
+				}
+			}
+			_ => self.error("expected value"),
+		}
+	}
+
+	fn parse_table(&mut self) -> Result<LuaValue> {
+		let mut entries = Vec::new();
+
+		self.next();
+
+		loop {
+			self.skip_trivia()?;
+
+			if self.eat('}') {
+				break;
+			}
+
+			let key = if self.peek() == Some('[') && self.long_bracket_level().is_none() {
+				self.next();
+				self.skip_trivia()?;
+
+				let key = self.parse_value()?;
+
+				self.skip_trivia()?;
+
+				if !self.eat(']') {
+					self.error("expected `]`")?;
+				}
+
+				self.skip_trivia()?;
+
+				if !self.eat('=') {
+					self.error("expected `=`")?;
+				}
+
+				Some(key)
+			} else if self
+				.peek()
+				.is_some_and(|char| is_identifier(char) && !char.is_ascii_digit())
+			{
+				let mut lookahead = self.chars.clone();
+				let mut name = String::new();
+
+				while let Some(char) = lookahead.peek().copied() {
+					if !is_identifier(char) {
+						break;
+					}
+
+					name.push(char);
+					lookahead.next();
+				}
+
+				while lookahead.peek().is_some_and(|char| char.is_whitespace()) {
+					lookahead.next();
+				}
+
+				if lookahead.next() == Some('=') && lookahead.peek() != Some(&'=') {
+					for _ in name.chars() {
+						self.next();
+					}
+
+					self.skip_trivia()?;
+					self.next();
+
+					Some(LuaValue::String(name))
+				} else {
+					None
+				}
+			} else {
+				None
+			};
+
+			self.skip_trivia()?;
+
+			let value = self.parse_value()?;
+			entries.push((key, value));
+
+			self.skip_trivia()?;
+
+			if self.eat('}') {
+				break;
+			}
+
+			if !self.eat(',') && !self.eat(';') {
+				self.error("expected `,` or `}`")?;
+			}
+		}
+
+		Ok(LuaValue::Table(entries))
+	}
+
+	fn parse_number(&mut self) -> Result<LuaValue> {
+		let mut number = String::new();
+
+		while let Some(char) = self.peek() {
+			let is_exponent_sign = (char == '+' || char == '-')
+				&& number.ends_with(['e', 'E'])
+				&& !number.starts_with("0x")
+				&& !number.starts_with("0X");
+
+			if char.is_ascii_alphanumeric() || char == '.' || char == '_' || is_exponent_sign {
+				if char != '_' {
+					number.push(char);
+				}
+
+				self.next();
+			} else {
+				break;
+			}
+		}
+
+		let lowercase = number.to_lowercase();
+
+		let parsed = if let Some(hex) = lowercase.strip_prefix("0x") {
+			i64::from_str_radix(hex, 16).ok().map(LuaValue::Integer)
+		} else if let Some(binary) = lowercase.strip_prefix("0b") {
+			i64::from_str_radix(binary, 2).ok().map(LuaValue::Integer)
+		} else if lowercase.contains(['.', 'e']) {
+			lowercase.parse::<f64>().ok().map(LuaValue::Number)
+		} else {
+			lowercase
+				.parse::<i64>()
+				.ok()
+				.map(LuaValue::Integer)
+				.or_else(|| lowercase.parse::<f64>().ok().map(LuaValue::Number))
+		};
+
+		match parsed {
+			Some(value) => Ok(value),
+			None => bail!("malformed number `{}` at line {}", number, self.line),
+		}
+	}
+
+	fn parse_string(&mut self) -> Result<String> {
+		let quote = self.next().unwrap();
+		let mut string = String::new();
+
+		loop {
+			let char = match self.next() {
+				Some(char) => char,
+				None => bail!("unfinished string at line {}", self.line),
+			};
+
+			if char == quote {
+				break;
+			}
+
+			if quote == '`' && char == '{' {
+				bail!("string interpolation is not supported at line {}", self.line);
+			}
+
+			if char == '\n' && quote != '`' {
+				bail!("unfinished string at line {}", self.line - 1);
+			}
+
+			if char != '\\' {
+				string.push(char);
+				continue;
+			}
+
+			match self.next() {
+				Some('n') => string.push('\n'),
+				Some('t') => string.push('\t'),
+				Some('r') => string.push('\r'),
+				Some('a') => string.push('\u{07}'),
+				Some('b') => string.push('\u{08}'),
+				Some('f') => string.push('\u{0C}'),
+				Some('v') => string.push('\u{0B}'),
+				Some('\n') => string.push('\n'),
+				Some('z') => {
+					while self.peek().is_some_and(|char| char.is_whitespace()) {
+						self.next();
+					}
+				}
+				Some('x') => {
+					let hex: String = [self.next(), self.next()].iter().flatten().collect();
+
+					match u8::from_str_radix(&hex, 16) {
+						Ok(byte) => string.push(byte as char),
+						Err(_) => bail!("invalid hexadecimal escape sequence at line {}", self.line),
+					}
+				}
+				Some('u') => {
+					if !self.eat('{') {
+						bail!("invalid unicode escape sequence at line {}", self.line);
+					}
+
+					let mut hex = String::new();
+
+					while let Some(char) = self.next() {
+						if char == '}' {
+							break;
+						}
+
+						hex.push(char);
+					}
+
+					match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+						Some(char) => string.push(char),
+						None => bail!("invalid unicode escape sequence at line {}", self.line),
+					}
+				}
+				Some(char) if char.is_ascii_digit() => {
+					let mut digits = String::from(char);
+
+					while digits.len() < 3 && self.peek().is_some_and(|char| char.is_ascii_digit()) {
+						digits.push(self.next().unwrap());
+					}
+
+					match digits.parse::<u8>() {
+						Ok(byte) => string.push(byte as char),
+						Err(_) => bail!("invalid decimal escape sequence at line {}", self.line),
+					}
+				}
+				Some(char) => string.push(char),
+				None => bail!("unfinished string at line {}", self.line),
+			}
+		}
+
+		Ok(string)
+	}
+
+	/// Returns the level of long bracket (`[[`, `[=[`, etc.) if there is one
+	fn long_bracket_level(&mut self) -> Option<usize> {
+		let mut lookahead = self.chars.clone();
+		let mut level = 0;
+
+		if lookahead.next() != Some('[') {
+			return None;
+		}
+
+		while lookahead.peek() == Some(&'=') {
+			lookahead.next();
+			level += 1;
+		}
+
+		if lookahead.next() == Some('[') {
+			Some(level)
+		} else {
+			None
+		}
+	}
+
+	fn read_long_string(&mut self, level: usize) -> Result<String> {
+		for _ in 0..level + 2 {
+			self.next();
+		}
+
+		// First newline is skipped just like in Luau
+		if self.peek() == Some('\r') {
+			self.next();
+		}
+
+		if self.peek() == Some('\n') {
+			self.next();
+		}
+
+		let closing = format!("]{}]", "=".repeat(level));
+		let mut string = String::new();
+
+		loop {
+			match self.next() {
+				Some(char) => {
+					string.push(char);
+
+					if string.ends_with(&closing) {
+						string.truncate(string.len() - closing.len());
+						return Ok(string);
+					}
+				}
+				None => bail!("unfinished long string at line {}", self.line),
+			}
+		}
+	}
+}
+
+fn is_identifier(char: char) -> bool {
+	char.is_ascii_alphanumeric() || char == '_'
+}
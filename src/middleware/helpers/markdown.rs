@@ -1,108 +1,822 @@
-use markdown::{
-	Block::{self, Blockquote, CodeBlock, Header, Hr, OrderedList, Paragraph, Raw, UnorderedList},
-	ListItem,
-	Span::{self, Break, Code, Emphasis, Image, Link, Strong, Text},
-};
+//! Markdown to Roblox RichText conversion and back. Constructs that
+//! RichText can't display (links, images, code block languages) are
+//! stored in RichText comments so they survive the round trip
 
-const LIST_ELEMENT_PREFIX: &str = "<b>•</b>";
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::iter::Peekable;
+
+const MONOSPACE_FONT: &str = "rbxasset://fonts/families/RobotoMono.json";
+const HEADING_SIZES: [u32; 6] = [30, 26, 22, 20, 18, 16];
+
+const LIST_PREFIX: &str = "<b>•</b> ";
+const QUOTE_PREFIX: &str = "<font transparency=\"0.5\">┃</font> ";
+const RULE: &str = "<font transparency=\"0.5\">────────────────</font>";
+const INDENT: &str = "    ";
+
+/// RichText tags that can be used directly in Markdown as inline HTML
+const RICH_TEXT_TAGS: [&str; 12] = [
+	"b",
+	"i",
+	"u",
+	"s",
+	"br",
+	"font",
+	"stroke",
+	"mark",
+	"sc",
+	"smallcaps",
+	"uc",
+	"uppercase",
+];
+
+#[derive(Debug)]
+enum Block {
+	Heading(usize, String),
+	Paragraph(String),
+	Code(Option<String>, String),
+	Quote(Vec<Block>),
+	List(Option<usize>, Vec<Vec<Block>>),
+	Rule,
+}
+
+// Markdown to RichText
+
+pub fn parse(text: &str) -> String {
+	let parser = Parser::new_ext(text.trim_start_matches('\u{feff}'), Options::ENABLE_STRIKETHROUGH);
+
+	let mut converter = Converter {
+		events: parser.peekable(),
+		spans: 0,
+	};
+
+	render_blocks(&converter.blocks())
+}
+
+struct Converter<'a> {
+	events: Peekable<Parser<'a>>,
+	/// Number of `<span>` tags converted to font tags that are still open
+	spans: usize,
+}
+
+impl Converter<'_> {
+	/// Collects blocks until the end of the current container
+	fn blocks(&mut self) -> Vec<Block> {
+		let mut blocks = Vec::new();
+
+		loop {
+			match self.events.peek() {
+				None => break,
+				Some(Event::End(_)) => {
+					self.events.next();
+					break;
+				}
+				// Content of tight list items is not wrapped in paragraphs
+				Some(event) if !is_block(event) => {
+					blocks.push(Block::Paragraph(self.inline()));
+					continue;
+				}
+				_ => {}
+			}
+
+			let block = match self.events.next().unwrap() {
+				Event::Start(Tag::Paragraph) => Block::Paragraph(self.inline_block()),
+				Event::Start(Tag::Heading { level, .. }) => Block::Heading(level as usize, self.inline_block()),
+				Event::Start(Tag::BlockQuote(_)) => Block::Quote(self.blocks()),
+				Event::Start(Tag::CodeBlock(kind)) => {
+					let language = match kind {
+						CodeBlockKind::Fenced(info) => {
+							info.split_whitespace().next().map(|language| language.to_owned())
+						}
+						CodeBlockKind::Indented => None,
+					};
+
+					let code = self.text();
+
+					Block::Code(language, code.strip_suffix('\n').unwrap_or(&code).to_owned())
+				}
+				Event::Start(Tag::List(start)) => {
+					let mut items = Vec::new();
+
+					while let Some(Event::Start(Tag::Item)) = self.events.next() {
+						items.push(self.blocks());
+					}
+
+					Block::List(start.map(|start| start as usize), items)
+				}
+				Event::Start(Tag::HtmlBlock) => {
+					let html = self.text();
+					Block::Paragraph(self.html(html.trim_end()))
+				}
+				Event::Rule => Block::Rule,
+				Event::Start(_) => {
+					blocks.extend(self.blocks());
+					continue;
+				}
+				_ => continue,
+			};
+
+			blocks.push(block);
+		}
+
+		blocks
+	}
+
+	/// Renders inline content of a paragraph or heading and skips its end
+	fn inline_block(&mut self) -> String {
+		let text = self.inline();
+		self.events.next();
+
+		text
+	}
+
+	fn inline(&mut self) -> String {
+		let mut output = String::new();
+
+		while self.events.peek().is_some_and(|event| !is_block(event)) {
+			match self.events.next().unwrap() {
+				Event::Text(text) => output.push_str(&escape(&text)),
+				Event::Code(code) => {
+					output.push_str(&format!("<font family=\"{MONOSPACE_FONT}\">{}</font>", escape(&code)))
+				}
+				Event::InlineHtml(html) | Event::Html(html) => output.push_str(&self.html(&html)),
+				Event::SoftBreak => output.push(' '),
+				Event::HardBreak => output.push('\n'),
+				Event::Start(Tag::Emphasis) => output.push_str("<i>"),
+				Event::End(TagEnd::Emphasis) => output.push_str("</i>"),
+				Event::Start(Tag::Strong) => output.push_str("<b>"),
+				Event::End(TagEnd::Strong) => output.push_str("</b>"),
+				Event::Start(Tag::Strikethrough) => output.push_str("<s>"),
+				Event::End(TagEnd::Strikethrough) => output.push_str("</s>"),
+				Event::Start(Tag::Link { dest_url, title, .. }) => {
+					output.push_str(&format!("<!--link{}--><u>", destination(&dest_url, &title)));
+				}
+				Event::End(TagEnd::Link) => output.push_str("</u><!--/link-->"),
+				Event::Start(Tag::Image { dest_url, title, .. }) => {
+					let alt = self.text();
+					output.push_str(&format!("<!--![{alt}]{}-->", destination(&dest_url, &title)));
+				}
+				_ => {}
+			}
+		}
+
+		output
+	}
+
+	/// Returns plain text of the current element and skips its end
+	fn text(&mut self) -> String {
+		let mut text = String::new();
+		let mut depth = 0;
+
+		for event in self.events.by_ref() {
+			match event {
+				Event::Text(string) | Event::Code(string) | Event::Html(string) => text.push_str(&string),
+				Event::Start(_) => depth += 1,
+				Event::End(_) if depth == 0 => break,
+				Event::End(_) => depth -= 1,
+				_ => {}
+			}
+		}
+
+		text
+	}
+
+	/// Converts raw HTML, RichText tags and comments are passed through,
+	/// `<span style="color: ...">` becomes a font tag, anything else is text
+	fn html(&mut self, html: &str) -> String {
+		let mut output = String::new();
+		let mut rest = html;
+
+		while let Some(start) = rest.find('<') {
+			output.push_str(&escape(&unescape(&rest[..start])));
+			rest = &rest[start..];
+
+			let end = if rest.starts_with("<!--") {
+				rest.find("-->").map(|end| end + 3)
+			} else {
+				rest.find('>').map(|end| end + 1)
+			};
+
+			match end {
+				Some(end) => {
+					output.push_str(&self.tag(&rest[..end]));
+					rest = &rest[end..];
+				}
+				None => break,
+			}
+		}
+
+		output.push_str(&escape(&unescape(rest)));
+
+		output
+	}
+
+	fn tag(&mut self, tag: &str) -> String {
+		if tag.starts_with("<!--") {
+			return tag.to_owned();
+		}
+
+		let inner = &tag[1..tag.len() - 1];
+		let closing = inner.starts_with('/');
+		let name = inner
+			.trim_start_matches('/')
+			.split(|char: char| char.is_whitespace() || char == '/')
+			.next()
+			.unwrap_or_default()
+			.to_lowercase();
+
+		if RICH_TEXT_TAGS.contains(&name.as_str()) {
+			return tag.to_owned();
+		}
+
+		if name == "span" {
+			if closing && self.spans > 0 {
+				self.spans -= 1;
+				return String::from("</font>");
+			}
+
+			let color = inner
+				.split_once("color")
+				.map(|(_, rest)| rest.trim_start_matches([':', ' ', '=']))
+				.map(|rest| rest.split([';', '"', '\'']).next().unwrap_or_default().trim());
+
+			if let Some(color) = color.filter(|color| !color.is_empty()) {
+				self.spans += 1;
+				return format!("<font color=\"{color}\">");
+			}
+		}
+
+		escape(tag)
+	}
+}
+
+/// Checks if the event starts or ends a block,
+/// everything else is rendered by `Converter::inline`
+fn is_block(event: &Event) -> bool {
+	match event {
+		Event::Start(tag) => !matches!(
+			tag,
+			Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+		),
+		Event::End(tag) => !matches!(
+			tag,
+			TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image
+		),
+		Event::Rule => true,
+		_ => false,
+	}
+}
+
+/// Formats link destination the way it is written in Markdown, e.g. `(url "title")`
+fn destination(url: &str, title: &str) -> String {
+	let url = if url.is_empty() || url.contains(|char: char| char.is_whitespace() || char == '(' || char == ')') {
+		format!("<{url}>")
+	} else {
+		url.to_owned()
+	};
+
+	if title.is_empty() {
+		format!("({url})")
+	} else {
+		format!("({url} \"{}\")", title.replace('"', "\\\""))
+	}
+}
+
+fn render_blocks(blocks: &[Block]) -> String {
+	blocks.iter().map(render_block).collect::<Vec<String>>().join("\n\n")
+}
+
+fn render_block(block: &Block) -> String {
+	match block {
+		Block::Heading(level, text) => format!("<font size=\"{}\"><b>{text}</b></font>", HEADING_SIZES[level - 1]),
+		Block::Paragraph(text) => text.to_owned(),
+		Block::Code(language, code) => format!(
+			"{}<font family=\"{MONOSPACE_FONT}\">{}</font>",
+			language
+				.as_ref()
+				.map(|language| format!("<!--code:{language}-->"))
+				.unwrap_or_default(),
+			escape(code)
+		),
+		Block::Quote(blocks) => render_blocks(blocks)
+			.split('\n')
+			.map(|line| format!("{QUOTE_PREFIX}{line}").trim_end().to_owned())
+			.collect::<Vec<String>>()
+			.join("\n"),
+		Block::List(start, items) => items
+			.iter()
+			.enumerate()
+			.map(|(index, item)| {
+				let marker = match start {
+					Some(start) => format!("{}. ", start + index),
+					None => LIST_PREFIX.to_owned(),
+				};
+
+				let content = item.iter().map(render_block).collect::<Vec<String>>().join("\n");
+				let mut lines = content.split('\n');
+
+				let mut rendered = format!("{marker}{}", lines.next().unwrap_or_default());
+
+				for line in lines {
+					rendered.push('\n');
+
+					if !line.is_empty() {
+						rendered.push_str(INDENT);
+						rendered.push_str(line);
+					}
+				}
+
+				rendered.trim_end().to_owned()
+			})
+			.collect::<Vec<String>>()
+			.join("\n"),
+		Block::Rule => RULE.to_owned(),
+	}
+}
 
 fn escape(text: &str) -> String {
-	text.replace("&", "&amp;")
-		.replace("<", "&lt;")
-		.replace("\"", "&quot;")
-		.replace("'", "&#8217;")
-		.replace(">", "&gt;")
-}
-
-fn format_spans(elements: &[Span]) -> String {
-	let mut string = String::new();
-
-	for element in elements.iter() {
-		string.push_str(&match *element {
-			Text(ref text) => escape(text),
-			Strong(ref content) => format!("<b>{}</b>", format_spans(content)),
-			Emphasis(ref content) => format!("<i>{}</i>", format_spans(content)),
-			Code(ref text) => format!(
-				"<font family='rbxasset://fonts/families/RobotoMono.json'>{}</font>",
-				&escape(text)
-			),
-			Link(ref text, _, _) => escape(text),
-			Image(_, _, _) => String::new(),
-			Break => String::from("\n"),
-		})
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&apos;")
+}
+
+// RichText to Markdown
+
+pub fn unparse(text: &str) -> String {
+	let markdown = blocks_to_markdown(&text.replace("\r\n", "\n"));
+
+	if markdown.trim().is_empty() {
+		return String::new();
 	}
 
-	string
+	format!("{}\n", markdown.trim_end())
 }
 
-fn format_list(elements: &[ListItem], ordered: bool) -> String {
-	let mut string = String::new();
+fn blocks_to_markdown(text: &str) -> String {
+	let mut markdown = Vec::new();
+	let mut lists = 0;
 
-	for (index, item) in elements.iter().enumerate() {
-		let prefix = if ordered {
-			format!("{}.", index + 1)
-		} else {
-			String::from(LIST_ELEMENT_PREFIX)
-		};
+	for block in split_blocks(text) {
+		if parse_rich_list_marker(block).is_none() {
+			lists = 0;
+			markdown.push(block_to_markdown(block));
+			continue;
+		}
 
-		string.push_str(&format!(
-			"{prefix} {}\n",
-			match *item {
-				ListItem::Simple(ref elements) => format_spans(elements),
-				ListItem::Paragraph(ref elements) => walk(elements),
+		// Adjacent lists with the same markers would be merged into one
+		markdown.push(list_to_markdown(block.trim_matches('\n'), lists % 2 == 1));
+		lists += 1;
+	}
+
+	markdown.join("\n\n")
+}
+
+/// Splits RichText on empty lines that are not inside monospace font
+fn split_blocks(text: &str) -> Vec<&str> {
+	let mut blocks = Vec::new();
+	let mut in_code = false;
+	let mut start = 0;
+	let mut index = 0;
+
+	while index < text.len() {
+		let rest = &text[index..];
+
+		if rest.starts_with("<font") {
+			if rest[..rest.find('>').unwrap_or(rest.len())].contains("RobotoMono") {
+				in_code = true;
 			}
-		))
+		} else if in_code && rest.starts_with("</font>") {
+			in_code = false;
+		} else if !in_code && rest.starts_with("\n\n") {
+			blocks.push(&text[start..index]);
+
+			index += rest.len() - rest.trim_start_matches('\n').len();
+			start = index;
+
+			continue;
+		}
+
+		index += rest.chars().next().map(char::len_utf8).unwrap_or(1);
 	}
 
-	format!("{string}\n")
+	blocks.push(&text[start..]);
+	blocks.retain(|block| !block.trim().is_empty());
+
+	blocks
 }
 
-fn format_header(elements: &[Span], _level: usize) -> String {
-	format!("<b>{}</b>\n\n", format_spans(elements),)
+fn block_to_markdown(block: &str) -> String {
+	let block = block.trim_matches('\n');
+
+	if let Some((language, code)) = parse_code_block(block) {
+		let mut fence = String::from("```");
+
+		while code.contains(&fence) {
+			fence.push('`');
+		}
+
+		return format!("{fence}{language}\n{code}\n{fence}");
+	}
+
+	if block == RULE {
+		return String::from("---");
+	}
+
+	if let Some((level, text)) = parse_rich_heading(block) {
+		return format!("{} {}", "#".repeat(level), inline_to_markdown(text));
+	}
+
+	let quote_prefix = QUOTE_PREFIX.trim_end();
+
+	if block.lines().all(|line| line.starts_with(quote_prefix)) {
+		let inner = block
+			.lines()
+			.map(|line| {
+				let line = &line[quote_prefix.len()..];
+				line.strip_prefix(' ').unwrap_or(line)
+			})
+			.collect::<Vec<&str>>()
+			.join("\n");
+
+		return blocks_to_markdown(&inner)
+			.split('\n')
+			.map(|line| {
+				if line.is_empty() {
+					String::from(">")
+				} else {
+					format!("> {line}")
+				}
+			})
+			.collect::<Vec<String>>()
+			.join("\n");
+	}
+
+	if parse_rich_list_marker(block).is_some() {
+		return list_to_markdown(block, false);
+	}
+
+	inline_to_markdown(block)
+		.split('\n')
+		.map(escape_line_start)
+		.collect::<Vec<String>>()
+		.join("\n")
 }
 
-fn format_paragraph(elements: &[Span]) -> String {
-	format!("{}\n\n", format_spans(elements))
+/// Converts list, alternative markers are `*` and `1)` instead of `-` and `1.`
+fn list_to_markdown(block: &str, alternative: bool) -> String {
+	let mut items: Vec<(String, Vec<&str>)> = Vec::new();
+
+	for line in block.lines() {
+		if let Some((marker, text)) = parse_rich_list_marker(line) {
+			let marker = match marker.as_str() {
+				"- " if alternative => String::from("* "),
+				_ if alternative => marker.replace(". ", ") "),
+				_ => marker,
+			};
+
+			items.push((marker, vec![text]));
+			continue;
+		}
+
+		let line = line.strip_prefix(INDENT).unwrap_or(line);
+
+		match items.last_mut() {
+			Some((_, lines)) => lines.push(line),
+			None => items.push((String::new(), vec![line])),
+		}
+	}
+
+	items
+		.iter()
+		.map(|(marker, lines)| {
+			let indent = " ".repeat(marker.len());
+
+			item_to_markdown(lines)
+				.split('\n')
+				.enumerate()
+				.map(|(index, line)| {
+					if index == 0 {
+						format!("{marker}{line}")
+					} else if line.is_empty() {
+						String::new()
+					} else {
+						format!("{indent}{line}")
+					}
+				})
+				.collect::<Vec<String>>()
+				.join("\n")
+		})
+		.collect::<Vec<String>>()
+		.join("\n")
 }
 
-fn format_blockquote(elements: &[Block]) -> String {
-	format!("<i>{}</i>\n\n", walk(elements))
+/// Converts content of a single list item, every line is a separate
+/// block except for nested lists, multi-line code blocks and quotes
+fn item_to_markdown(lines: &[&str]) -> String {
+	let quote_prefix = QUOTE_PREFIX.trim_end();
+	let mut markdown = String::new();
+	let mut index = 0;
+
+	while index < lines.len() {
+		let line = lines[index];
+		let mut end = index + 1;
+
+		let marker = parse_rich_list_marker(line).map(|(marker, _)| marker);
+
+		if marker.is_some() {
+			while end < lines.len()
+				&& (lines[end].is_empty()
+					|| lines[end].starts_with(INDENT)
+					|| parse_rich_list_marker(lines[end]).is_some())
+			{
+				end += 1;
+			}
+		} else if opens_code(line) {
+			while end < lines.len() && !lines[end - 1].contains("</font>") {
+				end += 1;
+			}
+		} else if line.starts_with(quote_prefix) {
+			while end < lines.len() && lines[end].starts_with(quote_prefix) {
+				end += 1;
+			}
+		}
+
+		let chunk = lines[index..end].join("\n");
+
+		if !markdown.is_empty() {
+			// Lists that can interrupt a paragraph keep the item tight
+			match marker.as_deref() {
+				Some("- ") | Some("1. ") => markdown.push('\n'),
+				_ => markdown.push_str("\n\n"),
+			}
+		}
+
+		match marker {
+			Some(_) => markdown.push_str(&list_to_markdown(&chunk, false)),
+			None => markdown.push_str(&block_to_markdown(&chunk)),
+		}
+
+		index = end;
+	}
+
+	markdown
 }
 
-fn format_codeblock(_lang: &Option<String>, elements: &str) -> String {
-	format!(
-		"<font family='rbxasset://fonts/families/RobotoMono.json'>{}</font>\n\n",
-		&escape(elements)
-	)
+/// Checks if the line opens monospace font that is closed on one of the next lines
+fn opens_code(line: &str) -> bool {
+	match line.rfind("<font") {
+		Some(start) => {
+			let rest = &line[start..];
+			rest[..rest.find('>').unwrap_or(rest.len())].contains("RobotoMono") && !rest.contains("</font>")
+		}
+		None => false,
+	}
 }
 
-fn format_unordered_list(elements: &[ListItem]) -> String {
-	format_list(elements, false)
+fn parse_code_block(block: &str) -> Option<(&str, String)> {
+	let (language, rest) = match block.strip_prefix("<!--code:") {
+		Some(rest) => {
+			let end = rest.find("-->")?;
+			(&rest[..end], &rest[end + 3..])
+		}
+		None => ("", block),
+	};
+
+	let tag_end = rest.find('>')?;
+
+	if !rest.starts_with("<font") || !rest[..tag_end].contains("RobotoMono") {
+		return None;
+	}
+
+	let code = rest[tag_end + 1..].strip_suffix("</font>")?;
+
+	if code.contains("</font>") {
+		return None;
+	}
+
+	Some((language, unescape(code)))
 }
 
-fn format_ordered_list(elements: &[ListItem]) -> String {
-	format_list(elements, true)
+fn parse_rich_heading(block: &str) -> Option<(usize, &str)> {
+	let rest = block.strip_prefix("<font size=\"")?;
+	let (size, rest) = rest.split_once("\">")?;
+	let text = rest.strip_prefix("<b>")?.strip_suffix("</b></font>")?;
+
+	if text.contains('\n') || text.contains("</font>") {
+		return None;
+	}
+
+	let level = HEADING_SIZES.iter().position(|heading| heading.to_string() == size)?;
+
+	Some((level + 1, text))
 }
 
-fn walk(blocks: &[Block]) -> String {
-	let mut string = String::new();
+fn parse_rich_list_marker(line: &str) -> Option<(String, &str)> {
+	if let Some(rest) = line.strip_prefix(LIST_PREFIX) {
+		return Some((String::from("- "), rest));
+	}
 
-	for block in blocks.iter() {
-		string.push_str(&match *block {
-			Header(ref elements, level) => format_header(elements, level),
-			Paragraph(ref elements) => format_paragraph(elements),
-			Blockquote(ref elements) => format_blockquote(elements),
-			CodeBlock(ref lang, ref elements) => format_codeblock(lang, elements),
-			UnorderedList(ref elements) => format_unordered_list(elements),
-			OrderedList(ref elements, _) => format_ordered_list(elements),
-			Raw(ref elements) => elements.to_owned(),
-			Hr => String::from("\n\n"),
-		})
+	let digits = line.chars().take_while(|char| char.is_ascii_digit()).count();
+
+	if digits > 0 && digits <= 9 {
+		if let Some(rest) = line[digits..].strip_prefix(". ") {
+			return Some((format!("{}. ", &line[..digits]), rest));
+		}
 	}
 
-	string.trim().to_owned()
+	None
 }
 
-pub fn parse(text: &str) -> String {
-	walk(&markdown::tokenize(text))
+fn inline_to_markdown(text: &str) -> String {
+	let mut markdown = String::new();
+	let mut tags: Vec<(String, String)> = Vec::new();
+	let mut links = Vec::new();
+	let mut is_link_start = false;
+	let mut rest = text;
+
+	while let Some(start) = rest.find('<') {
+		markdown.push_str(&escape_markdown(&unescape(&rest[..start])));
+		rest = &rest[start..];
+
+		if let Some(comment) = rest.strip_prefix("<!--") {
+			if let Some(end) = comment.find("-->") {
+				let content = &comment[..end];
+				rest = &comment[end + 3..];
+
+				if content.starts_with("![") {
+					markdown.push_str(content);
+				} else if let Some(destination) = content.strip_prefix("link") {
+					markdown.push('[');
+					links.push(destination);
+					is_link_start = true;
+				} else if content == "/link" {
+					markdown.push(']');
+					markdown.push_str(links.pop().unwrap_or("()"));
+				} else {
+					markdown.push_str(&format!("<!--{content}-->"));
+				}
+
+				continue;
+			}
+		}
+
+		let end = match rest.find('>') {
+			Some(end) => end,
+			None => {
+				markdown.push_str(&escape_markdown(rest));
+				rest = "";
+				break;
+			}
+		};
+
+		let raw = &rest[..end + 1];
+		let tag = &rest[1..end];
+		rest = &rest[end + 1..];
+
+		if let Some(name) = tag.strip_prefix('/') {
+			let name = name.trim().to_lowercase();
+
+			if let Some(index) = tags.iter().rposition(|(open, _)| *open == name) {
+				let (_, closing) = tags.remove(index);
+				markdown.push_str(&closing);
+			}
+
+			continue;
+		}
+
+		let name = tag
+			.split(|char: char| char.is_whitespace() || char == '/')
+			.next()
+			.unwrap_or_default()
+			.to_lowercase();
+
+		let closing = match name.as_str() {
+			"b" => "**",
+			"i" => "*",
+			"s" => "~~",
+			"u" if is_link_start => "",
+			// Hard breaks are rendered as new lines,
+			// so this tag can only come from inline HTML
+			"br" => {
+				markdown.push_str(raw);
+				continue;
+			}
+			"font" if tag.contains("RobotoMono") => {
+				let end = rest.find("</font>").unwrap_or(rest.len());
+				let code = unescape(&rest[..end]);
+				rest = rest.get(end + 7..).unwrap_or_default();
+
+				markdown.push_str(&code_span(&code));
+
+				continue;
+			}
+			_ => {
+				markdown.push_str(raw);
+
+				if !tag.ends_with('/') {
+					tags.push((name.clone(), format!("</{name}>")));
+				}
+
+				continue;
+			}
+		};
+
+		is_link_start = false;
+
+		// Delimiter right after the same one would extend it instead of opening a new one
+		let closing = if markdown.ends_with('*') && closing.starts_with('*') {
+			closing.replace('*', "_")
+		} else {
+			closing.to_owned()
+		};
+
+		markdown.push_str(&closing);
+		tags.push((name, closing));
+	}
+
+	markdown.push_str(&escape_markdown(&unescape(rest)));
+
+	markdown
+}
+
+fn code_span(code: &str) -> String {
+	let mut longest = 0;
+	let mut current = 0;
+
+	for char in code.chars() {
+		if char == '`' {
+			current += 1;
+			longest = longest.max(current);
+		} else {
+			current = 0;
+		}
+	}
+
+	let fence = "`".repeat(longest + 1);
+
+	let padding = if code.starts_with('`') || code.ends_with('`') || (code.starts_with(' ') && code.ends_with(' ')) {
+		" "
+	} else {
+		""
+	};
+
+	format!("{fence}{padding}{code}{padding}{fence}")
+}
+
+fn escape_markdown(text: &str) -> String {
+	let chars = text.chars().collect::<Vec<char>>();
+	let mut escaped = String::with_capacity(text.len());
+
+	for (index, char) in chars.iter().enumerate() {
+		let before = index.checked_sub(1).map(|index| chars[index]);
+		let after = chars.get(index + 1);
+
+		let needs_escape = match char {
+			'\\' | '*' | '`' | '[' | ']' | '<' => true,
+			'_' => {
+				!before.is_some_and(|before| before.is_alphanumeric())
+					|| !after.is_some_and(|after| after.is_alphanumeric())
+			}
+			'~' => true,
+			'&' => after.is_some_and(|after| after.is_ascii_alphanumeric() || *after == '#'),
+			'\n' => {
+				escaped.push_str("\\\n");
+				continue;
+			}
+			_ => false,
+		};
+
+		if needs_escape {
+			escaped.push('\\');
+		}
+
+		escaped.push(*char);
+	}
+
+	escaped
+}
+
+/// Escapes characters that would start a block when placed at the line start
+fn escape_line_start(line: &str) -> String {
+	if line.starts_with(['#', '>', '-', '+', '=']) {
+		return format!("\\{line}");
+	}
+
+	let digits = line.chars().take_while(|char| char.is_ascii_digit()).count();
+
+	if digits > 0 && line[digits..].starts_with(['.', ')']) {
+		return format!("{}\\{}", &line[..digits], &line[digits..]);
+	}
+
+	line.to_owned()
+}
+
+fn unescape(text: &str) -> String {
+	text.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&quot;", "\"")
+		.replace("&#8217;", "'")
+		.replace("&apos;", "'")
+		.replace("&amp;", "&")
 }
@@ -1,13 +1,21 @@
-use rbx_dom_weak::{types::Ref, WeakDom};
+use anyhow::Result;
+use rbx_dom_weak::{
+	types::{Ref, Variant},
+	ustr, WeakDom,
+};
 
 use crate::{
 	core::{helpers::apply_migrations, snapshot::Snapshot},
 	Properties,
 };
 
+pub use self::lua::LuaValue;
+
+mod lua;
 mod markdown;
 mod mesh_part;
 mod snapshot;
+mod yaml;
 
 #[inline]
 pub fn save_mesh(properties: &Properties) -> Option<String> {
@@ -22,7 +30,46 @@ pub fn snapshot_from_dom(dom: WeakDom, id: Ref) -> Snapshot {
 	snapshot::snapshot_from_dom(dom, id)
 }
 
+#[inline]
+pub fn dom_from_snapshot(snapshot: Snapshot) -> (WeakDom, Ref) {
+	snapshot::dom_from_snapshot(snapshot)
+}
+
 #[inline]
 pub fn markdown_to_rich_text(text: &str) -> String {
 	markdown::parse(text)
 }
+
+#[inline]
+pub fn rich_text_to_markdown(text: &str) -> String {
+	markdown::unparse(text)
+}
+
+#[inline]
+pub fn parse_lua(source: &str) -> Result<LuaValue> {
+	lua::parse(source)
+}
+
+#[inline]
+pub fn lua_has_comments(source: &str) -> bool {
+	lua::has_comments(source)
+}
+
+#[inline]
+pub fn parse_yaml(yaml: &str) -> Result<LuaValue> {
+	yaml::parse(yaml)
+}
+
+#[inline]
+pub fn yaml_to_string(value: &serde_json::Value) -> String {
+	yaml::unparse(value)
+}
+
+/// Removes `Source` property and parses it as a literal
+/// Luau value, returns `None` if the source is empty
+pub fn take_lua_source(properties: &mut Properties) -> Result<Option<LuaValue>> {
+	match properties.remove(&ustr("Source")) {
+		Some(Variant::String(source)) if !source.trim().is_empty() => lua::parse(&source).map(Some),
+		_ => Ok(None),
+	}
+}
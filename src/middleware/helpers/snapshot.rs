@@ -1,22 +1,61 @@
-use rbx_dom_weak::{types::Ref, AHashMap, Instance, WeakDom};
+use rbx_dom_weak::{
+	types::{Ref, Variant},
+	AHashMap, Instance, InstanceBuilder, Ustr, WeakDom,
+};
+use std::collections::{HashMap, HashSet};
 
-use crate::core::{meta::Meta, snapshot::Snapshot};
+use crate::{
+	core::{helpers::refs, meta::Meta, snapshot::Snapshot},
+	resolution::is_ref_property,
+};
 
 // Based on Rojo's InstanceSnapshot::from_tree (https://github.com/rojo-rbx/rojo/blob/master/src/snapshot/instance_snapshot.rs#L105)
 pub fn snapshot_from_dom(dom: WeakDom, id: Ref) -> Snapshot {
+	// Refs are only valid within the given DOM so we store
+	// them as relative instance paths that are resolved later
+	let mut ref_paths: HashMap<Ref, Vec<(Ustr, String)>> = HashMap::new();
+	let descendants: HashSet<Ref> = dom.descendants_of(id).map(|instance| instance.referent()).collect();
+
+	for instance in dom.descendants_of(id) {
+		for (property, value) in &instance.properties {
+			if let Variant::Ref(target) = value {
+				if !descendants.contains(target) {
+					continue;
+				}
+
+				if let Some(path) = refs::relative_path(&dom, instance.referent(), *target) {
+					ref_paths
+						.entry(instance.referent())
+						.or_default()
+						.push((*property, path));
+				}
+			}
+		}
+	}
+
 	let (_, mut raw_dom) = dom.into_raw();
 
-	fn walk(id: Ref, raw_dom: &mut AHashMap<Ref, Instance>) -> Snapshot {
-		let instance = raw_dom
+	fn walk(
+		id: Ref,
+		raw_dom: &mut AHashMap<Ref, Instance>,
+		ref_paths: &mut HashMap<Ref, Vec<(Ustr, String)>>,
+	) -> Snapshot {
+		let mut instance = raw_dom
 			.remove(&id)
 			.expect("Provided ID does not exist in the current DOM");
 
 		let children = instance
 			.children()
 			.iter()
-			.map(|&child_id| walk(child_id, raw_dom))
+			.map(|&child_id| walk(child_id, raw_dom, ref_paths))
 			.collect();
 
+		instance.properties.retain(|_, value| !matches!(value, Variant::Ref(_)));
+
+		for (property, path) in ref_paths.remove(&id).unwrap_or_default() {
+			instance.properties.insert(property, Variant::String(path));
+		}
+
 		let mut meta = Meta::new();
 
 		if instance.class == "MeshPart" {
@@ -31,5 +70,61 @@ pub fn snapshot_from_dom(dom: WeakDom, id: Ref) -> Snapshot {
 			.with_children(children)
 	}
 
-	walk(id, &mut raw_dom)
+	walk(id, &mut raw_dom, &mut ref_paths)
+}
+
+pub fn dom_from_snapshot(snapshot: Snapshot) -> (WeakDom, Ref) {
+	let mut dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+
+	fn walk(snapshot: Snapshot, parent: Ref, dom: &mut WeakDom) -> Ref {
+		let builder = InstanceBuilder::new(snapshot.class)
+			.with_name(snapshot.meta.original_name.as_ref().unwrap_or(&snapshot.name))
+			.with_properties(snapshot.properties);
+
+		let id = dom.insert(parent, builder);
+
+		for child in snapshot.children {
+			walk(child, id, dom);
+		}
+
+		id
+	}
+
+	let root = dom.root_ref();
+	let id = walk(snapshot, root, &mut dom);
+
+	// Resolve relative instance paths of ref properties,
+	// refs pointing outside of the model can't be stored
+	let ids: Vec<Ref> = dom.descendants_of(id).map(|instance| instance.referent()).collect();
+
+	for id in ids {
+		let instance = dom.get_by_ref(id).unwrap();
+
+		let resolved: Vec<(Ustr, Option<Ref>)> = instance
+			.properties
+			.iter()
+			.filter(|(property, value)| {
+				matches!(value, Variant::String(_)) && is_ref_property(&instance.class, property)
+			})
+			.map(|(property, value)| {
+				let target = match value {
+					Variant::String(path) if path.starts_with('.') => refs::find_by_path(&dom, id, path),
+					_ => None,
+				};
+
+				(*property, target)
+			})
+			.collect();
+
+		let instance = dom.get_by_ref_mut(id).unwrap();
+
+		for (property, target) in resolved {
+			match target {
+				Some(target) => instance.properties.insert(property, Variant::Ref(target)),
+				None => instance.properties.remove(&property),
+			};
+		}
+	}
+
+	(dom, id)
 }
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use serde_json::Value;
+
+use super::lua::{self, LuaValue};
+
+/// Words that plain YAML scalars would be read as something other than strings
+const RESERVED: [&str; 9] = ["true", "false", "null", "yes", "no", "on", "off", "y", "n"];
+
+/// Parses YAML document using the same converter as YAML modules
+pub fn parse(yaml: &str) -> Result<LuaValue> {
+	lua::parse(&format!("return {}", yaml2lua::parse(yaml)?))
+}
+
+/// Writes value as block style YAML, strings are
+/// quoted only when they could be read differently
+pub fn unparse(value: &Value) -> String {
+	let mut yaml = String::new();
+	write_block(value, 0, &mut yaml);
+	yaml
+}
+
+fn write_block(value: &Value, indent: usize, yaml: &mut String) {
+	match value {
+		Value::Object(map) if !map.is_empty() => {
+			for (key, value) in map {
+				yaml.push_str(&" ".repeat(indent));
+				yaml.push_str(&string(key));
+				yaml.push(':');
+
+				if is_block(value) {
+					yaml.push('\n');
+					write_block(value, indent + 2, yaml);
+				} else {
+					yaml.push(' ');
+					yaml.push_str(&scalar(value));
+					yaml.push('\n');
+				}
+			}
+		}
+		Value::Array(items) if !items.is_empty() => {
+			for item in items {
+				yaml.push_str(&" ".repeat(indent));
+				yaml.push_str("- ");
+
+				if is_block(item) {
+					// First line of the nested block goes right after the dash
+					let mut nested = String::new();
+					write_block(item, indent + 2, &mut nested);
+
+					yaml.push_str(&nested[indent + 2..]);
+				} else {
+					yaml.push_str(&scalar(item));
+					yaml.push('\n');
+				}
+			}
+		}
+		value => {
+			yaml.push_str(&" ".repeat(indent));
+			yaml.push_str(&scalar(value));
+			yaml.push('\n');
+		}
+	}
+}
+
+fn is_block(value: &Value) -> bool {
+	match value {
+		Value::Object(map) => !map.is_empty(),
+		Value::Array(items) => !items.is_empty(),
+		_ => false,
+	}
+}
+
+fn scalar(value: &Value) -> String {
+	match value {
+		Value::Null => String::from("null"),
+		Value::Bool(bool) => bool.to_string(),
+		Value::Number(number) => number.to_string(),
+		Value::String(string) => self::string(string),
+		Value::Object(_) => String::from("{}"),
+		Value::Array(_) => String::from("[]"),
+	}
+}
+
+fn string(string: &str) -> String {
+	let is_plain = string.starts_with(|char: char| char.is_alphabetic() || char == '_')
+		&& !string.ends_with(' ')
+		&& string
+			.chars()
+			.all(|char| char.is_alphanumeric() || matches!(char, ' ' | '_' | '-' | '.' | '/'))
+		&& !RESERVED.contains(&string.to_lowercase().as_str());
+
+	if is_plain {
+		string.to_owned()
+	} else {
+		// JSON strings are valid double quoted YAML strings
+		serde_json::to_string(string).unwrap_or_default()
+	}
+}
@@ -1,18 +1,78 @@
 use anyhow::Result;
+use json_formatter::JsonFormatter;
 use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
+use serde::{
+	ser::{SerializeMap, SerializeSeq},
+	Serialize,
+};
+use serde_json::{Serializer, Value};
 use std::path::Path;
 
-use crate::{core::snapshot::Snapshot, vfs::Vfs};
+use crate::{
+	core::snapshot::Snapshot,
+	json,
+	middleware::helpers::{self, LuaValue},
+	vfs::Vfs,
+	Properties,
+};
+
+/// Luau value serialized as JSON where empty tables are
+/// written as arrays only if they replace an array
+struct JsonValue<'a> {
+	value: &'a LuaValue,
+	original: Option<&'a Value>,
+}
+
+impl<'a> Serialize for JsonValue<'a> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let child = |value, original: Option<&'a Value>| JsonValue { value, original };
+
+		match self.value {
+			LuaValue::Table(entries) if entries.is_empty() && self.original.is_some_and(Value::is_array) => {
+				serializer.serialize_seq(Some(0))?.end()
+			}
+			LuaValue::Table(entries) if self.value.is_array() => {
+				let mut seq = serializer.serialize_seq(Some(entries.len()))?;
+
+				for (index, (_, value)) in entries.iter().enumerate() {
+					seq.serialize_element(&child(value, self.original.and_then(|original| original.get(index))))?;
+				}
+
+				seq.end()
+			}
+			LuaValue::Table(_) => {
+				let entries = self
+					.value
+					.entries()
+					.into_iter()
+					.filter(|(_, value)| **value != LuaValue::Nil)
+					.collect::<Vec<_>>();
+
+				let mut map = serializer.serialize_map(Some(entries.len()))?;
+
+				for (key, value) in entries {
+					let key = key.to_key();
+					let original = self.original.and_then(|original| original.get(&key));
+
+					map.serialize_entry(&key, &child(value, original))?;
+				}
+
+				map.end()
+			}
+			value => value.serialize(serializer),
+		}
+	}
+}
 
 #[profiling::function]
 pub fn read_json(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
-	let json = vfs.read_to_string(path)?;
+	let contents = vfs.read_to_string(path)?;
 
-	if json.is_empty() {
+	if contents.is_empty() {
 		return Ok(Snapshot::new().with_class("ModuleScript"));
 	}
 
-	let lua = json2lua::parse(&json)?;
+	let lua = json2lua::parse(&json::normalize(&contents)?)?;
 
 	let source = format!("return {lua}");
 
@@ -21,3 +81,32 @@ pub fn read_json(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 
 	Ok(Snapshot::new().with_class("ModuleScript").with_properties(properties))
 }
+
+#[profiling::function]
+pub fn write_json(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let value = match helpers::take_lua_source(&mut properties)? {
+		Some(value) => value,
+		None => {
+			vfs.write(path, &[])?;
+			return Ok(properties);
+		}
+	};
+
+	let original = vfs
+		.read_to_string(path)
+		.ok()
+		.and_then(|contents| json::from_str::<Value>(&contents).ok());
+
+	let value = JsonValue {
+		value: &value,
+		original: original.as_ref(),
+	};
+
+	let mut writer = Vec::new();
+	let mut serializer = Serializer::with_formatter(&mut writer, JsonFormatter::new().with_extra_newline(true));
+
+	value.serialize(&mut serializer)?;
+	json::write(path, &writer, vfs)?;
+
+	Ok(properties)
+}
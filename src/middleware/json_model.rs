@@ -1,11 +1,14 @@
 use anyhow::Result;
 use log::error;
 use rbx_dom_weak::{types::Tags, ustr, HashMapExt, Ustr, UstrMap};
-use serde::Deserialize;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Serializer;
+use std::{collections::BTreeMap, path::Path};
 
 use super::helpers;
-use crate::{core::snapshot::Snapshot, resolution::UnresolvedValue, vfs::Vfs};
+use crate::{
+	core::snapshot::Snapshot, json, resolution::UnresolvedValue, util::get_json_formatter, vfs::Vfs, Properties,
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -34,7 +37,7 @@ pub fn read_json_model(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 		return Ok(Snapshot::new().with_class("Folder"));
 	}
 
-	let model = serde_json::from_str(&contents)?;
+	let model = json::from_str(&contents)?;
 	let snapshot = walk(model, path)?;
 
 	Ok(snapshot)
@@ -97,3 +100,47 @@ fn walk(model: JsonModel, path: &Path) -> Result<Snapshot> {
 
 	Ok(snapshot)
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WritableJsonModel {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	name: Option<String>,
+	class_name: Ustr,
+
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	properties: BTreeMap<Ustr, UnresolvedValue>,
+
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	children: Vec<WritableJsonModel>,
+}
+
+#[profiling::function]
+pub fn write_json_model(snapshot: Snapshot, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	fn walk(snapshot: Snapshot, is_root: bool) -> WritableJsonModel {
+		let class = snapshot.class;
+
+		let properties = snapshot
+			.properties
+			.into_iter()
+			.map(|(property, variant)| (property, UnresolvedValue::from_variant(variant, &class, &property)))
+			.collect();
+
+		WritableJsonModel {
+			name: if is_root { None } else { Some(snapshot.name) },
+			class_name: class,
+			properties,
+			children: snapshot.children.into_iter().map(|child| walk(child, false)).collect(),
+		}
+	}
+
+	let model = walk(snapshot, true);
+
+	let mut writer = Vec::new();
+	let mut serializer = Serializer::with_formatter(&mut writer, get_json_formatter());
+
+	model.serialize(&mut serializer)?;
+	json::write(path, &writer, vfs)?;
+
+	Ok(Properties::new())
+}
@@ -1,11 +1,12 @@
 use anyhow::Result;
+use log::warn;
 use rbx_dom_weak::{
-	types::{Enum, Variant},
+	types::{Attributes, Enum, Tags, Variant},
 	ustr, HashMapExt, UstrMap,
 };
 use std::path::Path;
 
-use super::Middleware;
+use super::{luau_data, Middleware};
 use crate::{
 	core::{meta::Context, snapshot::Snapshot},
 	vfs::Vfs,
@@ -45,13 +46,23 @@ pub fn read_luau(path: &Path, context: &Context, vfs: &Vfs, script_type: ScriptT
 
 	let source = vfs.read_to_string(path)?;
 
+	if script_type == ScriptType::Module && luau_data::parse_directive(&source).is_some() {
+		return luau_data::read_luau_data(path, vfs);
+	}
+
 	if script_type != ScriptType::Module {
 		if let Some(run_context) = run_context {
 			properties.insert(ustr("RunContext"), run_context);
 		}
 	}
 
-	properties.insert(ustr("Source"), Variant::String(source));
+	let (directives, source) = split_directives(&source);
+
+	if !directives.is_empty() {
+		apply_directives(&directives, class_name, &mut properties, path);
+	}
+
+	properties.insert(ustr("Source"), Variant::String(source.to_owned()));
 	snapshot.set_properties(properties);
 
 	Ok(snapshot)
@@ -65,7 +76,257 @@ pub fn write_luau(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<
 		String::new()
 	};
 
-	vfs.write(path, source.as_bytes())?;
+	// Tags and attributes are moved to the header only if the file already
+	// uses directives, otherwise they stay in the instance data file
+	let has_directives = vfs.exists(path) && !split_directives(&vfs.read_to_string(path)?).0.is_empty();
+
+	let mut header = String::new();
+
+	for directive in take_directives(&mut properties, has_directives) {
+		header.push_str(&format!("{DIRECTIVE} {directive}\n"));
+	}
+
+	vfs.write(path, (header + &source).as_bytes())?;
 
 	Ok(properties)
 }
+
+/// Header comment that sets script properties, example:
+/// `--@argon runContext=Client`, `--@argon disabled` or `--@argon tag=Foo`,
+/// other `name=value` pairs are treated as attributes
+const DIRECTIVE: &str = "--@argon";
+
+const RUN_CONTEXTS: [&str; 4] = ["Legacy", "Server", "Client", "Plugin"];
+
+/// Splits source into directive arguments from the
+/// leading header lines and the rest of the source
+fn split_directives(source: &str) -> (Vec<(String, Option<String>)>, &str) {
+	let mut directives = Vec::new();
+	let mut offset = 0;
+
+	for line in source.split_inclusive('\n') {
+		let Some(arguments) = line.trim_end().strip_prefix(DIRECTIVE) else {
+			break;
+		};
+
+		if !arguments.is_empty() && !arguments.starts_with(char::is_whitespace) {
+			break;
+		}
+
+		directives.extend(
+			tokenize(arguments)
+				.into_iter()
+				.map(|argument| match argument.split_once('=') {
+					Some((key, value)) => (key.to_owned(), Some(value.to_owned())),
+					None => (argument, None),
+				}),
+		);
+
+		offset += line.len();
+	}
+
+	(directives, &source[offset..])
+}
+
+fn apply_directives(directives: &[(String, Option<String>)], class: &str, properties: &mut Properties, path: &Path) {
+	let is_script = class != "ModuleScript";
+	let mut tags = Vec::new();
+	let mut attributes = Attributes::new();
+
+	for (key, raw) in directives {
+		let value = raw.as_deref().map(unquote);
+
+		match (key.as_str(), &value) {
+			("runContext", Some(value)) if class == "Script" => {
+				match RUN_CONTEXTS
+					.iter()
+					.position(|context| context.eq_ignore_ascii_case(value))
+				{
+					Some(index) => {
+						properties.insert(ustr("RunContext"), Variant::Enum(Enum::from_u32(index as u32)));
+					}
+					None => warn!("Unknown RunContext: {} in {}", value, path.display()),
+				}
+			}
+			("disabled", None) if is_script => {
+				properties.insert(ustr("Disabled"), Variant::Bool(true));
+			}
+			("disabled", Some(value)) if is_script => match value.parse::<bool>() {
+				Ok(disabled) => {
+					properties.insert(ustr("Disabled"), Variant::Bool(disabled));
+				}
+				Err(_) => warn!("Invalid disabled directive value: {} in {}", value, path.display()),
+			},
+			("runContext" | "disabled", _) => {
+				warn!("Directive: {} is not supported by {} in {}", key, class, path.display())
+			}
+			("tag", Some(value)) => tags.push(value.to_owned()),
+			(_, Some(value)) => {
+				let is_quoted = raw.as_ref().is_some_and(|raw| raw.starts_with('"'));
+
+				let value = if is_quoted {
+					Variant::String(value.to_owned())
+				} else if let Ok(value) = value.parse::<bool>() {
+					Variant::Bool(value)
+				} else if let Ok(value) = value.parse::<f64>() {
+					Variant::Float64(value)
+				} else {
+					Variant::String(value.to_owned())
+				};
+
+				attributes.insert(key.to_owned(), value);
+			}
+			(_, None) => warn!("Unknown directive: {} in {}", key, path.display()),
+		}
+	}
+
+	if !tags.is_empty() {
+		properties.insert(ustr("Tags"), Tags::from(tags).into());
+	}
+
+	if !attributes.is_empty() {
+		properties.insert(ustr("Attributes"), attributes.into());
+	}
+}
+
+/// Removes properties that can be written as directives
+/// and returns them as directive arguments
+fn take_directives(properties: &mut Properties, all: bool) -> Vec<String> {
+	let mut directives = Vec::new();
+
+	if let Some(Variant::Enum(run_context)) = properties.get(&ustr("RunContext")) {
+		if let Some(run_context) = RUN_CONTEXTS.get(run_context.to_u32() as usize) {
+			directives.push(format!("runContext={run_context}"));
+			properties.remove(&ustr("RunContext"));
+		}
+	}
+
+	match properties.get(&ustr("Disabled")) {
+		Some(Variant::Bool(true)) => {
+			directives.push(String::from("disabled"));
+			properties.remove(&ustr("Disabled"));
+		}
+		Some(Variant::Bool(false)) => {
+			properties.remove(&ustr("Disabled"));
+		}
+		_ => {}
+	}
+
+	if !all {
+		return directives;
+	}
+
+	if let Some(Variant::Tags(tags)) = properties.get(&ustr("Tags")) {
+		if tags.iter().all(|tag| !tag.is_empty() && quote(tag).is_some()) {
+			directives.extend(tags.iter().map(|tag| format!("tag={}", quote(tag).unwrap())));
+			properties.remove(&ustr("Tags"));
+		}
+	}
+
+	if let Some(Variant::Attributes(attributes)) = properties.remove(&ustr("Attributes")) {
+		let mut remaining = Attributes::new();
+		let mut written = Vec::new();
+
+		for (name, value) in attributes {
+			let is_valid_name = !name.is_empty()
+				&& !matches!(name.as_str(), "runContext" | "disabled" | "tag")
+				&& name.chars().all(|char| char.is_alphanumeric() || char == '_');
+
+			let argument = match &value {
+				_ if !is_valid_name => None,
+				Variant::Bool(value) => Some(value.to_string()),
+				Variant::Float64(value) if value.is_finite() => Some(value.to_string()),
+				// Strings that would be read back as a different type have to be quoted
+				Variant::String(value) if value.parse::<bool>().is_ok() || value.parse::<f64>().is_ok() => {
+					Some(format!("\"{value}\""))
+				}
+				Variant::String(value) => quote(value),
+				_ => None,
+			};
+
+			if let Some(argument) = argument {
+				written.push(format!("{name}={argument}"));
+			} else {
+				remaining.insert(name, value);
+			}
+		}
+
+		written.sort();
+		directives.extend(written);
+
+		if !remaining.is_empty() {
+			properties.insert(ustr("Attributes"), remaining.into());
+		}
+	}
+
+	directives
+}
+
+/// Splits directive arguments by whitespace, keeping double quoted values together
+fn tokenize(arguments: &str) -> Vec<String> {
+	let mut tokens = Vec::new();
+	let mut current = String::new();
+	let mut quoted = false;
+	let mut chars = arguments.chars();
+
+	while let Some(char) = chars.next() {
+		match char {
+			'\\' if quoted => {
+				current.push(char);
+
+				if let Some(char) = chars.next() {
+					current.push(char);
+				}
+			}
+			'"' => {
+				quoted = !quoted;
+				current.push(char);
+			}
+			char if char.is_whitespace() && !quoted => {
+				if !current.is_empty() {
+					tokens.push(std::mem::take(&mut current));
+				}
+			}
+			char => current.push(char),
+		}
+	}
+
+	if !current.is_empty() {
+		tokens.push(current);
+	}
+
+	tokens
+}
+
+fn unquote(value: &str) -> String {
+	let Some(value) = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')) else {
+		return value.to_owned();
+	};
+
+	let mut unquoted = String::with_capacity(value.len());
+	let mut chars = value.chars();
+
+	while let Some(char) = chars.next() {
+		if char == '\\' {
+			unquoted.extend(chars.next());
+		} else {
+			unquoted.push(char);
+		}
+	}
+
+	unquoted
+}
+
+/// Returns value in a form that can be read back from a directive,
+/// `None` if it can't be represented in a single line
+fn quote(value: &str) -> Option<String> {
+	if value.contains(['\n', '\r']) {
+		return None;
+	}
+
+	if !value.is_empty() && !value.contains(|char: char| char.is_whitespace() || char == '"' || char == '\\') {
+		return Some(value.to_owned());
+	}
+
+	Some(format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+}
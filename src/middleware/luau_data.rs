@@ -0,0 +1,222 @@
+use anyhow::{bail, Result};
+use log::{error, warn};
+use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::{
+	core::snapshot::Snapshot,
+	middleware::helpers::{self, LuaValue},
+	resolution::{self, UnresolvedValue},
+	vfs::Vfs,
+	Properties,
+};
+
+/// Directive that marks Luau file as instance data,
+/// example: `--!argon class=Configuration`
+const DIRECTIVE: &str = "--!argon";
+const DEFAULT_CLASS: &str = "Configuration";
+
+/// Returns the class declared by the `--!argon` directive
+/// placed in the first line of the source, if there is one
+pub fn parse_directive(source: &str) -> Option<String> {
+	let line = source.lines().next()?.trim();
+	let arguments = line.strip_prefix(DIRECTIVE)?;
+
+	if !arguments.is_empty() && !arguments.starts_with(char::is_whitespace) {
+		return None;
+	}
+
+	let mut class = None;
+
+	for argument in arguments.split_whitespace() {
+		match argument.split_once('=') {
+			Some(("class", value)) if !value.is_empty() => class = Some(value.to_owned()),
+			_ => warn!("Unknown {DIRECTIVE} directive argument: {argument}"),
+		}
+	}
+
+	Some(class.unwrap_or(DEFAULT_CLASS.to_owned()))
+}
+
+/// Reads Luau file returning a literal table whose entries are
+/// properties of the declared class or attributes otherwise
+#[profiling::function]
+pub fn read_luau_data(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
+	let source = vfs.read_to_string(path)?;
+	let class = parse_directive(&source).unwrap_or(DEFAULT_CLASS.to_owned());
+
+	let mut snapshot = Snapshot::new().with_class(&class);
+
+	if source
+		.lines()
+		.all(|line| line.trim().is_empty() || line.trim().starts_with("--"))
+	{
+		return Ok(snapshot);
+	}
+
+	let entries = match helpers::parse_lua(&source)? {
+		LuaValue::Table(entries) => entries,
+		_ => bail!("Luau data file has to return a table"),
+	};
+
+	let mut properties = UstrMap::new();
+	let mut attributes = Map::new();
+
+	for (key, value) in entries {
+		let Some(LuaValue::String(key)) = key else {
+			bail!("Luau data table can only have string keys");
+		};
+
+		if value == LuaValue::Nil {
+			continue;
+		}
+
+		let value = serde_json::to_value(value)?;
+
+		if resolution::is_property(&class, &key) {
+			let resolved = serde_json::from_value::<UnresolvedValue>(value)
+				.map_err(anyhow::Error::from)
+				.and_then(|value| value.resolve(&class, &key));
+
+			match resolved {
+				Ok(value) => {
+					properties.insert(ustr(&key), value);
+				}
+				Err(err) => error!("Failed to parse property: {} at {}", err, path.display()),
+			}
+		} else {
+			attributes.insert(key, value);
+		}
+	}
+
+	if !attributes.is_empty() {
+		let resolved = serde_json::from_value::<UnresolvedValue>(Value::Object(attributes))
+			.map_err(anyhow::Error::from)
+			.and_then(|value| value.resolve(&class, "Attributes"));
+
+		match resolved {
+			Ok(value) => {
+				properties.insert(ustr("Attributes"), value);
+			}
+			Err(err) => error!("Failed to parse attributes: {} at {}", err, path.display()),
+		}
+	}
+
+	snapshot.set_properties(properties);
+
+	Ok(snapshot)
+}
+
+/// Writes instance data keeping the order of keys and the leading
+/// comments of the current file, entries that were not there yet
+/// are appended in alphabetical order. Files that describe the same
+/// data are left untouched and files with comments inside the table
+/// are never rewritten as these comments would be lost
+#[profiling::function]
+pub fn write_luau_data(snapshot: Snapshot, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let class = snapshot.class.as_str();
+	let mut table = Map::new();
+	let mut attributes = None;
+
+	for (property, variant) in snapshot.properties {
+		let value = serde_json::to_value(UnresolvedValue::from_variant(variant.clone(), class, &property))?;
+
+		if let Variant::Attributes(_) = variant {
+			attributes = Some(value);
+		} else {
+			table.insert(property.to_string(), value);
+		}
+	}
+
+	if let Some(Value::Object(attributes)) = attributes {
+		for (name, value) in attributes {
+			if table.contains_key(&name) {
+				warn!(
+					"Attribute {} of {} has the same name as a property and won't be saved",
+					name,
+					path.display()
+				);
+				continue;
+			}
+
+			table.insert(name, value);
+		}
+	}
+
+	let current = vfs.read_to_string(path).unwrap_or_default();
+	let (header, body) = split_header(&current);
+
+	let current_table = match helpers::parse_lua(body) {
+		Ok(LuaValue::Table(entries)) => entries,
+		_ => vec![],
+	};
+
+	let order = current_table
+		.iter()
+		.filter_map(|(key, _)| match key {
+			Some(LuaValue::String(key)) => Some(key.as_str()),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+
+	let mut entries = table.into_iter().collect::<Vec<_>>();
+
+	entries.sort_by(|(a, _), (b, _)| {
+		let position = |key: &String| order.iter().position(|current| current == key).unwrap_or(usize::MAX);
+		position(a).cmp(&position(b)).then_with(|| a.cmp(b))
+	});
+
+	let table = entries.into_iter().collect::<Map<_, _>>();
+
+	if parse_directive(header).as_deref() == Some(class) && !body.trim().is_empty() {
+		let current_table = LuaValue::Table(current_table);
+
+		if serde_json::to_value(current_table).ok() == Some(Value::Object(table.clone())) {
+			return Ok(UstrMap::new());
+		}
+
+		if helpers::lua_has_comments(body) {
+			bail!(
+				"File {} contains comments that would be lost, apply this change manually",
+				path.display()
+			);
+		}
+	}
+
+	let mut source = match header.split_once('\n') {
+		Some((directive, comments)) if directive.trim().starts_with(DIRECTIVE) => {
+			format!("{DIRECTIVE} class={class}\n{comments}")
+		}
+		_ => format!("{DIRECTIVE} class={class}\n"),
+	};
+
+	if !table.is_empty() {
+		let lua = json2lua::parse(&serde_json::to_string(&table)?)?;
+		source.push_str(&format!("return {lua}\n"));
+	} else {
+		source.push_str("return {}\n");
+	}
+
+	vfs.write(path, source.as_bytes())?;
+
+	Ok(UstrMap::new())
+}
+
+/// Splits the source into leading comment lines,
+/// including the directive, and the rest of the file
+fn split_header(source: &str) -> (&str, &str) {
+	let mut length = 0;
+
+	for line in source.split_inclusive('\n') {
+		let trimmed = line.trim();
+
+		if !trimmed.is_empty() && !trimmed.starts_with("--") {
+			break;
+		}
+
+		length += line.len();
+	}
+
+	source.split_at(length)
+}
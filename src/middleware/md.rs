@@ -2,7 +2,12 @@ use anyhow::Result;
 use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
 use std::path::Path;
 
-use crate::{core::snapshot::Snapshot, middleware::helpers::markdown_to_rich_text, vfs::Vfs};
+use crate::{
+	core::snapshot::Snapshot,
+	middleware::helpers::{markdown_to_rich_text, rich_text_to_markdown},
+	vfs::Vfs,
+	Properties,
+};
 
 #[profiling::function]
 pub fn read_md(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
@@ -14,3 +19,21 @@ pub fn read_md(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 
 	Ok(Snapshot::new().with_class("StringValue").with_properties(properties))
 }
+
+#[profiling::function]
+pub fn write_md(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let rich_text = if let Some(Variant::String(rich_text)) = properties.remove(&ustr("Value")) {
+		rich_text
+	} else {
+		String::new()
+	};
+
+	// Keep the original formatting if the file already describes the same text
+	if vfs.exists(path) && markdown_to_rich_text(&vfs.read_to_string(path)?) == rich_text {
+		return Ok(properties);
+	}
+
+	vfs.write(path, rich_text_to_markdown(&rich_text).as_bytes())?;
+
+	Ok(properties)
+}
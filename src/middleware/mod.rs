@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use colored::Colorize;
 use log::trace;
 use rbx_dom_weak::{
@@ -14,7 +14,7 @@ use std::{
 use self::data::DataSnapshot;
 use crate::{
 	argon_warn,
-	constants::BLACKLISTED_PATHS,
+	constants::{BLACKLISTED_PATHS, IGNORE_FILE},
 	core::{
 		meta::{Context, Source},
 		snapshot::Snapshot,
@@ -26,12 +26,14 @@ use crate::{
 
 mod helpers;
 
+pub mod asset;
 pub mod csv;
 pub mod data;
 pub mod dir;
 pub mod json;
 pub mod json_model;
 pub mod luau;
+pub mod luau_data;
 pub mod md;
 pub mod msgpack;
 pub mod project;
@@ -39,6 +41,7 @@ pub mod rbxm;
 pub mod rbxmx;
 pub mod toml;
 pub mod txt;
+pub mod value;
 pub mod yaml;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -49,11 +52,19 @@ pub enum Middleware {
 	ServerScript,
 	ClientScript,
 	ModuleScript,
+	LuauData,
 
 	StringValue,
 	RichStringValue,
 	LocalizationTable,
 
+	NumberValue,
+	BoolValue,
+	IntValue,
+	Color3Value,
+
+	Asset,
+
 	JsonModule,
 	TomlModule,
 	YamlModule,
@@ -79,11 +90,19 @@ impl Middleware {
 			Middleware::ServerScript | Middleware::ClientScript | Middleware::ModuleScript => {
 				luau::read_luau(path, context, vfs, self.clone().into())
 			}
+			Middleware::LuauData => luau_data::read_luau_data(path, vfs),
 			//
 			Middleware::StringValue => txt::read_txt(path, vfs),
 			Middleware::RichStringValue => md::read_md(path, vfs),
 			Middleware::LocalizationTable => csv::read_csv(path, vfs),
 			//
+			Middleware::NumberValue => value::read_value(path, "NumberValue", vfs),
+			Middleware::BoolValue => value::read_value(path, "BoolValue", vfs),
+			Middleware::IntValue => value::read_value(path, "IntValue", vfs),
+			Middleware::Color3Value => value::read_value(path, "Color3Value", vfs),
+			//
+			Middleware::Asset => asset::read_asset(path, context, vfs),
+			//
 			Middleware::JsonModule => json::read_json(path, vfs),
 			Middleware::TomlModule => toml::read_toml(path, vfs),
 			Middleware::YamlModule => yaml::read_yaml(path, vfs),
@@ -102,15 +121,35 @@ impl Middleware {
 		})
 	}
 
-	pub fn write(&self, properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	pub fn write(&self, snapshot: Snapshot, path: &Path, vfs: &Vfs) -> Result<Properties> {
 		match self {
+			Middleware::Project | Middleware::InstanceData => {
+				Err(anyhow!("{} middleware cannot be used to write instances", self))
+			}
+			//
 			Middleware::ServerScript | Middleware::ClientScript | Middleware::ModuleScript => {
-				luau::write_luau(properties, path, vfs)
+				luau::write_luau(snapshot.properties, path, vfs)
+			}
+			Middleware::LuauData => luau_data::write_luau_data(snapshot, path, vfs),
+			//
+			Middleware::StringValue => txt::write_txt(snapshot.properties, path, vfs),
+			Middleware::RichStringValue => md::write_md(snapshot.properties, path, vfs),
+			Middleware::LocalizationTable => csv::write_csv(snapshot.properties, path, vfs),
+			//
+			Middleware::NumberValue | Middleware::BoolValue | Middleware::IntValue | Middleware::Color3Value => {
+				value::write_value(snapshot.properties, &snapshot.class, path, vfs)
 			}
-			Middleware::StringValue => txt::write_txt(properties, path, vfs),
-			Middleware::LocalizationTable => csv::write_csv(properties, path, vfs),
-			// TODO: Add support for other middleware
-			_ => unimplemented!(),
+			//
+			Middleware::Asset => asset::write_asset(snapshot),
+			//
+			Middleware::JsonModule => json::write_json(snapshot.properties, path, vfs),
+			Middleware::TomlModule => toml::write_toml(snapshot.properties, path, vfs),
+			Middleware::YamlModule => yaml::write_yaml(snapshot.properties, path, vfs),
+			Middleware::MsgpackModule => msgpack::write_msgpack(snapshot.properties, path, vfs),
+			//
+			Middleware::JsonModel => json_model::write_json_model(snapshot, path, vfs),
+			Middleware::RbxmModel => rbxm::write_rbxm(snapshot, path, vfs),
+			Middleware::RbxmxModel => rbxmx::write_rbxmx(snapshot, path, vfs),
 		}
 		.with_desc(|| {
 			format!(
@@ -121,8 +160,48 @@ impl Middleware {
 		})
 	}
 
+	/// Whether the middleware stores the whole instance tree in a single file
+	pub fn is_model(&self) -> bool {
+		matches!(
+			self,
+			Middleware::JsonModel | Middleware::RbxmModel | Middleware::RbxmxModel
+		)
+	}
+
+	/// Whether the middleware produces instances of the same class as the given one,
+	/// example: `TomlModule` is a variant of `ModuleScript`
+	pub fn is_variant_of(&self, middleware: &Middleware) -> bool {
+		match middleware {
+			Middleware::ModuleScript => matches!(
+				self,
+				Middleware::ModuleScript
+					| Middleware::JsonModule
+					| Middleware::TomlModule
+					| Middleware::YamlModule
+					| Middleware::MsgpackModule
+			),
+			Middleware::StringValue => matches!(self, Middleware::StringValue | Middleware::RichStringValue),
+			_ => self == middleware,
+		}
+	}
+
+	/// Whether the middleware is able to store the given instance properties,
+	/// data modules can only store sources that return a literal value
+	pub fn can_write(&self, properties: &Properties) -> bool {
+		match self {
+			Middleware::JsonModule | Middleware::TomlModule | Middleware::YamlModule | Middleware::MsgpackModule => {
+				match properties.get(&ustr("Source")) {
+					Some(Variant::String(source)) => source.trim().is_empty() || helpers::parse_lua(source).is_ok(),
+					_ => true,
+				}
+			}
+			_ => true,
+		}
+	}
+
+	/// Returns the default middleware for the given class,
+	/// `None` if the class has no dedicated middleware
 	pub fn from_class(class: &str, properties: Option<&mut Properties>) -> Option<Self> {
-		// TODO: Implement matcher for detecting remaining middleware
 		match class {
 			"Script" => {
 				if let Some(properties) = properties {
@@ -148,6 +227,10 @@ impl Middleware {
 			"ModuleScript" => Some(Middleware::ModuleScript),
 			"StringValue" => Some(Middleware::StringValue),
 			"LocalizationTable" => Some(Middleware::LocalizationTable),
+			"NumberValue" => Some(Middleware::NumberValue),
+			"BoolValue" => Some(Middleware::BoolValue),
+			"IntValue" => Some(Middleware::IntValue),
+			"Color3Value" => Some(Middleware::Color3Value),
 			_ => None,
 		}
 	}
@@ -156,7 +239,8 @@ impl Middleware {
 /// Returns a snapshot of the given path, `None` if path no longer exists
 pub fn new_snapshot(path: &Path, context: &Context, vfs: &Vfs) -> Result<Option<Snapshot>> {
 	if BLACKLISTED_PATHS.iter().any(|blacklisted| path.ends_with(blacklisted))
-		|| context.ignore_rules().iter().any(|rule| rule.matches(path))
+		|| path.ends_with(IGNORE_FILE)
+		|| context.is_ignored(path, vfs.is_dir(path))
 	{
 		trace!("Snapshot of {} not created: ignored or blacklisted", path.display());
 		return Ok(None);
@@ -211,8 +295,14 @@ fn new_snapshot_file(path: &Path, context: &Context, vfs: &Vfs) -> Result<Option
 
 		if let Some(instance_data) = get_instance_data(&name, Some(&snapshot.class), path, context, vfs)? {
 			snapshot.apply_data(instance_data);
+
+			if middleware == Middleware::Asset {
+				asset::retarget(&mut snapshot);
+			}
 		}
 
+		snapshot.meta.update_hashes(vfs);
+
 		Ok(Some(snapshot))
 	} else {
 		Ok(None)
@@ -244,12 +334,14 @@ fn new_snapshot_file_child(path: &Path, context: &Context, vfs: &Vfs) -> Result<
 			snapshot.meta.set_context(context);
 			snapshot.meta.set_source(Source::child_file(parent, path));
 
+			let child_context = dir::child_context(parent, context, vfs)?;
+
 			for entry in vfs.read_dir(parent)? {
 				if entry == path {
 					continue;
 				}
 
-				if let Some(child_snapshot) = new_snapshot(&entry, context, vfs)? {
+				if let Some(child_snapshot) = new_snapshot(&entry, &child_context, vfs)? {
 					snapshot.add_child(child_snapshot);
 				}
 			}
@@ -259,8 +351,14 @@ fn new_snapshot_file_child(path: &Path, context: &Context, vfs: &Vfs) -> Result<
 
 		if let Some(instance_data) = get_instance_data(&name, Some(&snapshot.class), parent, context, vfs)? {
 			snapshot.apply_data(instance_data);
+
+			if middleware == Middleware::Asset {
+				asset::retarget(&mut snapshot);
+			}
 		}
 
+		snapshot.meta.update_hashes(vfs);
+
 		Ok(Some(snapshot))
 	} else {
 		Ok(None)
@@ -276,6 +374,8 @@ fn new_snapshot_dir(path: &Path, context: &Context, vfs: &Vfs) -> Result<Option<
 		snapshot.apply_data(instance_data);
 	}
 
+	snapshot.meta.update_hashes(vfs);
+
 	Ok(Some(snapshot))
 }
 
@@ -4,7 +4,7 @@ use rmp_serde::Deserializer;
 use rmpv::Value;
 use std::path::Path;
 
-use crate::{core::snapshot::Snapshot, vfs::Vfs};
+use crate::{core::snapshot::Snapshot, middleware::helpers, vfs::Vfs, Properties};
 
 #[profiling::function]
 pub fn read_msgpack(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
@@ -91,3 +91,21 @@ fn escape_chars(string: &str) -> String {
 
 	validated
 }
+
+#[profiling::function]
+pub fn write_msgpack(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let value = match helpers::take_lua_source(&mut properties)? {
+		Some(value) => value.to_msgpack(),
+		None => {
+			vfs.write(path, &[])?;
+			return Ok(properties);
+		}
+	};
+
+	let mut writer = Vec::new();
+	rmpv::encode::write_value(&mut writer, &value)?;
+
+	vfs.write(path, &writer)?;
+
+	Ok(properties)
+}
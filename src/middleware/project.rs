@@ -26,6 +26,12 @@ pub fn read_project(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 	vfs.watch(path, false)?;
 
 	let meta = Meta::from_project(&project);
+
+	// Asset lock might not exist yet, so its directory is watched instead
+	if let Some(asset_lock) = meta.context.asset_lock() {
+		vfs.watch(asset_lock.path().get_parent(), false)?;
+	}
+
 	let mut snapshot = new_snapshot_node(&project.name, path, project.node, NodePath::new(), &meta.context, vfs)?;
 
 	snapshot.meta.source.add_project(path);
@@ -1,8 +1,9 @@
 use anyhow::Result;
+use rbx_dom_weak::HashMapExt;
 use std::path::Path;
 
 use super::helpers;
-use crate::{core::snapshot::Snapshot, vfs::Vfs};
+use crate::{core::snapshot::Snapshot, vfs::Vfs, Properties};
 
 #[profiling::function]
 pub fn read_rbxm(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
@@ -18,3 +19,15 @@ pub fn read_rbxm(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 
 	Ok(snapshot)
 }
+
+#[profiling::function]
+pub fn write_rbxm(snapshot: Snapshot, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let (dom, id) = helpers::dom_from_snapshot(snapshot);
+
+	let mut writer = Vec::new();
+	rbx_binary::to_writer(&mut writer, &dom, &[id])?;
+
+	vfs.write(path, &writer)?;
+
+	Ok(Properties::new())
+}
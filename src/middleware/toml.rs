@@ -1,8 +1,13 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
 use std::path::Path;
 
-use crate::{core::snapshot::Snapshot, vfs::Vfs};
+use crate::{
+	core::snapshot::Snapshot,
+	middleware::helpers::{self, LuaValue},
+	vfs::Vfs,
+	Properties,
+};
 
 #[profiling::function]
 pub fn read_toml(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
@@ -16,3 +21,23 @@ pub fn read_toml(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 
 	Ok(Snapshot::new().with_class("ModuleScript").with_properties(properties))
 }
+
+#[profiling::function]
+pub fn write_toml(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let value = match helpers::take_lua_source(&mut properties)? {
+		Some(value) => value,
+		None => {
+			vfs.write(path, &[])?;
+			return Ok(properties);
+		}
+	};
+
+	if !matches!(value, LuaValue::Table(_)) || value.is_array() {
+		bail!("TOML document has to be a table with keys");
+	}
+
+	let toml = toml::to_string(&value)?;
+	vfs.write(path, toml.as_bytes())?;
+
+	Ok(properties)
+}
@@ -0,0 +1,130 @@
+use anyhow::{bail, Result};
+use rbx_dom_weak::{
+	types::{Color3, Variant},
+	ustr, HashMapExt, UstrMap,
+};
+use std::path::Path;
+
+use crate::{
+	core::snapshot::Snapshot,
+	json,
+	resolution::{AmbiguousValue, UnresolvedValue},
+	vfs::Vfs,
+	Properties,
+};
+
+/// Reads plain text file containing the `Value` of the given value class,
+/// colors can be written as `#rrggbb`, `r, g, b` or `[r, g, b]`
+#[profiling::function]
+pub fn read_value(path: &Path, class: &str, vfs: &Vfs) -> Result<Snapshot> {
+	let mut properties = UstrMap::new();
+
+	if let Some(value) = parse_value(&vfs.read_to_string(path)?, class)? {
+		properties.insert(ustr("Value"), value);
+	}
+
+	Ok(Snapshot::new().with_class(class).with_properties(properties))
+}
+
+#[profiling::function]
+pub fn write_value(mut properties: Properties, class: &str, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let value = properties.remove(&ustr("Value"));
+
+	// Keep the original formatting if the file already stores the same value
+	if vfs.exists(path) {
+		if let Ok(current) = parse_value(&vfs.read_to_string(path)?, class) {
+			if current == value {
+				return Ok(properties);
+			}
+		}
+	}
+
+	let contents = match value {
+		Some(value) => format!("{}\n", format_value(value)?),
+		None => String::new(),
+	};
+
+	vfs.write(path, contents.as_bytes())?;
+
+	Ok(properties)
+}
+
+fn parse_value(contents: &str, class: &str) -> Result<Option<Variant>> {
+	let contents = contents.trim();
+
+	if contents.is_empty() {
+		return Ok(None);
+	}
+
+	let value = if class == "Color3Value" {
+		if let Some(hex) = contents.strip_prefix('#') {
+			return Ok(Some(parse_hex(hex)?.into()));
+		}
+
+		let contents = if contents.starts_with('[') {
+			contents.to_owned()
+		} else {
+			format!("[{contents}]")
+		};
+
+		match json::from_str::<UnresolvedValue>(&contents)? {
+			// Components greater than 1 are treated as 0-255 range
+			UnresolvedValue::Ambiguous(AmbiguousValue::Array3(color))
+				if color.iter().any(|component| *component > 1.0) =>
+			{
+				UnresolvedValue::Ambiguous(AmbiguousValue::Array3(color.map(|component| component / 255.0)))
+			}
+			value => value,
+		}
+	} else {
+		json::from_str::<UnresolvedValue>(contents)?
+	};
+
+	Ok(Some(value.resolve(class, "Value")?))
+}
+
+fn format_value(value: Variant) -> Result<String> {
+	Ok(match value {
+		Variant::Bool(value) => value.to_string(),
+		Variant::Int32(value) => value.to_string(),
+		Variant::Int64(value) => value.to_string(),
+		Variant::Float32(value) => value.to_string(),
+		Variant::Float64(value) => value.to_string(),
+		Variant::Color3(color) => {
+			let components = [color.r, color.g, color.b].map(|component| component * 255.0);
+
+			// Use hex form only if it doesn't lose precision
+			if components
+				.iter()
+				.all(|component| (component - component.round()).abs() < 0.001 && (0.0..=255.0).contains(component))
+			{
+				format!(
+					"#{:02x}{:02x}{:02x}",
+					components[0].round() as u8,
+					components[1].round() as u8,
+					components[2].round() as u8
+				)
+			} else {
+				format!("[{}, {}, {}]", color.r, color.g, color.b)
+			}
+		}
+		value => bail!("Value of type {:?} cannot be written as plain text", value.ty()),
+	})
+}
+
+fn parse_hex(hex: &str) -> Result<Color3> {
+	if !hex.chars().all(|char| char.is_ascii_hexdigit()) {
+		bail!("Invalid hex color: #{hex}");
+	}
+
+	let hex = match hex.len() {
+		// Short form, e.g. `#f80`
+		3 => hex.chars().flat_map(|char| [char, char]).collect(),
+		6 => hex.to_owned(),
+		_ => bail!("Invalid hex color: #{hex}"),
+	};
+
+	let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap_or_default() as f32 / 255.0;
+
+	Ok(Color3::new(component(0), component(2), component(4)))
+}
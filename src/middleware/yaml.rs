@@ -2,7 +2,7 @@ use anyhow::Result;
 use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
 use std::path::Path;
 
-use crate::{core::snapshot::Snapshot, vfs::Vfs};
+use crate::{core::snapshot::Snapshot, middleware::helpers, vfs::Vfs, Properties};
 
 #[profiling::function]
 pub fn read_yaml(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
@@ -16,3 +16,19 @@ pub fn read_yaml(path: &Path, vfs: &Vfs) -> Result<Snapshot> {
 
 	Ok(Snapshot::new().with_class("ModuleScript").with_properties(properties))
 }
+
+#[profiling::function]
+pub fn write_yaml(mut properties: Properties, path: &Path, vfs: &Vfs) -> Result<Properties> {
+	let value = match helpers::take_lua_source(&mut properties)? {
+		Some(value) => value,
+		None => {
+			vfs.write(path, &[])?;
+			return Ok(properties);
+		}
+	};
+
+	let yaml = helpers::yaml_to_string(&serde_json::to_value(value)?);
+	vfs.write(path, yaml.as_bytes())?;
+
+	Ok(properties)
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use colored::Colorize;
 use rbx_dom_weak::{types::Ref, Ustr, UstrMap};
 use serde::{Deserialize, Serialize};
@@ -17,8 +17,11 @@ use crate::{
 	},
 	ext::{PathExt, ResultExt},
 	glob::Glob,
+	json,
+	middleware::Middleware,
 	resolution::UnresolvedValue,
 	util::get_json_formatter,
+	vfs::Vfs,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -74,6 +77,9 @@ pub struct SyncbackSettings {
 	pub ignore_classes: Vec<String>,
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub ignore_properties: Vec<String>,
+
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub model_classes: HashMap<String, Middleware>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -103,6 +109,8 @@ pub struct Project {
 
 	#[serde(alias = "emitLegacyScripts", skip_serializing_if = "Option::is_none")]
 	pub legacy_scripts: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sync_assets: Option<bool>,
 
 	#[serde(skip)]
 	pub path: PathBuf,
@@ -112,14 +120,34 @@ pub struct Project {
 
 impl Project {
 	pub fn load(project_path: &Path) -> Result<Self> {
-		let project = fs::read_to_string(project_path)?;
-		let mut project: Project = serde_json::from_str(&project).with_desc(|| {
+		Self::parse(&fs::read_to_string(project_path)?, project_path)
+	}
+
+	/// Loads project through the VFS so it can be
+	/// modified as part of the syncback transaction
+	pub fn load_vfs(project_path: &Path, vfs: &Vfs) -> Result<Self> {
+		Self::parse(&vfs.read_to_string(project_path)?, project_path)
+	}
+
+	fn parse(contents: &str, project_path: &Path) -> Result<Self> {
+		let mut project: Project = json::from_str(contents).with_desc(|| {
 			format!(
 				"Failed to parse project at {}",
 				project_path.display().to_string().bold()
 			)
 		})?;
 
+		if let Some(syncback) = &project.syncback {
+			if let Some((class, middleware)) = syncback.model_classes.iter().find(|(_, m)| !m.is_model()) {
+				bail!(
+					"Failed to parse project at {}: class {} cannot be written with {} middleware as it is not a model",
+					project_path.display().to_string().bold(),
+					class.bold(),
+					middleware.to_string().bold()
+				);
+			}
+		}
+
 		let workspace_dir = project_path.get_parent();
 
 		project_path.clone_into(&mut project.path);
@@ -129,13 +157,23 @@ impl Project {
 	}
 
 	pub fn save(&self, path: &Path) -> Result<()> {
+		fs::write(path, self.to_vec()?)?;
+
+		Ok(())
+	}
+
+	/// Saves project through the VFS, see `load_vfs`
+	pub fn save_vfs(&self, path: &Path, vfs: &Vfs) -> Result<()> {
+		json::write(path, &self.to_vec()?, vfs)
+	}
+
+	fn to_vec(&self) -> Result<Vec<u8>> {
 		let mut writer = Vec::new();
 		let mut serializer = Serializer::with_formatter(&mut writer, get_json_formatter());
 
 		self.serialize(&mut serializer)?;
-		fs::write(path, &writer)?;
 
-		Ok(())
+		Ok(writer)
 	}
 
 	pub fn reload(&mut self) -> Result<&Self> {
@@ -213,9 +213,10 @@ impl UnresolvedValue {
 				rect.max.x as f64,
 				rect.max.y as f64,
 			]),
-			// TODO: Implement Ref
-			// Variant::Ref(reference) => AmbiguousValue::
-			//
+			// Refs should be converted to instance paths before serialization
+			// as they are meaningless outside of the current session
+			Variant::Ref(_) => AmbiguousValue::String(String::new()),
+
 			Variant::Region3(region) => AmbiguousValue::Array3Array2([
 				[region.min.x as f64, region.min.y as f64, region.min.z as f64],
 				[region.max.x as f64, region.max.y as f64, region.max.z as f64],
@@ -477,9 +478,10 @@ impl AmbiguousValue {
 					Vector2::new(rect[2] as f32, rect[3] as f32),
 				)
 				.into()),
-				// TODO: Implement Ref
-				// (VariantType::Ref, AmbiguousValue::String(path)) => Ok(),
-				//
+				// Instance paths are kept as strings and resolved
+				// once the whole tree is built, see `Tree::resolve_refs`
+				(VariantType::Ref, AmbiguousValue::String(path)) => Ok(path.into()),
+
 				(VariantType::Region3, AmbiguousValue::Array3Array2(region)) => Ok(Region3::new(
 					Vector3::new(region[0][0] as f32, region[0][1] as f32, region[0][2] as f32),
 					Vector3::new(region[1][0] as f32, region[1][1] as f32, region[1][2] as f32),
@@ -564,6 +566,17 @@ impl AmbiguousValue {
 	}
 }
 
+/// Checks whether the given class (or any of its superclasses) has the property
+pub fn is_property(class: &str, property: &str) -> bool {
+	find_descriptor(class, property).is_some()
+}
+
+/// Checks whether the given property holds a reference to another instance
+pub fn is_ref_property(class: &str, property: &str) -> bool {
+	find_descriptor(class, property)
+		.is_some_and(|descriptor| matches!(descriptor.data_type, DataType::Value(VariantType::Ref)))
+}
+
 fn find_descriptor(class: &str, property: &str) -> Option<&'static PropertyDescriptor<'static>> {
 	let database = get_reflection_database();
 	let mut current_class = class;
@@ -0,0 +1,63 @@
+use actix_web::{
+	body::{BoxBody, MessageBody},
+	dev::{ServiceRequest, ServiceResponse},
+	http::header::AUTHORIZATION,
+	middleware::Next,
+	web::{Data, Query},
+	Error, HttpResponse,
+};
+use log::trace;
+use serde::Deserialize;
+
+/// Secret that every request has to provide when set, either as
+/// `Authorization: Bearer <token>` header or `token` query
+/// parameter (for clients that can't set WebSocket headers)
+pub struct Token(pub Option<String>);
+
+#[derive(Deserialize)]
+struct TokenQuery {
+	token: String,
+}
+
+pub async fn middleware(
+	request: ServiceRequest,
+	next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+	let expected = request.app_data::<Data<Token>>().and_then(|token| token.0.clone());
+
+	let provided = request
+		.headers()
+		.get(AUTHORIZATION)
+		.and_then(|header| header.to_str().ok())
+		.and_then(|header| header.strip_prefix("Bearer "))
+		.map(|token| token.trim().to_owned())
+		.or_else(|| {
+			Query::<TokenQuery>::from_query(request.query_string())
+				.ok()
+				.map(|query| query.into_inner().token)
+		});
+
+	let authorized = match (expected, provided) {
+		(Some(expected), Some(provided)) => compare(expected.as_bytes(), provided.as_bytes()),
+		(None, _) => true,
+		_ => false,
+	};
+
+	if !authorized {
+		trace!("Rejected unauthorized request: {}", request.path());
+
+		let response = HttpResponse::Unauthorized().body("Invalid or missing token");
+		return Ok(request.into_response(response));
+	}
+
+	Ok(next.call(request).await?.map_into_boxed_body())
+}
+
+/// Compares tokens in constant time so they can't be guessed byte by byte
+fn compare(a: &[u8], b: &[u8]) -> bool {
+	if a.len() != b.len() {
+		return false;
+	}
+
+	a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
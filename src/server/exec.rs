@@ -1,16 +1,24 @@
-use actix_msgpack::MsgPack;
-use actix_web::{post, web::Data, HttpResponse, Responder};
+use actix_msgpack::{MsgPack, MsgPackResponseBuilder};
+use actix_web::{
+	post,
+	web::{self, Data},
+	HttpResponse, Responder,
+};
 use log::{error, trace};
 use serde::Deserialize;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
 
-use crate::{core::Core, server, studio};
+use crate::{constants::EXEC_TIMEOUT, core::Core, server, studio};
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct Request {
 	code: String,
 	focus: bool,
+	timeout: Option<u64>,
+	/// Client that runs the code, defaults to the most recent one
+	client_id: Option<u32>,
 }
 
 #[post("/exec")]
@@ -19,24 +27,47 @@ async fn main(request: MsgPack<Request>, core: Data<Arc<Core>>) -> impl Responde
 
 	let queue = core.queue();
 
+	let client_id = match request.client_id.or_else(|| queue.get_last_non_internal_listener_id()) {
+		Some(client_id) => client_id,
+		None => return HttpResponse::ServiceUnavailable().body("No client is connected"),
+	};
+
+	let name = match queue.get_non_internal_listener_name(client_id) {
+		Some(name) => name,
+		None => return HttpResponse::NotFound().body(format!("Client {client_id} is not connected")),
+	};
+
+	let id = Uuid::new_v4().to_string();
+	let receiver = queue.expect_result(&id, client_id);
+
 	let pushed = queue.push(
 		server::ExecuteCode {
+			id: id.clone(),
 			code: request.code.clone(),
 		},
-		None,
+		Some(client_id),
 	);
 
+	if let Err(err) = pushed {
+		queue.forget_result(&id);
+		return HttpResponse::InternalServerError().body(err.to_string());
+	}
+
 	if request.focus {
-		if let Some(name) = queue.get_first_non_internal_listener_name() {
-			match studio::focus(Some(name)) {
-				Ok(()) => (),
-				Err(err) => error!("Failed to focus Roblox Studio: {err}"),
-			}
+		match studio::focus(Some(name)) {
+			Ok(()) => (),
+			Err(err) => error!("Failed to focus Roblox Studio: {err}"),
 		}
 	}
 
-	match pushed {
-		Ok(()) => HttpResponse::Ok().body("Code executed successfully"),
-		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	let timeout = request.timeout.map(Duration::from_secs).unwrap_or(EXEC_TIMEOUT);
+	let result = web::block(move || receiver.recv_timeout(timeout)).await;
+
+	match result {
+		Ok(Ok(result)) => HttpResponse::Ok().msgpack(result),
+		_ => {
+			queue.forget_result(&id);
+			HttpResponse::RequestTimeout().body("Client did not return the result in time")
+		}
 	}
 }
@@ -0,0 +1,16 @@
+use actix_msgpack::MsgPackResponseBuilder;
+use actix_web::{get, web::Data, HttpResponse, Responder};
+use log::trace;
+use std::sync::Arc;
+
+use crate::core::Core;
+
+#[get("/history")]
+async fn main(core: Data<Arc<Core>>) -> impl Responder {
+	trace!("Received request: history");
+
+	match core.history() {
+		Ok(history) => HttpResponse::Ok().msgpack(history),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
@@ -1,28 +1,37 @@
 use actix_msgpack::MsgPackConfig;
 use actix_web::{
+	middleware,
 	web::{self, Data},
 	App, HttpServer, Responder,
 };
 use derive_from_one::FromOne;
 use serde::{Deserialize, Serialize};
 use std::{io::Result, net::TcpListener, sync::Arc};
+use uuid::Uuid;
 
 use crate::{
+	config::Config,
 	constants::MAX_PAYLOAD_SIZE,
 	core::{changes::Changes, Core},
 	project::ProjectDetails,
 };
 
+pub mod auth;
 mod details;
 mod exec;
+mod history;
 mod home;
 mod open;
+mod preview;
 mod read;
+mod result;
 mod snapshot;
 mod stop;
 mod subscribe;
+mod undo;
 mod unsubscribe;
 mod write;
+mod ws;
 
 #[derive(Debug, Clone, Serialize, FromOne)]
 pub enum Message {
@@ -31,11 +40,15 @@ pub enum Message {
 	SyncDetails(SyncDetails),
 	ExecuteCode(ExecuteCode),
 	Disconnect(Disconnect),
+	Resync(Resync),
 }
 
 impl Message {
 	pub fn is_change(&self) -> bool {
-		matches!(self, Message::SyncChanges(_) | Message::SyncbackChanges(_))
+		matches!(
+			self,
+			Message::SyncChanges(_) | Message::SyncbackChanges(_) | Message::Resync(_)
+		)
 	}
 }
 
@@ -50,6 +63,7 @@ pub struct SyncDetails(pub ProjectDetails);
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ExecuteCode {
+	pub id: String,
 	pub code: String,
 }
 
@@ -58,6 +72,41 @@ pub struct Disconnect {
 	pub message: String,
 }
 
+/// Sent instead of changes that did not fit in the client's
+/// queue, client should fetch the whole tree via `/snapshot`
+#[derive(Debug, Clone, Serialize)]
+pub struct Resync {
+	pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExecutionResult {
+	/// Values returned by the code, converted to strings
+	pub values: Vec<String>,
+	/// Error message, `None` if code executed successfully
+	pub error: Option<String>,
+	/// Stack trace of the error
+	pub traceback: Option<String>,
+	/// Messages logged while code was running
+	pub logs: Vec<ExecutionLog>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionLog {
+	pub kind: LogKind,
+	pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LogKind {
+	Output,
+	Info,
+	Warning,
+	Error,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AuthRequest {
@@ -68,20 +117,23 @@ pub struct Server {
 	core: Arc<Core>,
 	host: String,
 	port: u16,
+	token: Option<String>,
 }
 
 impl Server {
-	pub fn new(core: Arc<Core>, host: &str, port: u16) -> Self {
+	pub fn new(core: Arc<Core>, host: &str, port: u16, token: Option<String>) -> Self {
 		Self {
 			core,
 			host: host.to_owned(),
 			port,
+			token,
 		}
 	}
 
 	#[actix_web::main]
 	pub async fn start(&self) -> Result<()> {
 		let core = self.core.clone();
+		let token = Data::new(auth::Token(self.token.clone()));
 
 		HttpServer::new(move || {
 			let mut msgpack_config = MsgPackConfig::default();
@@ -90,14 +142,21 @@ impl Server {
 			App::new()
 				.app_data(Data::new(core.clone()))
 				.app_data(msgpack_config)
+				.app_data(token.clone())
+				.wrap(middleware::from_fn(auth::middleware))
 				.service(details::main)
 				.service(subscribe::main)
 				.service(unsubscribe::main)
 				.service(snapshot::main)
 				.service(read::main)
 				.service(write::main)
+				.service(ws::main)
 				.service(exec::main)
+				.service(result::main)
 				.service(open::main)
+				.service(preview::main)
+				.service(history::main)
+				.service(undo::main)
 				.service(stop::main)
 				.service(home::main)
 				.default_service(web::to(Self::default_redirect))
@@ -133,6 +192,12 @@ pub fn get_free_port(host: &str, port: u16) -> u16 {
 	port
 }
 
+/// Generates random secret used to authenticate clients
+/// if it is required by the `require_token` setting
+pub fn generate_token() -> Option<String> {
+	Config::new().require_token.then(|| Uuid::new_v4().simple().to_string())
+}
+
 pub fn format_address(host: &str, port: u16) -> String {
 	format!("http://{host}:{port}")
 }
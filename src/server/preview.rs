@@ -0,0 +1,32 @@
+use actix_msgpack::{MsgPack, MsgPackResponseBuilder};
+use actix_web::{
+	post,
+	web::{self, Data},
+	HttpResponse, Responder,
+};
+use log::trace;
+use std::sync::Arc;
+
+use crate::core::{processor::WriteRequest, Core};
+
+#[post("/preview")]
+async fn main(request: MsgPack<WriteRequest>, core: Data<Arc<Core>>) -> impl Responder {
+	trace!("Received request: preview");
+
+	let request = request.0;
+
+	if !core.queue().is_subscribed(request.client_id) {
+		return HttpResponse::Unauthorized().body("Not subscribed");
+	}
+
+	// Waiting for the tree lock and writing the preview
+	// must not block the worker that handles other requests
+	let core = core.into_inner();
+	let plan = web::block(move || core.preview(request.changes).map(|preview| preview.plan)).await;
+
+	match plan {
+		Ok(Ok(plan)) => HttpResponse::Ok().msgpack(plan),
+		Ok(Err(err)) => HttpResponse::InternalServerError().body(err.to_string()),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
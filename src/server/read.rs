@@ -1,5 +1,9 @@
 use actix_msgpack::{MsgPack, MsgPackResponseBuilder};
-use actix_web::{post, web::Data, HttpResponse, Responder};
+use actix_web::{
+	post,
+	web::{self, Data},
+	HttpResponse, Responder,
+};
 use log::trace;
 use std::sync::Arc;
 
@@ -10,14 +14,19 @@ async fn main(request: MsgPack<AuthRequest>, core: Data<Arc<Core>>) -> impl Resp
 	trace!("Received request: read");
 
 	let id = request.client_id;
-	let queue = core.queue();
 
-	if !queue.is_subscribed(id) {
+	if !core.queue().is_subscribed(id) {
 		return HttpResponse::Unauthorized().body("Not subscribed");
 	}
 
-	match queue.get_timeout(id) {
-		Ok(message) => HttpResponse::Ok().msgpack(message),
+	// Long polling must not block the worker, otherwise other
+	// requests (like `/result`) could not be handled meanwhile
+	let core = core.into_inner();
+	let message = web::block(move || core.queue().get_timeout(id)).await;
+
+	match message {
+		Ok(Ok(message)) => HttpResponse::Ok().msgpack(message),
+		Ok(Err(err)) => HttpResponse::InternalServerError().body(err.to_string()),
 		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
 	}
 }
@@ -0,0 +1,32 @@
+use actix_msgpack::MsgPack;
+use actix_web::{post, web::Data, HttpResponse, Responder};
+use log::trace;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{core::Core, server::ExecutionResult};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+	client_id: u32,
+	id: String,
+	result: ExecutionResult,
+}
+
+#[post("/result")]
+async fn main(request: MsgPack<Request>, core: Data<Arc<Core>>) -> impl Responder {
+	trace!("Received request: result");
+
+	let request = request.0;
+	let queue = core.queue();
+
+	if !queue.is_subscribed(request.client_id) {
+		return HttpResponse::Unauthorized().body("Not subscribed");
+	}
+
+	match queue.push_result(&request.id, request.client_id, request.result) {
+		Ok(()) => HttpResponse::Ok().body("Result received successfully"),
+		Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+	}
+}
@@ -0,0 +1,33 @@
+use actix_msgpack::{MsgPack, MsgPackResponseBuilder};
+use actix_web::{
+	post,
+	web::{self, Data},
+	HttpResponse, Responder,
+};
+use log::trace;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::core::Core;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+	count: usize,
+}
+
+#[post("/undo")]
+async fn main(request: MsgPack<Request>, core: Data<Arc<Core>>) -> impl Responder {
+	trace!("Received request: undo");
+
+	// Reverting waits for the tree lock and writes files,
+	// so it must not block the worker that handles other requests
+	let core = core.into_inner();
+	let reverted = web::block(move || core.undo(request.count)).await;
+
+	match reverted {
+		Ok(Ok(reverted)) => HttpResponse::Ok().msgpack(reverted),
+		Ok(Err(err)) => HttpResponse::InternalServerError().body(err.to_string()),
+		Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+	}
+}
@@ -0,0 +1,175 @@
+use actix_codec::{Decoder, Encoder};
+use actix_http::ws::{self, Codec, Frame, Item, Message as WsMessage};
+use actix_web::{
+	body::{BodyStream, MessageBody},
+	get,
+	rt::task,
+	web::{BytesMut, Data, Payload, Query},
+	Error, HttpRequest, HttpResponse,
+};
+use anyhow::Result;
+use futures_util::{stream, StreamExt};
+use log::{error, trace, warn};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::{
+	constants::MAX_PAYLOAD_SIZE,
+	core::{changes::Changes, processor::WriteRequest, Core},
+	server::Message,
+};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+	client_id: u32,
+	name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct WriteFrame {
+	changes: Changes,
+}
+
+/// WebSocket alternative to `/subscribe`, `/read` and `/write`,
+/// client is subscribed for the lifetime of the connection
+#[get("/ws")]
+async fn main(
+	request: HttpRequest,
+	query: Query<Request>,
+	payload: Payload,
+	core: Data<Arc<Core>>,
+) -> Result<HttpResponse, Error> {
+	trace!("Received request: ws");
+
+	let mut response = ws::handshake(request.head())?;
+	let id = query.client_id;
+
+	if core.queue().subscribe(id, &query.name).is_err() {
+		return Ok(HttpResponse::BadRequest().body("Already subscribed"));
+	}
+
+	let core = core.get_ref().clone();
+	let (sender, receiver) = mpsc::unbounded_channel();
+
+	forward(core.clone(), sender.clone(), id);
+
+	actix_web::rt::spawn(async move {
+		if let Err(err) = receive(payload, sender, core.clone(), id).await {
+			warn!("WebSocket connection of client {id} failed: {err}");
+		}
+
+		core.queue().unsubscribe(id).ok();
+
+		trace!("Client {id} disconnected from WebSocket");
+	});
+
+	let frames = stream::unfold((receiver, Codec::new()), |(mut receiver, mut codec)| async move {
+		let message = receiver.recv().await?;
+		let mut buffer = BytesMut::new();
+
+		let frame = codec.encode(message, &mut buffer).map(|_| buffer.freeze());
+
+		Some((frame, (receiver, codec)))
+	});
+
+	let response = response.message_body(BodyStream::new(frames).boxed())?;
+
+	Ok(HttpResponse::from(response))
+}
+
+/// Streams messages from the client's queue to the socket on the blocking
+/// thread pool, stops as soon as the client unsubscribes (the buffer is
+/// closed) or within `QUEUE_TIMEOUT` after the socket stream is dropped
+fn forward(core: Arc<Core>, sender: UnboundedSender<WsMessage>, id: u32) {
+	task::spawn_blocking(move || {
+		let queue = core.queue();
+
+		while !sender.is_closed() {
+			let message = match queue.get_timeout(id) {
+				Ok(Some(message)) => message,
+				Ok(None) if queue.is_subscribed(id) => continue,
+				_ => break,
+			};
+
+			let is_disconnect = matches!(message, Message::Disconnect(_));
+
+			match rmp_serde::to_vec_named(&message) {
+				Ok(bytes) => {
+					if sender.send(WsMessage::Binary(bytes.into())).is_err() {
+						break;
+					}
+				}
+				Err(err) => error!("Failed to serialize message for client {id}: {err}"),
+			}
+
+			if is_disconnect {
+				sender.send(WsMessage::Close(None)).ok();
+				break;
+			}
+		}
+
+		trace!("Stopped forwarding messages to client {id}");
+	});
+}
+
+/// Reads frames from the socket and passes
+/// binary ones to the processor as write requests
+async fn receive(mut payload: Payload, sender: UnboundedSender<WsMessage>, core: Arc<Core>, id: u32) -> Result<()> {
+	let mut codec = Codec::new().max_size(MAX_PAYLOAD_SIZE);
+	let mut buffer = BytesMut::new();
+	let mut fragments: Option<BytesMut> = None;
+
+	while let Some(chunk) = payload.next().await {
+		buffer.extend_from_slice(&chunk?);
+
+		while let Some(frame) = codec.decode(&mut buffer)? {
+			let data = match frame {
+				Frame::Binary(data) => data,
+				Frame::Continuation(Item::FirstBinary(data)) => {
+					fragments = Some(BytesMut::from(&data[..]));
+					continue;
+				}
+				Frame::Continuation(Item::Continue(data)) => {
+					if let Some(fragments) = fragments.as_mut() {
+						fragments.extend_from_slice(&data);
+					}
+
+					continue;
+				}
+				Frame::Continuation(Item::Last(data)) => {
+					let Some(mut fragments) = fragments.take() else {
+						continue;
+					};
+
+					fragments.extend_from_slice(&data);
+					fragments.freeze()
+				}
+				Frame::Ping(data) => {
+					sender.send(WsMessage::Pong(data))?;
+					continue;
+				}
+				Frame::Close(reason) => {
+					sender.send(WsMessage::Close(reason)).ok();
+					return Ok(());
+				}
+				Frame::Text(_) | Frame::Continuation(Item::FirstText(_)) => {
+					warn!("Client {id} sent a text frame, only binary MessagePack frames are supported");
+					continue;
+				}
+				Frame::Pong(_) => continue,
+			};
+
+			match rmp_serde::from_slice::<WriteFrame>(&data) {
+				Ok(frame) => core.processor().write(WriteRequest {
+					changes: frame.changes,
+					client_id: id,
+				}),
+				Err(err) => warn!("Client {id} sent invalid write request: {err}"),
+			}
+		}
+	}
+
+	Ok(())
+}
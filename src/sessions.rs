@@ -10,6 +10,8 @@ pub struct Session {
 	pub pid: u32,
 	pub host: Option<String>,
 	pub port: Option<u16>,
+	#[serde(default)]
+	pub token: Option<String>,
 }
 
 impl Session {
@@ -58,10 +60,17 @@ fn set_sessions(sessions: &Sessions) -> Result<()> {
 	Ok(())
 }
 
-pub fn add(id: Option<String>, host: Option<String>, port: Option<u16>, pid: u32, run_async: bool) -> Result<()> {
+pub fn add(
+	id: Option<String>,
+	host: Option<String>,
+	port: Option<u16>,
+	token: Option<String>,
+	pid: u32,
+	run_async: bool,
+) -> Result<()> {
 	let mut sessions = get_sessions()?;
 
-	let session = Session { host, port, pid };
+	let session = Session { host, port, token, pid };
 	let id = id.unwrap_or(generate_id(&sessions));
 
 	sessions.last_session.clone_from(&id);
@@ -2,23 +2,23 @@ use anyhow::Result;
 use roblox_install::RobloxStudio;
 use std::{
 	path::PathBuf,
-	process::{Command, Stdio},
+	process::{Child, Command, Stdio},
 };
 
 #[cfg(target_os = "windows")]
 use winsafe::{co::SW, EnumWindows};
 
-pub fn launch(path: Option<PathBuf>) -> Result<()> {
+pub fn launch(path: Option<PathBuf>) -> Result<Child> {
 	let studio_path = RobloxStudio::locate()?.application_path().to_owned();
 
-	Command::new(studio_path)
+	let child = Command::new(studio_path)
 		.arg(path.unwrap_or_default())
 		.stdin(Stdio::null())
 		.stdout(Stdio::null())
 		.stderr(Stdio::null())
 		.spawn()?;
 
-	Ok(())
+	Ok(child)
 }
 
 #[allow(unused_variables)]
@@ -0,0 +1,175 @@
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::{
+	io::Result,
+	path::{Path, PathBuf},
+};
+
+use super::VfsBackend;
+
+/// Files (with contents) and directories (`None`) in parent-first order
+type Backup = Vec<(PathBuf, Option<Vec<u8>>)>;
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Entry {
+	/// File was written, holds its previous contents or `None` if it was created
+	Write(PathBuf, Option<Vec<u8>>),
+	/// Directory was created, holds the topmost directory that did not exist before
+	CreateDir(PathBuf),
+	/// Path was renamed, holds backup of the path that got replaced
+	Rename(PathBuf, PathBuf, Backup),
+	/// Path was removed, holds backup of it and all its descendants
+	Remove(Backup),
+}
+
+/// Journal of filesystem changes made during a transaction
+/// that holds everything needed to revert them
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+	entries: Vec<Entry>,
+}
+
+impl Journal {
+	pub fn record_write(&mut self, path: &Path, backend: &dyn VfsBackend) -> Result<()> {
+		let contents = if backend.is_file(path) {
+			Some(backend.read(path)?)
+		} else {
+			None
+		};
+
+		self.entries.push(Entry::Write(path.to_owned(), contents));
+
+		Ok(())
+	}
+
+	pub fn record_create_dir(&mut self, path: &Path, backend: &dyn VfsBackend) {
+		if let Some(topmost) = path.ancestors().take_while(|path| !backend.exists(path)).last() {
+			self.entries.push(Entry::CreateDir(topmost.to_owned()));
+		}
+	}
+
+	pub fn record_rename(&mut self, from: &Path, to: &Path, backend: &dyn VfsBackend) -> Result<()> {
+		let backup = backup(to, backend)?;
+
+		self.entries.push(Entry::Rename(from.to_owned(), to.to_owned(), backup));
+
+		Ok(())
+	}
+
+	pub fn record_remove(&mut self, path: &Path, backend: &dyn VfsBackend) -> Result<()> {
+		let backup = backup(path, backend)?;
+
+		self.entries.push(Entry::Remove(backup));
+
+		Ok(())
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns paths affected by all recorded changes
+	pub fn paths(&self) -> Vec<PathBuf> {
+		self.entries
+			.iter()
+			.filter_map(|entry| entry.path().map(Path::to_path_buf))
+			.collect()
+	}
+
+	/// Returns paths of all written files and renamed paths
+	pub fn written_paths(&self) -> Vec<PathBuf> {
+		self.entries
+			.iter()
+			.filter_map(|entry| match entry {
+				Entry::Write(path, _) | Entry::Rename(_, path, _) => Some(path.to_owned()),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Returns all paths that get overwritten when the journal is reverted
+	pub fn reverted_paths(&self) -> Vec<PathBuf> {
+		let mut paths = Vec::new();
+
+		for entry in &self.entries {
+			if let Entry::Rename(from, _, _) = entry {
+				paths.push(from.to_owned());
+			}
+
+			if let Some(path) = entry.path() {
+				paths.push(path.to_owned());
+			}
+		}
+
+		paths
+	}
+
+	/// Reverts all recorded changes in reverse order, keeps going
+	/// when some of them fail and returns the first error
+	pub fn rollback(self, backend: &mut dyn VfsBackend) -> Result<()> {
+		let mut result = Ok(());
+
+		for entry in self.entries.into_iter().rev() {
+			let reverted = match &entry {
+				Entry::Write(path, Some(contents)) => backend.write(path, contents),
+				Entry::Write(path, None) | Entry::CreateDir(path) => backend.remove(path),
+				Entry::Rename(from, to, backup) => backend.rename(to, from).and_then(|_| restore(backup, backend)),
+				Entry::Remove(backup) => restore(backup, backend),
+			};
+
+			if let Err(err) = reverted {
+				error!("Failed to revert changes of {:?}: {}", entry.path(), err);
+
+				if result.is_ok() {
+					result = Err(err);
+				}
+			}
+		}
+
+		result
+	}
+}
+
+impl Entry {
+	fn path(&self) -> Option<&Path> {
+		match self {
+			Entry::Write(path, _) | Entry::CreateDir(path) | Entry::Rename(_, path, _) => Some(path),
+			Entry::Remove(backup) => backup.first().map(|(path, _)| path.as_path()),
+		}
+	}
+}
+
+fn backup(path: &Path, backend: &dyn VfsBackend) -> Result<Backup> {
+	fn walk(path: &Path, backend: &dyn VfsBackend, backup: &mut Backup) -> Result<()> {
+		if backend.is_dir(path) {
+			backup.push((path.to_owned(), None));
+
+			for child in backend.read_dir(path)? {
+				walk(&child, backend, backup)?;
+			}
+		} else {
+			backup.push((path.to_owned(), Some(backend.read(path)?)));
+		}
+
+		Ok(())
+	}
+
+	let mut backup = Vec::new();
+
+	if backend.exists(path) {
+		walk(path, backend, &mut backup)?;
+	}
+
+	Ok(backup)
+}
+
+fn restore(backup: &Backup, backend: &mut dyn VfsBackend) -> Result<()> {
+	for (path, contents) in backup {
+		match contents {
+			Some(contents) => backend.write(path, contents)?,
+			None => backend.create_dir(path)?,
+		}
+	}
+
+	Ok(())
+}
@@ -5,11 +5,18 @@ use std::{
 	sync::Mutex,
 };
 
-use self::{mem_backend::MemBackend, std_backend::StdBackend};
+use self::{
+	journal::Journal,
+	mem_backend::MemBackend,
+	preview::{Operation, Plan, PreviewBackend},
+	std_backend::StdBackend,
+};
 use crate::lock;
 
 pub mod debouncer;
+pub mod journal;
 pub mod mem_backend;
+pub mod preview;
 pub mod std_backend;
 
 #[derive(Debug, Clone)]
@@ -39,6 +46,16 @@ pub trait VfsBackend: Send {
 	fn resume(&mut self);
 
 	fn receiver(&self) -> Receiver<VfsEvent>;
+
+	/// Returns changes that were staged instead of being written to the disk
+	fn plan(&self) -> Option<Plan> {
+		None
+	}
+
+	/// Returns staged operations in the order they were made
+	fn operations(&self) -> Vec<Operation> {
+		Vec::new()
+	}
 }
 
 impl VfsEvent {
@@ -51,21 +68,49 @@ impl VfsEvent {
 
 pub struct Vfs {
 	inner: Mutex<Box<dyn VfsBackend>>,
+	/// Changes made since `begin`, `None` if there is no transaction
+	journal: Mutex<Option<Journal>>,
+	/// Whether snapshots should keep content hashes of their files,
+	/// these are only needed to detect conflicts of two-way sync
+	track_hashes: bool,
 }
 
 impl Vfs {
 	pub fn new(watch: bool) -> Self {
 		Self {
 			inner: Mutex::new(Box::new(StdBackend::new(watch))),
+			journal: Mutex::new(None),
+			track_hashes: false,
 		}
 	}
 
 	pub fn new_virtual() -> Self {
 		Self {
 			inner: Mutex::new(Box::new(MemBackend::new())),
+			journal: Mutex::new(None),
+			track_hashes: false,
+		}
+	}
+
+	/// Creates VFS that reads from the disk but never writes to it,
+	/// all changes can be later retrieved with `plan`
+	pub fn new_preview() -> Self {
+		Self {
+			inner: Mutex::new(Box::new(PreviewBackend::new())),
+			journal: Mutex::new(None),
+			track_hashes: false,
 		}
 	}
 
+	pub fn with_hash_tracking(mut self) -> Self {
+		self.track_hashes = true;
+		self
+	}
+
+	pub fn tracks_hashes(&self) -> bool {
+		self.track_hashes
+	}
+
 	pub fn read(&self, path: &Path) -> Result<Vec<u8>> {
 		lock!(self.inner).read(path)
 	}
@@ -79,19 +124,77 @@ impl Vfs {
 	}
 
 	pub fn write(&self, path: &Path, contents: &[u8]) -> Result<()> {
-		lock!(self.inner).write(path, contents)
+		let mut inner = lock!(self.inner);
+
+		if let Some(journal) = lock!(self.journal).as_mut() {
+			journal.record_write(path, inner.as_ref())?;
+		}
+
+		inner.write(path, contents)
 	}
 
 	pub fn create_dir(&self, path: &Path) -> Result<()> {
-		lock!(self.inner).create_dir(path)
+		let mut inner = lock!(self.inner);
+
+		if let Some(journal) = lock!(self.journal).as_mut() {
+			journal.record_create_dir(path, inner.as_ref());
+		}
+
+		inner.create_dir(path)
 	}
 
 	pub fn rename(&self, from: &Path, to: &Path) -> Result<()> {
-		lock!(self.inner).rename(from, to)
+		let mut inner = lock!(self.inner);
+
+		if let Some(journal) = lock!(self.journal).as_mut() {
+			journal.record_rename(from, to, inner.as_ref())?;
+		}
+
+		inner.rename(from, to)
 	}
 
 	pub fn remove(&self, path: &Path) -> Result<()> {
-		lock!(self.inner).remove(path)
+		let mut inner = lock!(self.inner);
+
+		if let Some(journal) = lock!(self.journal).as_mut() {
+			journal.record_remove(path, inner.as_ref())?;
+		}
+
+		inner.remove(path)
+	}
+
+	/// Starts recording all changes so they can be reverted with `rollback`
+	pub fn begin(&self) {
+		*lock!(self.journal) = Some(Journal::default());
+	}
+
+	/// Stops recording changes and keeps them,
+	/// returns journal that can be used to revert them later
+	pub fn commit(&self) -> Journal {
+		lock!(self.journal).take().unwrap_or_default()
+	}
+
+	/// Returns paths that would be overwritten by `rollback`
+	pub fn pending_paths(&self) -> Vec<PathBuf> {
+		lock!(self.journal)
+			.as_ref()
+			.map(Journal::reverted_paths)
+			.unwrap_or_default()
+	}
+
+	/// Reverts all changes recorded since `begin`
+	pub fn rollback(&self) -> Result<()> {
+		let journal = lock!(self.journal).take();
+
+		match journal {
+			Some(journal) => self.revert(journal),
+			None => Ok(()),
+		}
+	}
+
+	/// Reverts changes recorded in the given journal
+	pub fn revert(&self, journal: Journal) -> Result<()> {
+		journal.rollback(lock!(self.inner).as_mut())
 	}
 
 	pub fn exists(&self, path: &Path) -> bool {
@@ -125,4 +228,26 @@ impl Vfs {
 	pub fn receiver(&self) -> Receiver<VfsEvent> {
 		lock!(self.inner).receiver()
 	}
+
+	pub fn plan(&self) -> Option<Plan> {
+		lock!(self.inner).plan()
+	}
+
+	pub fn operations(&self) -> Vec<Operation> {
+		lock!(self.inner).operations()
+	}
+
+	/// Makes operations staged by a preview VFS, stops at the first failure
+	pub fn replay(&self, operations: &[Operation]) -> Result<()> {
+		for operation in operations {
+			match operation {
+				Operation::Write(path, contents) => self.write(path, contents)?,
+				Operation::CreateDir(path) => self.create_dir(path)?,
+				Operation::Rename(from, to) => self.rename(from, to)?,
+				Operation::Remove(path) => self.remove(path)?,
+			}
+		}
+
+		Ok(())
+	}
 }
@@ -0,0 +1,445 @@
+use colored::Colorize;
+use crossbeam_channel::Receiver;
+use serde::Serialize;
+use similar::TextDiff;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt::{self, Display, Formatter},
+	fs,
+	io::{Error, ErrorKind, Result},
+	path::{Path, PathBuf},
+	time::Duration,
+};
+
+use super::{VfsBackend, VfsEvent};
+use crate::ext::PathExt;
+
+/// Number of unchanged lines shown around each change in diffs
+const DIFF_CONTEXT: usize = 3;
+/// How long diffing a single file can take before a less precise diff is used
+const DIFF_TIMEOUT: Duration = Duration::from_millis(500);
+
+static REMOVED: Staged = Staged::Removed;
+
+#[derive(Debug)]
+enum Staged {
+	File(Vec<u8>),
+	/// Directory created during preview, it never shows the original disk contents
+	Dir,
+	Removed,
+}
+
+/// Filesystem operation made during preview, in the order it was made
+#[derive(Debug, Clone)]
+pub enum Operation {
+	Write(PathBuf, Vec<u8>),
+	CreateDir(PathBuf),
+	Rename(PathBuf, PathBuf),
+	Remove(PathBuf),
+}
+
+/// Backend that reads from the disk but keeps all changes in memory,
+/// used to compute the filesystem plan without touching any files
+pub struct PreviewBackend {
+	staged: BTreeMap<PathBuf, Staged>,
+	renames: Vec<(PathBuf, PathBuf)>,
+	operations: Vec<Operation>,
+	receiver: Receiver<VfsEvent>,
+}
+
+impl PreviewBackend {
+	pub fn new() -> Self {
+		let (_sender, receiver) = crossbeam_channel::unbounded();
+
+		Self {
+			staged: BTreeMap::new(),
+			renames: Vec::new(),
+			operations: Vec::new(),
+			receiver,
+		}
+	}
+
+	/// Returns staged entry of the path, `Removed` if the path is hidden
+	/// by one of its staged parents or `None` if the disk should be used
+	fn get(&self, path: &Path) -> Option<&Staged> {
+		if let Some(staged) = self.staged.get(path) {
+			return Some(staged);
+		}
+
+		if path
+			.ancestors()
+			.skip(1)
+			.any(|ancestor| self.staged.contains_key(ancestor))
+		{
+			return Some(&REMOVED);
+		}
+
+		None
+	}
+
+	fn copy(&mut self, from: &Path, to: &Path) -> Result<()> {
+		if self.is_dir(from) {
+			self.staged.insert(to.to_owned(), Staged::Dir);
+
+			for child in self.read_dir(from)? {
+				self.copy(&child, &to.join(child.get_name()))?;
+			}
+		} else {
+			let contents = self.read(from)?;
+			self.staged.insert(to.to_owned(), Staged::File(contents));
+		}
+
+		Ok(())
+	}
+
+	/// Returns the disk path that the given path originates from
+	fn origin(&self, path: &Path) -> PathBuf {
+		for (from, to) in self.renames.iter().rev() {
+			match path.strip_prefix(to) {
+				// Joining empty suffix would add a trailing slash
+				Ok(suffix) if suffix.as_os_str().is_empty() => return from.to_owned(),
+				Ok(suffix) => return from.join(suffix),
+				Err(_) => {}
+			}
+		}
+
+		path.to_owned()
+	}
+}
+
+impl VfsBackend for PreviewBackend {
+	fn read(&self, path: &Path) -> Result<Vec<u8>> {
+		match self.get(path) {
+			Some(Staged::File(contents)) => Ok(contents.clone()),
+			Some(Staged::Dir) => Err(Error::other(format!("{} is a directory", path.display()))),
+			Some(Staged::Removed) => not_found(path),
+			None => fs::read(path),
+		}
+	}
+
+	fn read_to_string(&self, path: &Path) -> Result<String> {
+		String::from_utf8(self.read(path)?).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+	}
+
+	fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+		let mut paths = BTreeSet::new();
+
+		match self.get(path) {
+			Some(Staged::Dir) => {}
+			Some(_) => return not_found(path),
+			None => {
+				for entry in fs::read_dir(path)? {
+					paths.insert(entry?.path());
+				}
+			}
+		}
+
+		for (child, staged) in self.staged.range(path.to_owned()..) {
+			if !child.starts_with(path) {
+				break;
+			}
+
+			if child.parent() != Some(path) {
+				continue;
+			}
+
+			if let Staged::Removed = staged {
+				paths.remove(child);
+			} else {
+				paths.insert(child.to_owned());
+			}
+		}
+
+		Ok(paths.into_iter().collect())
+	}
+
+	fn write(&mut self, path: &Path, contents: &[u8]) -> Result<()> {
+		self.staged.insert(path.to_owned(), Staged::File(contents.to_owned()));
+		self.operations
+			.push(Operation::Write(path.to_owned(), contents.to_owned()));
+
+		Ok(())
+	}
+
+	fn create_dir(&mut self, path: &Path) -> Result<()> {
+		let missing = path
+			.ancestors()
+			.take_while(|path| !self.exists(path))
+			.map(|path| path.to_owned())
+			.collect::<Vec<_>>();
+
+		for path in missing.into_iter().rev() {
+			self.staged.insert(path, Staged::Dir);
+		}
+
+		self.operations.push(Operation::CreateDir(path.to_owned()));
+
+		Ok(())
+	}
+
+	fn rename(&mut self, from: &Path, to: &Path) -> Result<()> {
+		if !self.exists(from) {
+			return not_found(from);
+		}
+
+		self.copy(from, to)?;
+
+		self.staged.retain(|staged, _| !staged.starts_with(from));
+		self.staged.insert(from.to_owned(), Staged::Removed);
+
+		self.renames.push((from.to_owned(), to.to_owned()));
+		self.operations.push(Operation::Rename(from.to_owned(), to.to_owned()));
+
+		Ok(())
+	}
+
+	fn remove(&mut self, path: &Path) -> Result<()> {
+		self.staged.retain(|staged, _| !staged.starts_with(path));
+		self.staged.insert(path.to_owned(), Staged::Removed);
+		self.operations.push(Operation::Remove(path.to_owned()));
+
+		Ok(())
+	}
+
+	fn exists(&self, path: &Path) -> bool {
+		match self.get(path) {
+			Some(Staged::Removed) => false,
+			Some(_) => true,
+			None => path.exists(),
+		}
+	}
+
+	fn is_dir(&self, path: &Path) -> bool {
+		match self.get(path) {
+			Some(staged) => matches!(staged, Staged::Dir),
+			None => path.is_dir(),
+		}
+	}
+
+	fn is_file(&self, path: &Path) -> bool {
+		match self.get(path) {
+			Some(staged) => matches!(staged, Staged::File(_)),
+			None => path.is_file(),
+		}
+	}
+
+	fn watch(&mut self, _path: &Path, _recursive: bool) -> Result<()> {
+		Ok(())
+	}
+
+	fn unwatch(&mut self, _path: &Path) -> Result<()> {
+		Ok(())
+	}
+
+	fn pause(&mut self) {}
+
+	fn resume(&mut self) {}
+
+	fn receiver(&self) -> Receiver<VfsEvent> {
+		self.receiver.clone()
+	}
+
+	fn operations(&self) -> Vec<Operation> {
+		self.operations.clone()
+	}
+
+	fn plan(&self) -> Option<Plan> {
+		let mut changes = Vec::new();
+
+		let is_renamed = |path: &Path| self.renames.iter().any(|(from, _)| from == path);
+
+		for (from, to) in &self.renames {
+			if from.exists() {
+				changes.push(PlannedChange::Rename {
+					from: from.to_owned(),
+					to: to.to_owned(),
+				});
+			}
+		}
+
+		for (path, staged) in &self.staged {
+			let origin = self.origin(path);
+
+			match staged {
+				Staged::File(contents) => match fs::read(&origin) {
+					Ok(old) if &old == contents => {}
+					Ok(old) => changes.push(PlannedChange::Rewrite {
+						path: path.to_owned(),
+						diff: diff(&old, contents),
+					}),
+					Err(_) => changes.push(PlannedChange::Create {
+						path: path.to_owned(),
+						is_dir: false,
+						diff: diff(&[], contents),
+					}),
+				},
+				Staged::Dir => {
+					if !origin.is_dir() {
+						changes.push(PlannedChange::Create {
+							path: path.to_owned(),
+							is_dir: true,
+							diff: None,
+						});
+
+						continue;
+					}
+
+					// Directory was removed and created again,
+					// its original contents that were not restored are gone
+					for entry in fs::read_dir(&origin).into_iter().flatten().flatten() {
+						let child = path.join(entry.file_name());
+
+						if !self.staged.contains_key(&child) {
+							changes.push(PlannedChange::Remove { path: child });
+						}
+					}
+				}
+				Staged::Removed => {
+					if path.exists() && !is_renamed(path) {
+						changes.push(PlannedChange::Remove { path: path.to_owned() });
+					}
+				}
+			}
+		}
+
+		Some(Plan { changes })
+	}
+}
+
+/// Filesystem changes that would be made by applying client changes
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Plan {
+	pub changes: Vec<PlannedChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PlannedChange {
+	#[serde(rename_all = "camelCase")]
+	Create {
+		path: PathBuf,
+		is_dir: bool,
+		/// Unified diff of text files, `None` for binary ones
+		diff: Option<String>,
+	},
+	Rewrite {
+		path: PathBuf,
+		/// Unified diff of text files, `None` for binary ones
+		diff: Option<String>,
+	},
+	Rename {
+		from: PathBuf,
+		to: PathBuf,
+	},
+	Remove {
+		path: PathBuf,
+	},
+}
+
+impl Plan {
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Makes all paths relative to the given directory
+	pub fn relative_to(mut self, dir: &Path) -> Self {
+		let relative = |path: &mut PathBuf| {
+			if let Ok(stripped) = path.strip_prefix(dir) {
+				*path = stripped.to_owned();
+			}
+		};
+
+		for change in &mut self.changes {
+			match change {
+				PlannedChange::Create { path, .. }
+				| PlannedChange::Rewrite { path, .. }
+				| PlannedChange::Remove { path } => relative(path),
+				PlannedChange::Rename { from, to } => {
+					relative(from);
+					relative(to);
+				}
+			}
+		}
+
+		self
+	}
+}
+
+impl Display for Plan {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		for change in &self.changes {
+			let diff = match change {
+				PlannedChange::Create { path, is_dir, diff } => {
+					let kind = if *is_dir { "directory" } else { "file" };
+
+					writeln!(f, "{} {} {}", "+".green().bold(), kind, path.to_string().bold())?;
+
+					if *is_dir {
+						continue;
+					}
+
+					diff
+				}
+				PlannedChange::Rewrite { path, diff } => {
+					writeln!(f, "{} {}", "~".blue().bold(), path.to_string().bold())?;
+					diff
+				}
+				PlannedChange::Rename { from, to } => {
+					writeln!(
+						f,
+						"{} {} -> {}",
+						">".yellow().bold(),
+						from.to_string().bold(),
+						to.to_string().bold()
+					)?;
+					continue;
+				}
+				PlannedChange::Remove { path } => {
+					writeln!(f, "{} {}", "-".red().bold(), path.to_string().bold())?;
+					continue;
+				}
+			};
+
+			match diff {
+				Some(diff) => {
+					for line in diff.lines() {
+						let line = match line.chars().next() {
+							Some('+') => line.green(),
+							Some('-') => line.red(),
+							Some('@') => line.cyan(),
+							_ => line.normal(),
+						};
+
+						writeln!(f, "    {line}")?;
+					}
+				}
+				None => writeln!(f, "    {}", "binary file".dimmed())?,
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Returns unified line diff of the given contents, `None` if any of them is not text
+fn diff(old: &[u8], new: &[u8]) -> Option<String> {
+	let old = std::str::from_utf8(old).ok()?;
+	let new = std::str::from_utf8(new).ok()?;
+
+	let diff = TextDiff::configure().timeout(DIFF_TIMEOUT).diff_lines(old, new);
+
+	Some(
+		diff.unified_diff()
+			.context_radius(DIFF_CONTEXT)
+			.missing_newline_hint(false)
+			.to_string(),
+	)
+}
+
+fn not_found<T>(path: &Path) -> Result<T> {
+	Err(Error::new(
+		ErrorKind::NotFound,
+		format!("Path {} not found", path.display()),
+	))
+}
@@ -11,7 +11,8 @@ use crate::config::Config;
 pub struct StdBackend {
 	watching: bool,
 	debouncer: VfsDebouncer,
-	watched_paths: Vec<PathBuf>,
+	/// Watched paths and whether they are watched recursively
+	watched_paths: Vec<(PathBuf, bool)>,
 }
 
 impl StdBackend {
@@ -88,12 +89,17 @@ impl VfsBackend for StdBackend {
 	fn watch(&mut self, path: &Path, recursive: bool) -> Result<()> {
 		let path = path.to_owned();
 
-		if !self.watching || self.watched_paths.iter().any(|p| path.starts_with(p)) {
+		if !self.watching
+			|| self
+				.watched_paths
+				.iter()
+				.any(|(p, r)| (*r && path.starts_with(p)) || (!recursive && path == *p))
+		{
 			return Ok(());
 		}
 
 		self.debouncer.watch(&path, recursive)?;
-		self.watched_paths.push(path);
+		self.watched_paths.push((path, recursive));
 
 		Ok(())
 	}
@@ -105,7 +111,7 @@ impl VfsBackend for StdBackend {
 
 		let path = path.to_owned();
 
-		self.watched_paths.retain(|p| {
+		self.watched_paths.retain(|(p, _)| {
 			let unwatch = p.starts_with(&path);
 
 			if unwatch {
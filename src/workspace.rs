@@ -221,6 +221,7 @@ pub fn init_ts(workspace: WorkspaceConfig) -> Result<Option<PathBuf>> {
 			continue;
 		}
 
+		#[allow(clippy::collapsible_match)]
 		match path.get_stem() {
 			"wally" => {
 				if workspace.wally || template == "package" {
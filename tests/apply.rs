@@ -0,0 +1,159 @@
+mod apply {
+	use argon::{
+		core::{
+			changes::Changes,
+			snapshot::{Snapshot, UpdatedSnapshot},
+			Core,
+		},
+		project::Project,
+	};
+	use rbx_dom_weak::{
+		types::{Attributes, Ref, Variant},
+		ustr, HashMapExt, UstrMap,
+	};
+	use std::{fs, path::PathBuf};
+
+	/// Temporary project directory that is removed on drop
+	struct Fixture {
+		dir: PathBuf,
+	}
+
+	impl Fixture {
+		fn new(name: &str) -> Self {
+			let dir = std::env::temp_dir().join(format!("argon-apply-{}-{}", name, std::process::id()));
+
+			fs::remove_dir_all(&dir).ok();
+			fs::create_dir_all(dir.join("src")).unwrap();
+
+			fs::write(dir.join("src/a.lua"), "return 1").unwrap();
+			fs::write(
+				dir.join("src/data.luau"),
+				"--!argon class=Configuration\nreturn {\n\tValue = 1, -- kept\n}\n",
+			)
+			.unwrap();
+			fs::write(
+				dir.join("default.project.json"),
+				r#"{ "name": "Test", "tree": { "$path": "src" } }"#,
+			)
+			.unwrap();
+
+			Self { dir }
+		}
+
+		fn core(&self) -> Core {
+			Core::new(
+				Project::load(&self.dir.join("default.project.json")).unwrap(),
+				false,
+				false,
+			)
+			.unwrap()
+		}
+	}
+
+	impl Drop for Fixture {
+		fn drop(&mut self) {
+			fs::remove_dir_all(&self.dir).ok();
+		}
+	}
+
+	fn child(core: &Core, name: &str) -> Option<Ref> {
+		let tree = core.tree();
+
+		tree.root()
+			.children()
+			.iter()
+			.find(|&&id| tree.get_instance(id).unwrap().name == name)
+			.copied()
+	}
+
+	fn source(core: &Core, name: &str) -> Option<Variant> {
+		let id = child(core, name)?;
+		core.tree().get_instance(id)?.properties.get(&ustr("Source")).cloned()
+	}
+
+	fn properties(name: &str, value: Variant) -> UstrMap<Variant> {
+		let mut properties = UstrMap::new();
+		properties.insert(ustr(name), value);
+		properties
+	}
+
+	#[test]
+	fn revert_partially_applied() {
+		let fixture = Fixture::new("revert");
+		let core = fixture.core();
+		let root = core.tree().root_ref();
+
+		let mut changes = Changes::new();
+
+		changes.add(
+			Snapshot::new()
+				.with_name("new")
+				.with_class("ModuleScript")
+				.with_properties(properties("Source", Variant::String("return 3".into()))),
+			root,
+		);
+
+		let mut update = UpdatedSnapshot::new(child(&core, "a").unwrap());
+		update.properties = Some(properties("Source", Variant::String("return 2".into())));
+		changes.update(update);
+
+		// Data file with comments inside its table can't be rewritten
+		let mut attributes = Attributes::new();
+		attributes.insert("Value".into(), Variant::Float64(2.0));
+
+		let mut update = UpdatedSnapshot::new(child(&core, "data").unwrap());
+		update.properties = Some(properties("Attributes", Variant::Attributes(attributes)));
+		changes.update(update);
+
+		assert!(core.apply(changes).is_err());
+
+		assert_eq!(fs::read_to_string(fixture.dir.join("src/a.lua")).unwrap(), "return 1");
+		assert!(!fixture.dir.join("src/new.luau").exists());
+
+		assert_eq!(source(&core, "a"), Some(Variant::String("return 1".into())));
+		assert_eq!(child(&core, "new"), None);
+		assert!(child(&core, "data").is_some());
+	}
+
+	#[test]
+	fn apply_preview() {
+		let fixture = Fixture::new("preview");
+		let core = fixture.core();
+		let root = core.tree().root_ref();
+
+		let mut changes = Changes::new();
+
+		changes.add(
+			Snapshot::new()
+				.with_name("new")
+				.with_class("ModuleScript")
+				.with_properties(properties("Source", Variant::String("return 3".into()))),
+			root,
+		);
+
+		let mut update = UpdatedSnapshot::new(child(&core, "a").unwrap());
+		update.properties = Some(properties("Source", Variant::String("return 2".into())));
+		changes.update(update);
+
+		let preview = core.preview(changes.clone()).unwrap();
+
+		assert_eq!(preview.plan.changes.len(), 2);
+		assert!(!fixture.dir.join("src/new.luau").exists());
+		assert_eq!(child(&core, "new"), None);
+
+		core.apply_preview(changes, preview).unwrap();
+
+		assert_eq!(fs::read_to_string(fixture.dir.join("src/a.lua")).unwrap(), "return 2");
+		assert_eq!(
+			fs::read_to_string(fixture.dir.join("src/new.luau")).unwrap(),
+			"return 3"
+		);
+
+		assert_eq!(source(&core, "a"), Some(Variant::String("return 2".into())));
+		assert_eq!(source(&core, "new"), Some(Variant::String("return 3".into())));
+
+		assert_eq!(core.undo(1).unwrap().len(), 1);
+		assert_eq!(fs::read_to_string(fixture.dir.join("src/a.lua")).unwrap(), "return 1");
+		assert!(!fixture.dir.join("src/new.luau").exists());
+	}
+}
@@ -0,0 +1,86 @@
+mod assets {
+	use argon::{
+		core::{meta::Meta, snapshot::Snapshot},
+		middleware::asset::{read_asset, write_asset},
+		project::Project,
+		vfs::Vfs,
+	};
+	use rbx_dom_weak::ustr;
+	use std::path::Path;
+
+	fn meta(project: &str) -> Meta {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("/place/default.project.json");
+
+		vfs.write(path, project.as_bytes()).unwrap();
+
+		Meta::from_project(&Project::load_vfs(path, &vfs).unwrap())
+	}
+
+	fn content_id(snapshot: &Snapshot, property: &str) -> Option<String> {
+		snapshot
+			.properties
+			.get(&ustr(property))
+			.map(|value| format!("{value:?}"))
+	}
+
+	#[test]
+	fn asset_lock() {
+		let meta = self::meta(r#"{ "name": "Place", "syncAssets": true, "tree": { "$path": "src" } }"#);
+
+		assert_eq!(
+			meta.context
+				.asset_lock()
+				.map(|lock| lock.path() == Path::new("/place/assets.lock")),
+			Some(true)
+		);
+
+		let meta = self::meta(r#"{ "name": "Place", "tree": { "$path": "src" } }"#);
+
+		assert!(meta.context.asset_lock().is_none());
+	}
+
+	#[test]
+	fn read_locked_assets() {
+		let meta = self::meta(r#"{ "name": "Place", "syncAssets": true, "tree": { "$path": "src" } }"#);
+		let lock = meta.context.asset_lock().unwrap();
+
+		let vfs = Vfs::new_virtual();
+		let image = Path::new("/place/src/logo.png");
+		let audio = Path::new("/place/src/theme.ogg");
+
+		vfs.write(image, b"image").unwrap();
+		vfs.write(audio, b"audio").unwrap();
+		vfs.write(
+			lock.path(),
+			br#""src/logo.png" = { id = "rbxassetid://1", hash = "a" }"#,
+		)
+		.unwrap();
+
+		let snapshot = read_asset(image, &meta.context, &vfs).unwrap();
+
+		assert_eq!(snapshot.class, "Decal");
+		assert!(content_id(&snapshot, "Texture").unwrap().contains("rbxassetid://1"));
+
+		let snapshot = read_asset(audio, &meta.context, &vfs).unwrap();
+
+		assert_eq!(snapshot.class, "Sound");
+		assert!(content_id(&snapshot, "SoundId").is_none());
+
+		// Lock file is cached until it is invalidated
+		vfs.write(
+			lock.path(),
+			br#""src/theme.ogg" = { id = "rbxassetid://2", hash = "b" }"#,
+		)
+		.unwrap();
+
+		assert!(content_id(&read_asset(audio, &meta.context, &vfs).unwrap(), "SoundId").is_none());
+
+		lock.invalidate();
+
+		let snapshot = read_asset(audio, &meta.context, &vfs).unwrap();
+
+		assert!(content_id(&snapshot, "SoundId").unwrap().contains("rbxassetid://2"));
+		assert!(write_asset(snapshot).unwrap().is_empty());
+	}
+}
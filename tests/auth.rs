@@ -0,0 +1,64 @@
+mod auth {
+	use actix_web::{
+		http::{header::AUTHORIZATION, StatusCode},
+		middleware::from_fn,
+		test::{self, TestRequest},
+		web::{self, Data},
+		App, HttpResponse,
+	};
+	use argon::server::{
+		self,
+		auth::{self, Token},
+	};
+
+	async fn status(token: Option<&str>, request: TestRequest) -> StatusCode {
+		let app = test::init_service(
+			App::new()
+				.app_data(Data::new(Token(token.map(str::to_owned))))
+				.wrap(from_fn(auth::middleware))
+				.route("/details", web::get().to(HttpResponse::Ok)),
+		)
+		.await;
+
+		test::call_service(&app, request.to_request()).await.status()
+	}
+
+	#[actix_web::test]
+	async fn rejects_unauthenticated() {
+		let get = || TestRequest::get().uri("/details");
+
+		assert_eq!(status(Some("secret"), get()).await, StatusCode::UNAUTHORIZED);
+		assert_eq!(
+			status(Some("secret"), get().insert_header((AUTHORIZATION, "Bearer wrong"))).await,
+			StatusCode::UNAUTHORIZED
+		);
+		assert_eq!(
+			status(Some("secret"), TestRequest::get().uri("/details?token=secre")).await,
+			StatusCode::UNAUTHORIZED
+		);
+	}
+
+	#[actix_web::test]
+	async fn accepts_token() {
+		assert_eq!(
+			status(
+				Some("secret"),
+				TestRequest::get()
+					.uri("/details")
+					.insert_header((AUTHORIZATION, "Bearer secret"))
+			)
+			.await,
+			StatusCode::OK
+		);
+		assert_eq!(
+			status(Some("secret"), TestRequest::get().uri("/details?token=secret")).await,
+			StatusCode::OK
+		);
+	}
+
+	#[actix_web::test]
+	async fn disabled_by_default() {
+		assert_eq!(server::generate_token(), None);
+		assert_eq!(status(None, TestRequest::get().uri("/details")).await, StatusCode::OK);
+	}
+}
@@ -0,0 +1,98 @@
+mod build {
+	use argon::{
+		core::{changes::Changes, meta::Meta, snapshot::UpdatedSnapshot, Core},
+		project::Project,
+	};
+	use rbx_dom_weak::types::Ref;
+	use std::{fs, path::PathBuf};
+
+	/// Temporary project directory that is removed on drop
+	struct Fixture {
+		dir: PathBuf,
+	}
+
+	impl Fixture {
+		fn new(name: &str, tree: &str) -> Self {
+			let dir = std::env::temp_dir().join(format!("argon-build-{}-{}", name, std::process::id()));
+
+			fs::remove_dir_all(&dir).ok();
+			fs::create_dir_all(dir.join("src")).unwrap();
+
+			fs::write(dir.join("src/a.lua"), "return 1").unwrap();
+			fs::write(
+				dir.join("default.project.json"),
+				format!(r#"{{ "name": "Test", "tree": {tree} }}"#),
+			)
+			.unwrap();
+
+			Self { dir }
+		}
+
+		fn core(&self) -> Core {
+			Core::new(
+				Project::load(&self.dir.join("default.project.json")).unwrap(),
+				false,
+				false,
+			)
+			.unwrap()
+		}
+	}
+
+	impl Drop for Fixture {
+		fn drop(&mut self) {
+			fs::remove_dir_all(&self.dir).ok();
+		}
+	}
+
+	fn update(id: Ref, modify: impl FnOnce(&mut UpdatedSnapshot)) -> Changes {
+		let mut snapshot = UpdatedSnapshot::new(id);
+		modify(&mut snapshot);
+
+		let mut changes = Changes::new();
+		changes.update(snapshot);
+		changes
+	}
+
+	#[test]
+	fn replace_build() {
+		let fixture = Fixture::new("replace", r#"{ "$path": "src" }"#);
+		let core = fixture.core();
+		let path = fixture.dir.join("model.rbxm");
+
+		fs::write(&path, "old").unwrap();
+		core.build(&path, false).unwrap();
+
+		let dom = rbx_binary::from_reader(fs::read(&path).unwrap().as_slice()).unwrap();
+
+		assert_eq!(dom.root().children().len(), 1);
+		assert_eq!(fs::read_dir(&fixture.dir).unwrap().count(), 3);
+	}
+
+	#[test]
+	fn affects_build() {
+		let fixture = Fixture::new("affects", r#"{ "$path": "src" }"#);
+		let core = fixture.core();
+		let root = core.tree().root_ref();
+
+		assert!(!core.affects_build(&Changes::new()));
+		assert!(!core.affects_build(&update(root, |update| update.meta = Some(Meta::new()))));
+		assert!(core.affects_build(&update(root, |update| update.name = Some(String::from("Renamed")))));
+
+		let mut changes = Changes::new();
+		changes.remove(root);
+
+		assert!(core.affects_build(&changes));
+	}
+
+	#[test]
+	fn ignore_place_root() {
+		let fixture = Fixture::new(
+			"place",
+			r#"{ "$className": "DataModel", "Workspace": { "$path": "src" } }"#,
+		);
+		let core = fixture.core();
+		let root = core.tree().root_ref();
+
+		assert!(!core.affects_build(&update(root, |update| update.name = Some(String::from("Renamed")))));
+	}
+}
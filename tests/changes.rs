@@ -0,0 +1,106 @@
+mod changes {
+	use argon::core::{
+		changes::Changes,
+		snapshot::{Snapshot, UpdatedSnapshot},
+	};
+	use rbx_dom_weak::types::Ref;
+
+	fn added(id: Ref, parent: Ref) -> Changes {
+		let mut changes = Changes::new();
+		changes.add(Snapshot::new().with_id(id), parent);
+		changes
+	}
+
+	fn updated(id: Ref) -> Changes {
+		let mut changes = Changes::new();
+		changes.update(UpdatedSnapshot::new(id));
+		changes
+	}
+
+	fn removed(id: Ref) -> Changes {
+		let mut changes = Changes::new();
+		changes.remove(id);
+		changes
+	}
+
+	fn ids(changes: &Changes) -> (Vec<Ref>, Vec<Ref>, Vec<Ref>) {
+		(
+			changes.additions.iter().map(|addition| addition.id).collect(),
+			changes.updates.iter().map(|update| update.id).collect(),
+			changes.removals.clone(),
+		)
+	}
+
+	#[test]
+	fn remove_added_parent() {
+		let (root, parent, child, grandchild) = (Ref::new(), Ref::new(), Ref::new(), Ref::new());
+
+		let mut changes = added(parent, root);
+		changes.merge(added(child, parent));
+		changes.merge(added(grandchild, child));
+		changes.merge(updated(grandchild));
+
+		assert_eq!(ids(&changes), (vec![parent, child, grandchild], vec![], vec![]));
+
+		changes.merge(removed(parent));
+
+		assert!(changes.is_empty());
+	}
+
+	#[test]
+	fn remove_existing_parent() {
+		let (parent, sibling, child, grandchild, nested) = (Ref::new(), Ref::new(), Ref::new(), Ref::new(), Ref::new());
+
+		let mut changes = updated(parent);
+		changes.merge(added(child, parent));
+		changes.merge(added(sibling, Ref::new()));
+
+		let mut with_nested = Changes::new();
+		with_nested.add(
+			Snapshot::new()
+				.with_id(grandchild)
+				.with_children(vec![Snapshot::new().with_id(nested)]),
+			child,
+		);
+
+		changes.merge(with_nested);
+		changes.merge(updated(nested));
+		changes.merge(added(Ref::new(), nested));
+
+		changes.merge(removed(parent));
+
+		assert_eq!(ids(&changes), (vec![sibling], vec![], vec![parent]));
+	}
+
+	#[test]
+	fn remove_nested_child() {
+		let (root, parent, child, nested) = (Ref::new(), Ref::new(), Ref::new(), Ref::new());
+
+		let mut addition = Changes::new();
+		addition.add(
+			Snapshot::new().with_id(parent).with_children(vec![Snapshot::new()
+				.with_id(child)
+				.with_children(vec![Snapshot::new().with_id(nested)])]),
+			root,
+		);
+
+		let mut changes = addition;
+		changes.merge(added(Ref::new(), nested));
+		changes.merge(removed(child));
+
+		assert_eq!(ids(&changes), (vec![parent], vec![], vec![]));
+		assert!(changes.additions[0].children.is_empty());
+	}
+
+	#[test]
+	fn fold_updates() {
+		let (root, parent, existing) = (Ref::new(), Ref::new(), Ref::new());
+
+		let mut changes = added(parent, root);
+		changes.merge(updated(existing));
+		changes.merge(removed(existing));
+		changes.merge(removed(existing));
+
+		assert_eq!(ids(&changes), (vec![parent], vec![], vec![existing]));
+	}
+}
@@ -0,0 +1,71 @@
+mod csv {
+	use argon::{
+		middleware::csv::{read_csv, write_csv},
+		vfs::Vfs,
+	};
+	use rbx_dom_weak::{types::Variant, ustr};
+	use serde_json::{json, Value};
+	use std::path::Path;
+
+	const CSV: &str =
+		"Key,Source,de,Context,fr\r\nhello,Hello,Hallo,Greeting,Bonjour\r\nbye,Bye,Tschüss,,Au revoir\r\n";
+
+	/// Reads the CSV file, lets the caller modify its entries and writes them back
+	fn modify(contents: &str, modify: impl FnOnce(&mut Vec<Value>)) -> String {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("table.csv");
+
+		vfs.write(path, contents.as_bytes()).unwrap();
+
+		let mut properties = read_csv(path, &vfs).unwrap().properties;
+
+		let mut entries: Vec<Value> = match properties.get(&ustr("Contents")) {
+			Some(Variant::String(contents)) => serde_json::from_str(contents).unwrap(),
+			_ => panic!("missing Contents property"),
+		};
+
+		modify(&mut entries);
+
+		properties.insert(
+			ustr("Contents"),
+			Variant::String(serde_json::to_string(&entries).unwrap()),
+		);
+
+		write_csv(properties, path, &vfs).unwrap();
+
+		vfs.read_to_string(path).unwrap()
+	}
+
+	#[test]
+	fn keep_unchanged_file() {
+		let contents = CSV.replace("Hallo", "\"Hallo\"");
+
+		assert_eq!(modify(&contents, |_| {}), contents);
+	}
+
+	#[test]
+	fn keep_column_order() {
+		let contents = modify(CSV, |entries| {
+			entries[1]["values"]["de"] = json!("Auf Wiedersehen");
+		});
+
+		assert_eq!(
+			contents,
+			"Key,Source,de,Context,fr\r\nhello,Hello,Hallo,Greeting,Bonjour\r\nbye,Bye,Auf Wiedersehen,,Au revoir\r\n"
+		);
+	}
+
+	#[test]
+	fn append_new_columns() {
+		let contents = modify("Key,Source,fr\nhello,Hello,Bonjour\n", |entries| {
+			entries[0]["values"]["pl"] = json!("Cześć");
+			entries[0]["values"]["es"] = json!("Hola");
+			entries[0]["example"] = json!("Hello!");
+		});
+
+		assert_eq!(
+			contents,
+			"Key,Source,fr,Example,es,pl\nhello,Hello,Bonjour,Hello!,Hola,Cześć\n"
+		);
+	}
+}
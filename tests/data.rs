@@ -0,0 +1,87 @@
+mod data {
+	use argon::{
+		core::meta::Meta,
+		middleware::data::{read_data, write_data, DataSnapshot},
+		vfs::Vfs,
+	};
+	use rbx_dom_weak::{
+		types::{Tags, Variant},
+		ustr, HashMapExt, UstrMap,
+	};
+	use std::path::Path;
+
+	fn read(path: &str, contents: &str, vfs: &Vfs) -> DataSnapshot {
+		let path = Path::new(path);
+
+		vfs.write(path, contents.as_bytes()).unwrap();
+
+		read_data(path, None, vfs).unwrap()
+	}
+
+	fn assert_data(data: &DataSnapshot) {
+		assert_eq!(data.class.as_deref(), Some("Part"));
+		assert_eq!(data.keep_unknowns, Some(true));
+		assert_eq!(data.properties.get(&ustr("Anchored")), Some(&Variant::Bool(true)));
+		assert_eq!(
+			data.properties.get(&ustr("Tags")),
+			Some(&Variant::Tags(Tags::from(vec![String::from("Enemy")])))
+		);
+	}
+
+	#[test]
+	fn read_formats() {
+		let vfs = Vfs::new_virtual();
+
+		assert_data(&read(
+			"part.data.json",
+			r#"{ "className": "Part", "properties": { "Anchored": true }, "tags": ["Enemy"], "keepUnknowns": true }"#,
+			&vfs,
+		));
+
+		assert_data(&read(
+			"part.data.toml",
+			"className = \"Part\"\ntags = [\"Enemy\"]\nkeepUnknowns = true\n\n[properties]\nAnchored = true\n",
+			&vfs,
+		));
+
+		assert_data(&read(
+			"part.data.yaml",
+			"className: Part\nproperties:\n  Anchored: true\ntags:\n  - Enemy\nkeepUnknowns: true\n",
+			&vfs,
+		));
+
+		assert_data(&read(
+			"part.data.yml",
+			"className: Part\nproperties: { Anchored: true }\ntags: [Enemy]\nkeepUnknowns: true\n",
+			&vfs,
+		));
+	}
+
+	#[test]
+	fn write_formats() {
+		let vfs = Vfs::new_virtual();
+		let meta = Meta::new().with_keep_unknowns(true);
+
+		for path in ["part.data.json", "part.data.toml", "part.data.yaml"] {
+			let mut properties = UstrMap::new();
+			properties.insert(ustr("Anchored"), Variant::Bool(true));
+
+			write_data(false, "Part", properties, Path::new(path), &meta, &vfs).unwrap();
+
+			let data = read_data(Path::new(path), None, &vfs).unwrap();
+
+			assert_eq!(data.class.as_deref(), Some("Part"));
+			assert_eq!(data.keep_unknowns, Some(true));
+			assert_eq!(data.properties.get(&ustr("Anchored")), Some(&Variant::Bool(true)));
+		}
+
+		assert!(vfs
+			.read_to_string(Path::new("part.data.toml"))
+			.unwrap()
+			.contains("className = \"Part\""));
+		assert!(vfs
+			.read_to_string(Path::new("part.data.yaml"))
+			.unwrap()
+			.contains("className: Part"));
+	}
+}
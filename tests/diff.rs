@@ -0,0 +1,178 @@
+mod diff {
+	use argon::core::{changes::Changes, helpers::diff, snapshot::Snapshot, tree::Tree};
+	use rbx_dom_weak::{
+		types::{Ref, Variant},
+		ustr, InstanceBuilder, WeakDom,
+	};
+
+	/// Returns ref of the first instance with the given name path, starting from the root
+	fn find(dom: &WeakDom, path: &str) -> Ref {
+		path.split('/').fold(dom.root_ref(), |id, name| {
+			*dom.get_by_ref(id)
+				.unwrap()
+				.children()
+				.iter()
+				.find(|&&child| dom.get_by_ref(child).unwrap().name == name)
+				.unwrap()
+		})
+	}
+
+	fn instance(name: &str, class: &str) -> Snapshot {
+		Snapshot::new().with_name(name).with_class(class)
+	}
+
+	fn set_ref(dom: &mut WeakDom, path: &str, target: Ref) {
+		let id = find(dom, path);
+
+		dom.get_by_ref_mut(id)
+			.unwrap()
+			.properties
+			.insert(ustr("Value"), Variant::Ref(target));
+	}
+
+	fn names(changes: &Changes) -> (Vec<&str>, Vec<Ref>) {
+		(
+			changes
+				.additions
+				.iter()
+				.map(|addition| addition.name.as_str())
+				.collect(),
+			changes.removals.clone(),
+		)
+	}
+
+	#[test]
+	fn pairing() {
+		let tree = Tree::new(instance("Root", "Model").with_children(vec![
+			instance("A", "Part"),
+			instance("B", "Folder"),
+			instance("C", "Folder"),
+		]));
+
+		let dom = WeakDom::new(
+			InstanceBuilder::new("Model").with_name("Root").with_children([
+				InstanceBuilder::new("Part")
+					.with_name("A")
+					.with_property("Transparency", 0.5f32),
+				InstanceBuilder::new("Configuration").with_name("B"),
+				InstanceBuilder::new("Folder")
+					.with_name("C")
+					.with_child(InstanceBuilder::new("Folder").with_name("D")),
+			]),
+		);
+
+		let changes = diff::diff(&dom, dom.root_ref(), &tree, false);
+
+		// Instances are paired by both name and class, children of paired ones are compared too
+		assert_eq!(names(&changes), (vec!["D", "B"], vec![find(tree.inner(), "B")]));
+		assert_eq!(changes.additions[0].parent, find(tree.inner(), "C"));
+		assert_eq!(changes.additions[1].class, "Configuration");
+
+		assert_eq!(changes.updates.len(), 1);
+
+		let update = &changes.updates[0];
+
+		assert_eq!(update.id, find(tree.inner(), "A"));
+		assert_eq!(
+			update.properties.as_ref().unwrap().get(&ustr("Transparency")),
+			Some(&Variant::Float32(0.5))
+		);
+	}
+
+	#[test]
+	fn duplicate_names() {
+		let tree = Tree::new(
+			instance("Root", "Model").with_children(vec![instance("Part", "Part"), instance("Part", "Part")]),
+		);
+
+		let three = WeakDom::new(InstanceBuilder::new("Model").with_children([
+			InstanceBuilder::new("Part").with_name("Part"),
+			InstanceBuilder::new("Part").with_name("Part"),
+			InstanceBuilder::new("Part").with_name("Part"),
+		]));
+
+		let changes = diff::diff(&three, three.root_ref(), &tree, false);
+
+		assert_eq!(names(&changes), (vec!["Part"], vec![]));
+		assert!(changes.updates.is_empty());
+
+		let one =
+			WeakDom::new(InstanceBuilder::new("Model").with_child(InstanceBuilder::new("Part").with_name("Part")));
+
+		let changes = diff::diff(&one, one.root_ref(), &tree, false);
+
+		assert_eq!(names(&changes), (vec![], vec![tree.root().children()[1]]));
+	}
+
+	#[test]
+	fn refs() {
+		let tree = Tree::new(
+			instance("Root", "Model").with_children(vec![instance("A", "Part"), instance("Link", "ObjectValue")]),
+		);
+
+		let mut dom = WeakDom::new(InstanceBuilder::new("Model").with_children([
+			InstanceBuilder::new("Part").with_name("A"),
+			InstanceBuilder::new("ObjectValue").with_name("Link"),
+			InstanceBuilder::new("Folder").with_name("New").with_children([
+				InstanceBuilder::new("ObjectValue").with_name("ToPaired"),
+				InstanceBuilder::new("ObjectValue").with_name("ToAdded"),
+			]),
+		]));
+
+		let (a, new) = (find(&dom, "A"), find(&dom, "New"));
+
+		set_ref(&mut dom, "Link", new);
+		set_ref(&mut dom, "New/ToPaired", a);
+		set_ref(&mut dom, "New/ToAdded", new);
+
+		let changes = diff::diff(&dom, dom.root_ref(), &tree, false);
+
+		// Added instances keep DOM refs that are used as their IDs
+		assert_eq!(changes.additions[0].id, new);
+		assert_eq!(
+			changes.updates[0].properties.as_ref().unwrap().get(&ustr("Value")),
+			Some(&Variant::Ref(new))
+		);
+
+		let children = &changes.additions[0].children;
+
+		assert_eq!(
+			children[0].properties.get(&ustr("Value")),
+			Some(&Variant::Ref(find(tree.inner(), "A")))
+		);
+		assert_eq!(children[1].properties.get(&ustr("Value")), Some(&Variant::Ref(new)));
+	}
+
+	#[test]
+	fn place() {
+		let tree = Tree::new(instance("Game", "DataModel").with_children(vec![
+			instance("Workspace", "Workspace").with_children(vec![instance("Link", "ObjectValue")]),
+			instance("ServerStorage", "ServerStorage"),
+		]));
+
+		let mut dom = WeakDom::new(
+			InstanceBuilder::new("DataModel").with_children([
+				InstanceBuilder::new("Workspace").with_name("Workspace").with_children([
+					InstanceBuilder::new("ObjectValue").with_name("Link"),
+					InstanceBuilder::new("ObjectValue").with_name("New"),
+				]),
+				InstanceBuilder::new("Lighting")
+					.with_name("Lighting")
+					.with_child(InstanceBuilder::new("Sky").with_name("Sky")),
+			]),
+		);
+
+		let sky = find(&dom, "Lighting/Sky");
+
+		set_ref(&mut dom, "Workspace/Link", sky);
+		set_ref(&mut dom, "Workspace/New", sky);
+
+		let changes = diff::diff(&dom, dom.root_ref(), &tree, true);
+
+		// Unknown services are skipped, services missing in the file are kept
+		// and refs to instances that are not synced are dropped
+		assert_eq!(names(&changes), (vec!["New"], vec![]));
+		assert!(changes.updates.is_empty());
+		assert!(changes.additions[0].properties.is_empty());
+	}
+}
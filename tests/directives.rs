@@ -0,0 +1,114 @@
+mod directives {
+	use argon::{
+		core::meta::Context,
+		middleware::luau::{read_luau, write_luau, ScriptType},
+		vfs::Vfs,
+		Properties,
+	};
+	use rbx_dom_weak::{
+		types::{Enum, Variant},
+		ustr,
+	};
+	use std::path::Path;
+
+	fn read(source: &str, script_type: ScriptType, vfs: &Vfs) -> Properties {
+		let path = Path::new("script.luau");
+
+		vfs.write(path, source.as_bytes()).unwrap();
+
+		read_luau(path, &Context::default(), vfs, script_type)
+			.unwrap()
+			.properties
+	}
+
+	fn attribute(properties: &Properties, name: &str) -> Option<Variant> {
+		match properties.get(&ustr("Attributes")) {
+			Some(Variant::Attributes(attributes)) => attributes.get(name).cloned(),
+			_ => None,
+		}
+	}
+
+	#[test]
+	fn read_directives() {
+		let vfs = Vfs::new_virtual();
+		let properties = read(
+			"--@argon runContext=Client disabled\n--@argon tag=Enemy speed=16 name=\"Big boss\" code=\"1\"\nprint('Hello')\n",
+			ScriptType::Server,
+			&vfs,
+		);
+
+		assert_eq!(
+			properties.get(&ustr("Source")),
+			Some(&Variant::String("print('Hello')\n".into()))
+		);
+		assert_eq!(
+			properties.get(&ustr("RunContext")),
+			Some(&Variant::Enum(Enum::from_u32(2)))
+		);
+		assert_eq!(properties.get(&ustr("Disabled")), Some(&Variant::Bool(true)));
+
+		match properties.get(&ustr("Tags")) {
+			Some(Variant::Tags(tags)) => assert_eq!(tags.iter().collect::<Vec<_>>(), vec!["Enemy"]),
+			_ => panic!("missing Tags property"),
+		}
+
+		assert_eq!(attribute(&properties, "speed"), Some(Variant::Float64(16.0)));
+		assert_eq!(attribute(&properties, "name"), Some(Variant::String("Big boss".into())));
+		assert_eq!(attribute(&properties, "code"), Some(Variant::String("1".into())));
+	}
+
+	#[test]
+	fn ignore_unsupported() {
+		let vfs = Vfs::new_virtual();
+		let properties = read(
+			"--@argon runContext=Client disabled\nreturn {}\n",
+			ScriptType::Module,
+			&vfs,
+		);
+
+		assert!(!properties.contains_key(&ustr("RunContext")));
+		assert!(!properties.contains_key(&ustr("Disabled")));
+
+		// Directives must be at the very top of the file
+		let properties = read("print('Hello')\n--@argon disabled\n", ScriptType::Server, &vfs);
+
+		assert!(!properties.contains_key(&ustr("Disabled")));
+	}
+
+	#[test]
+	fn round_trip() {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("script.luau");
+		let source =
+			"--@argon runContext=Client disabled\n--@argon tag=Enemy name=\"Big boss\" speed=16\nprint('Hello')\n";
+
+		let properties = read(source, ScriptType::Server, &vfs);
+		let remaining = write_luau(properties, path, &vfs).unwrap();
+
+		assert!(remaining.is_empty());
+		assert_eq!(
+			read(&vfs.read_to_string(path).unwrap(), ScriptType::Server, &vfs),
+			read(source, ScriptType::Server, &vfs)
+		);
+	}
+
+	#[test]
+	fn keep_data_outside_of_plain_scripts() {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("script.luau");
+		let mut properties = read("--@argon tag=Enemy\nprint('Hello')\n", ScriptType::Server, &vfs);
+
+		// Without existing directives only script properties are written to the header
+		vfs.write(path, b"print('Hello')\n").unwrap();
+		properties.insert(ustr("RunContext"), Variant::Enum(Enum::from_u32(1)));
+		properties.insert(ustr("Disabled"), Variant::Bool(true));
+
+		let remaining = write_luau(properties, path, &vfs).unwrap();
+
+		assert!(remaining.contains_key(&ustr("Tags")));
+		assert_eq!(
+			vfs.read_to_string(path).unwrap(),
+			"--@argon runContext=Server\n--@argon disabled\nprint('Hello')\n"
+		);
+	}
+}
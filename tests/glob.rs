@@ -0,0 +1,234 @@
+mod glob {
+	use argon::{
+		core::meta::IgnoreRule,
+		glob::{Glob, GlobSet},
+	};
+	use std::{
+		fs,
+		path::{Path, PathBuf},
+	};
+
+	fn glob(pattern: &str) -> Glob {
+		Glob::new(pattern).unwrap()
+	}
+
+	fn gitignore(pattern: &str) -> Glob {
+		Glob::gitignore(pattern).unwrap()
+	}
+
+	fn set(patterns: &[&str]) -> GlobSet {
+		GlobSet::new(patterns.iter().map(|pattern| glob(pattern)).collect())
+	}
+
+	#[test]
+	fn regular() {
+		assert!(glob("*.lua").matches_path(Path::new("src/deep/file.lua")));
+		assert!(glob("src/*.lua").matches_path(Path::new("src/deep/file.lua")));
+		assert!(glob("**/*.json").matches_path(Path::new("a/b/c.json")));
+		assert!(glob("src/**").matches_path(Path::new("src/a/b")));
+
+		assert!(!glob("src/*.lua").matches_path(Path::new("lib/file.lua")));
+		assert!(!glob("file.lua").matches_path(Path::new("src/file.lua")));
+		assert!(!glob("*.lua").matches_path(Path::new("file.luau")));
+
+		assert!(glob("**/tsconfig.json").matches("**/tsconfig.json"));
+	}
+
+	#[test]
+	fn regular_dirs() {
+		assert!(glob("src/vendor/**").matches_dir(Path::new("src/vendor")));
+		assert!(glob("src/vendor/**").matches_dir(Path::new("src/vendor/lib")));
+		assert!(!glob("src/vendor/**").matches_path(Path::new("src/vendor")));
+	}
+
+	#[test]
+	fn gitignore_style() {
+		assert!(gitignore("*.lua").matches_path(Path::new("src/deep/file.lua")));
+		assert!(gitignore("file.lua").matches_path(Path::new("src/file.lua")));
+
+		assert!(gitignore("src/*.lua").matches_path(Path::new("src/file.lua")));
+		assert!(!gitignore("src/*.lua").matches_path(Path::new("src/deep/file.lua")));
+		assert!(gitignore("/file.lua").matches_path(Path::new("file.lua")));
+		assert!(!gitignore("/file.lua").matches_path(Path::new("src/file.lua")));
+
+		assert!(gitignore("build/").matches_entry(Path::new("src/build"), true));
+		assert!(!gitignore("build/").matches_entry(Path::new("src/build"), false));
+	}
+
+	#[test]
+	fn negation() {
+		let globs = set(&["src/**", "!src/vendor/**", "src/vendor/keep.lua"]);
+
+		assert!(globs.matches_path(Path::new("src/main.lua")));
+		assert!(!globs.matches_path(Path::new("src/vendor/lib.lua")));
+		assert!(globs.matches_path(Path::new("src/vendor/keep.lua")));
+		assert!(!globs.matches_path(Path::new("lib/main.lua")));
+
+		assert_eq!(globs.evaluate(Path::new("src/vendor/lib.lua"), false), Some(false));
+		assert_eq!(globs.evaluate(Path::new("lib/main.lua"), false), None);
+
+		assert!(!set(&["!*.lua"]).matches_path(Path::new("main.lua")));
+	}
+
+	#[test]
+	fn brace_expansion() {
+		let glob = glob("src/{client,server}/*.{lua,luau}");
+
+		assert!(glob.matches_path(Path::new("src/client/main.lua")));
+		assert!(glob.matches_path(Path::new("src/server/main.luau")));
+		assert!(!glob.matches_path(Path::new("src/shared/main.lua")));
+		assert!(!glob.matches_path(Path::new("src/client/main.json")));
+
+		let nested = self::glob("{a,b{c,d}}.txt");
+
+		assert!(nested.matches_path(Path::new("a.txt")));
+		assert!(nested.matches_path(Path::new("bc.txt")));
+		assert!(nested.matches_path(Path::new("bd.txt")));
+		assert!(!nested.matches_path(Path::new("b.txt")));
+
+		assert!(self::glob("[{]a,b}.txt").matches_path(Path::new("{a,b}.txt")));
+		assert_eq!(self::glob("!{a,b}").as_str(), "!{a,b}");
+	}
+
+	#[test]
+	fn ignore_file() {
+		let rule = IgnoreRule::from_ignore_file(
+			"# comment\n\n*.tmp\n/root.lua\nbuild/\n!keep.tmp\n\\#hash\n",
+			Path::new("project"),
+		);
+
+		let evaluate = |path: &str, is_dir: bool| rule.evaluate(Path::new(path), is_dir);
+
+		assert_eq!(evaluate("project/deep/file.tmp", false), Some(true));
+		assert_eq!(evaluate("project/deep/keep.tmp", false), Some(false));
+		assert_eq!(evaluate("project/root.lua", false), Some(true));
+		assert_eq!(evaluate("project/src/root.lua", false), None);
+		// `!keep.tmp` could match inside, so the directory is entered and its contents are ignored instead
+		assert_eq!(evaluate("project/src/build", true), Some(false));
+		assert_eq!(evaluate("project/src/build/main.lua", false), Some(true));
+		assert_eq!(evaluate("project/src/build/keep.tmp", false), Some(false));
+		assert_eq!(evaluate("project/src/build", false), None);
+		assert_eq!(evaluate("project/#hash", false), Some(true));
+		assert_eq!(evaluate("other/file.tmp", false), None);
+	}
+
+	#[test]
+	fn filesystem() {
+		let dir = std::env::temp_dir().join(format!("argon-glob-test-{}", std::process::id()));
+
+		fs::create_dir_all(&dir).unwrap();
+
+		for file in ["a.lua", "b.luau", "c.json"] {
+			fs::write(dir.join(file), "").unwrap();
+		}
+
+		let mut paths = Glob::from_path(&dir.join("*.{lua,luau}"))
+			.unwrap()
+			.iter()
+			.collect::<Vec<PathBuf>>();
+		paths.sort();
+
+		assert_eq!(paths, vec![dir.join("a.lua"), dir.join("b.luau")]);
+		assert_eq!(
+			Glob::from_path(&dir.join("*.json")).unwrap().first(),
+			Some(dir.join("c.json"))
+		);
+		assert_eq!(Glob::from_path(&dir.join("*.txt")).unwrap().first(), None);
+		assert_eq!(
+			Glob::new(&format!("!{}", dir.join("*").display())).unwrap().first(),
+			None
+		);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
+
+mod ignore {
+	use argon::{core::snapshot::Snapshot, middleware::project::read_project, vfs::Vfs};
+	use std::{fs, path::PathBuf};
+
+	/// Temporary project directory that is removed on drop
+	struct Fixture {
+		dir: PathBuf,
+	}
+
+	impl Fixture {
+		fn new(name: &str, ignore_globs: &str, files: &[(&str, &str)]) -> Self {
+			let dir = std::env::temp_dir().join(format!("argon-ignore-{}-{}", name, std::process::id()));
+
+			fs::remove_dir_all(&dir).ok();
+
+			for (path, contents) in files {
+				let path = dir.join(path);
+
+				fs::create_dir_all(path.parent().unwrap()).unwrap();
+				fs::write(path, contents).unwrap();
+			}
+
+			fs::write(
+				dir.join("default.project.json"),
+				format!(r#"{{ "name": "Test", "ignoreGlobs": {ignore_globs}, "tree": {{ "$path": "src" }} }}"#),
+			)
+			.unwrap();
+
+			Self { dir }
+		}
+
+		/// Returns paths of all synced instances, sorted
+		fn synced(&self) -> Vec<String> {
+			fn collect(snapshot: &Snapshot, prefix: &str, paths: &mut Vec<String>) {
+				for child in &snapshot.children {
+					let path = format!("{}{}", prefix, child.name);
+
+					collect(child, &format!("{path}/"), paths);
+					paths.push(path);
+				}
+			}
+
+			let snapshot = read_project(&self.dir.join("default.project.json"), &Vfs::new(false)).unwrap();
+			let mut paths = Vec::new();
+
+			collect(&snapshot, "", &mut paths);
+			paths.sort();
+			paths
+		}
+	}
+
+	impl Drop for Fixture {
+		fn drop(&mut self) {
+			fs::remove_dir_all(&self.dir).ok();
+		}
+	}
+
+	#[test]
+	fn negated_globs() {
+		let fixture = Fixture::new(
+			"globs",
+			r#"["src/**", "!src/vendor/**"]"#,
+			&[
+				("src/main.lua", ""),
+				("src/other/a.lua", ""),
+				("src/vendor/lib.lua", ""),
+			],
+		);
+
+		assert_eq!(fixture.synced(), vec!["vendor", "vendor/lib"]);
+	}
+
+	#[test]
+	fn negated_ignore_file() {
+		let fixture = Fixture::new(
+			"file",
+			"[]",
+			&[
+				("src/.argonignore", "vendor/\n!vendor/keep.lua\n"),
+				("src/main.lua", ""),
+				("src/vendor/keep.lua", ""),
+				("src/vendor/lib.lua", ""),
+				("src/vendor/deep/lib.lua", ""),
+			],
+		);
+
+		assert_eq!(fixture.synced(), vec!["main", "vendor", "vendor/keep"]);
+	}
+}
@@ -0,0 +1,124 @@
+mod hashes {
+	use argon::{
+		core::{
+			meta::{Meta, Source},
+			snapshot::Snapshot,
+			tree::Tree,
+		},
+		vfs::Vfs,
+	};
+	use std::{
+		fs,
+		path::{Path, PathBuf},
+	};
+
+	fn tracked(path: &Path, vfs: &Vfs) -> Meta {
+		let mut meta = Meta::new().with_source(Source::file(path));
+		meta.update_hashes(vfs);
+		meta
+	}
+
+	fn tree(dir: &Path, vfs: &Vfs) -> Tree {
+		let root = Snapshot::new()
+			.with_class("Folder")
+			.with_meta(Meta::new().with_source(Source::directory(&dir.join("src"))))
+			.with_children(vec![
+				Snapshot::new()
+					.with_name("a")
+					.with_class("ModuleScript")
+					.with_meta(tracked(&dir.join("src/a.lua"), vfs)),
+				Snapshot::new()
+					.with_name("b")
+					.with_class("ModuleScript")
+					.with_meta(tracked(&dir.join("src/nested/b.lua"), vfs)),
+			]);
+
+		Tree::new(root)
+	}
+
+	fn modified(tree: &Tree, vfs: &Vfs) -> Vec<PathBuf> {
+		let mut paths = tree
+			.meta_map()
+			.values()
+			.flat_map(|meta| meta.modified_files(vfs))
+			.collect::<Vec<PathBuf>>();
+
+		paths.sort();
+		paths
+	}
+
+	#[test]
+	fn equality() {
+		let vfs = Vfs::new_virtual().with_hash_tracking();
+		let path = Path::new("a.lua");
+
+		vfs.write(path, b"return 1").unwrap();
+		let old = tracked(path, &vfs);
+
+		vfs.write(path, b"return 2").unwrap();
+		let new = tracked(path, &vfs);
+
+		assert_ne!(old.hashes, new.hashes);
+		assert_eq!(old, new);
+		assert_ne!(old, new.with_keep_unknowns(true));
+	}
+
+	#[test]
+	fn modified_files() {
+		let vfs = Vfs::new_virtual().with_hash_tracking();
+
+		vfs.create_dir(Path::new("src/nested")).unwrap();
+		vfs.write(Path::new("src/a.lua"), b"return 1").unwrap();
+		vfs.write(Path::new("src/nested/b.lua"), b"return 2").unwrap();
+
+		let tree = tree(Path::new(""), &vfs);
+
+		assert!(modified(&tree, &vfs).is_empty());
+
+		vfs.write(Path::new("src/a.lua"), b"return 3").unwrap();
+		vfs.remove(Path::new("src/nested/b.lua")).unwrap();
+
+		assert_eq!(
+			modified(&tree, &vfs),
+			vec![PathBuf::from("src/a.lua"), PathBuf::from("src/nested/b.lua")]
+		);
+	}
+
+	#[test]
+	fn update_written_paths() {
+		let dir = std::env::temp_dir().join(format!("argon-hashes-test-{}", std::process::id()));
+		let path = |path: &str| dir.join(path);
+
+		fs::create_dir_all(path("src/nested")).unwrap();
+
+		let vfs = Vfs::new(false).with_hash_tracking();
+
+		vfs.write(&path("src/a.lua"), b"return 1").unwrap();
+		vfs.write(&path("src/nested/b.lua"), b"return 2").unwrap();
+
+		let mut tree = tree(&dir, &vfs);
+
+		vfs.write(&path("src/a.lua"), b"return 3").unwrap();
+		vfs.write(&path("src/nested/b.lua"), b"return 4").unwrap();
+
+		tree.update_hashes(&[path("src/a.lua")], &vfs);
+		assert_eq!(modified(&tree, &vfs), vec![path("src/nested/b.lua")]);
+
+		// Files inside written directories (e.g. renamed ones) are updated too
+		tree.update_hashes(&[path("src/nested")], &vfs);
+		assert!(modified(&tree, &vfs).is_empty());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn disabled_by_default() {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("a.lua");
+
+		vfs.write(path, b"return 1").unwrap();
+
+		assert!(tracked(path, &vfs).hashes.is_empty());
+		assert!(tracked(path, &vfs.with_hash_tracking()).hashes.contains_key(path));
+	}
+}
@@ -0,0 +1,126 @@
+mod history {
+	use argon::{
+		core::{
+			changes::Changes,
+			history::{History, Transaction},
+		},
+		vfs::Vfs,
+	};
+	use std::{fs, path::PathBuf};
+
+	/// Temporary directory that is removed on drop
+	struct Fixture {
+		dir: PathBuf,
+	}
+
+	impl Fixture {
+		fn new(name: &str) -> Self {
+			let dir = std::env::temp_dir().join(format!("argon-history-{}-{}", name, std::process::id()));
+
+			fs::remove_dir_all(&dir).ok();
+			fs::create_dir_all(dir.join("src")).unwrap();
+			fs::write(dir.join("src/a.lua"), "return 1").unwrap();
+			fs::write(dir.join("src/old.lua"), "return 2").unwrap();
+
+			Self { dir }
+		}
+
+		fn path(&self, path: &str) -> PathBuf {
+			self.dir.join(path)
+		}
+
+		/// Applies a transaction that rewrites, renames and creates files
+		fn apply(&self, vfs: &Vfs) -> Transaction {
+			vfs.begin();
+
+			vfs.write(&self.path("src/a.lua"), b"return 3").unwrap();
+			vfs.rename(&self.path("src/old.lua"), &self.path("src/new.lua"))
+				.unwrap();
+			vfs.create_dir(&self.path("src/dir")).unwrap();
+			vfs.write(&self.path("src/dir/b.lua"), b"return 4").unwrap();
+
+			Transaction::new(Changes::new(), vfs.commit(), vfs)
+		}
+	}
+
+	impl Drop for Fixture {
+		fn drop(&mut self) {
+			fs::remove_dir_all(&self.dir).ok();
+		}
+	}
+
+	#[test]
+	fn unmodified() {
+		let fixture = Fixture::new("unmodified");
+		let vfs = Vfs::new(false);
+		let transaction = fixture.apply(&vfs);
+
+		assert!(transaction.modified_files(&vfs).is_empty());
+
+		vfs.revert(transaction.journal).unwrap();
+
+		assert_eq!(fs::read_to_string(fixture.path("src/a.lua")).unwrap(), "return 1");
+		assert!(fixture.path("src/old.lua").exists());
+		assert!(!fixture.path("src/new.lua").exists());
+		assert!(!fixture.path("src/dir").exists());
+	}
+
+	#[test]
+	fn modified() {
+		let fixture = Fixture::new("modified");
+		let vfs = Vfs::new(false);
+		let transaction = fixture.apply(&vfs);
+
+		fs::write(fixture.path("src/a.lua"), "return 5").unwrap();
+		fs::write(fixture.path("src/dir/c.lua"), "return 6").unwrap();
+		fs::write(fixture.path("src/old.lua"), "return 7").unwrap();
+		fs::remove_file(fixture.path("src/new.lua")).unwrap();
+
+		assert_eq!(
+			transaction.modified_files(&vfs),
+			vec![
+				fixture.path("src/a.lua"),
+				fixture.path("src/dir/c.lua"),
+				fixture.path("src/new.lua"),
+				fixture.path("src/old.lua"),
+			]
+		);
+	}
+
+	#[test]
+	fn restored() {
+		let fixture = Fixture::new("restored");
+		let vfs = Vfs::new(false);
+		let transaction = fixture.apply(&vfs);
+
+		fs::write(fixture.path("src/a.lua"), "return 5").unwrap();
+		fs::write(fixture.path("src/a.lua"), "return 3").unwrap();
+
+		assert!(transaction.modified_files(&vfs).is_empty());
+	}
+
+	#[test]
+	fn refuse_modified() {
+		let fixture = Fixture::new("refuse");
+		let vfs = Vfs::new(false);
+		let history = History::new(&fixture.path("default.project.json")).unwrap();
+
+		let transaction = fixture.apply(&vfs);
+		history.push(Changes::new(), transaction.journal, &vfs).unwrap();
+
+		fs::write(fixture.path("src/a.lua"), "return 5").unwrap();
+
+		let err = history.undo(1, &vfs, false).unwrap_err().to_string();
+
+		assert!(err.contains("src/a.lua"));
+		assert_eq!(fs::read_to_string(fixture.path("src/a.lua")).unwrap(), "return 5");
+		assert!(fixture.path("src/new.lua").exists());
+		assert_eq!(history.list().unwrap().len(), 1);
+
+		fs::write(fixture.path("src/a.lua"), "return 3").unwrap();
+
+		assert_eq!(history.undo(1, &vfs, false).unwrap().len(), 1);
+		assert_eq!(fs::read_to_string(fixture.path("src/a.lua")).unwrap(), "return 1");
+		assert!(history.is_empty().unwrap());
+	}
+}
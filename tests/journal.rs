@@ -0,0 +1,104 @@
+mod journal {
+	use argon::vfs::Vfs;
+	use std::path::{Path, PathBuf};
+
+	fn vfs() -> Vfs {
+		let vfs = Vfs::new_virtual();
+
+		vfs.create_dir(Path::new("src")).unwrap();
+		vfs.write(Path::new("src/a.lua"), b"return 1").unwrap();
+		vfs.write(Path::new("src/old.lua"), b"return 2").unwrap();
+		vfs.write(Path::new("src/removed.lua"), b"return 3").unwrap();
+
+		vfs
+	}
+
+	/// Rewrites, renames, removes and creates files
+	fn change(vfs: &Vfs) {
+		vfs.write(Path::new("src/a.lua"), b"return 4").unwrap();
+		vfs.rename(Path::new("src/old.lua"), Path::new("src/new.lua")).unwrap();
+		vfs.remove(Path::new("src/removed.lua")).unwrap();
+		vfs.create_dir(Path::new("src/dir")).unwrap();
+		vfs.write(Path::new("src/dir/b.lua"), b"return 5").unwrap();
+	}
+
+	fn assert_original(vfs: &Vfs) {
+		assert_eq!(vfs.read_to_string(Path::new("src/a.lua")).unwrap(), "return 1");
+		assert_eq!(vfs.read_to_string(Path::new("src/old.lua")).unwrap(), "return 2");
+		assert_eq!(vfs.read_to_string(Path::new("src/removed.lua")).unwrap(), "return 3");
+		assert!(!vfs.exists(Path::new("src/new.lua")));
+		assert!(!vfs.exists(Path::new("src/dir")));
+	}
+
+	#[test]
+	fn rollback() {
+		let vfs = vfs();
+
+		vfs.begin();
+		change(&vfs);
+
+		let mut paths = vfs.pending_paths();
+		paths.sort();
+		paths.dedup();
+
+		assert_eq!(
+			paths,
+			[
+				"src/a.lua",
+				"src/dir",
+				"src/dir/b.lua",
+				"src/new.lua",
+				"src/old.lua",
+				"src/removed.lua"
+			]
+			.map(PathBuf::from)
+		);
+
+		vfs.rollback().unwrap();
+
+		assert_original(&vfs);
+		assert!(vfs.pending_paths().is_empty());
+	}
+
+	#[test]
+	fn commit_and_revert() {
+		let vfs = vfs();
+
+		vfs.begin();
+		change(&vfs);
+
+		let journal = vfs.commit();
+
+		// Nothing is recorded after the commit
+		vfs.write(Path::new("src/c.lua"), b"return 6").unwrap();
+		vfs.rollback().unwrap();
+
+		assert_eq!(vfs.read_to_string(Path::new("src/new.lua")).unwrap(), "return 2");
+		assert!(vfs.exists(Path::new("src/c.lua")));
+
+		let mut written = journal.written_paths();
+		written.sort();
+
+		assert_eq!(
+			written,
+			["src/a.lua", "src/dir/b.lua", "src/new.lua"].map(PathBuf::from)
+		);
+
+		vfs.revert(journal).unwrap();
+
+		assert_original(&vfs);
+		assert!(vfs.exists(Path::new("src/c.lua")));
+	}
+
+	#[test]
+	fn without_transaction() {
+		let vfs = vfs();
+
+		assert!(vfs.commit().is_empty());
+
+		vfs.write(Path::new("src/a.lua"), b"return 4").unwrap();
+		vfs.rollback().unwrap();
+
+		assert_eq!(vfs.read_to_string(Path::new("src/a.lua")).unwrap(), "return 4");
+	}
+}
@@ -0,0 +1,174 @@
+mod lenient_json {
+	use argon::{
+		json,
+		middleware::json::{read_json, write_json},
+		vfs::Vfs,
+	};
+	use rbx_dom_weak::{types::Variant, ustr};
+	use serde_json::{json, Value};
+	use std::path::Path;
+
+	fn parse(input: &str) -> Value {
+		json::from_str(input).unwrap()
+	}
+
+	fn parse_err(input: &str) -> serde_json::Error {
+		json::from_str::<Value>(input).unwrap_err()
+	}
+
+	#[test]
+	fn strict() {
+		let input = r#"{ "a": [1, -2.5, true, false, null], "b": { "c": "d" } }"#;
+
+		assert_eq!(parse(input), serde_json::from_str::<Value>(input).unwrap());
+	}
+
+	#[test]
+	fn comments() {
+		let input = "
+			// Line comment
+			{
+				/* Block
+				comment */
+				\"a\": 1, // Trailing comment
+				\"b\": \"// not a comment\",
+				\"c\": \"/* neither */\"
+			}
+		";
+
+		assert_eq!(
+			parse(input),
+			json!({ "a": 1, "b": "// not a comment", "c": "/* neither */" })
+		);
+	}
+
+	#[test]
+	fn trailing_commas() {
+		assert_eq!(parse("[1, 2, 3,]"), json!([1, 2, 3]));
+		assert_eq!(parse("{ \"a\": 1, }"), json!({ "a": 1 }));
+		assert_eq!(parse("{ \"a\": [1,\n// comment\n], }"), json!({ "a": [1] }));
+		assert_eq!(parse("[\"a,\", \"]\"]"), json!(["a,", "]"]));
+	}
+
+	#[test]
+	fn unquoted_keys() {
+		assert_eq!(
+			parse("{ foo: 1, _bar: 2, $baz: 3, qux1: 4 }"),
+			json!({ "foo": 1, "_bar": 2, "$baz": 3, "qux1": 4 })
+		);
+		assert_eq!(
+			parse("{ true: false, null /* comment */ : null }"),
+			json!({ "true": false, "null": null })
+		);
+		assert_eq!(
+			parse("{ nested: { key: [true] } }"),
+			json!({ "nested": { "key": [true] } })
+		);
+	}
+
+	#[test]
+	fn strings() {
+		assert_eq!(parse("'single'"), json!("single"));
+		assert_eq!(parse(r#"'with "double" quotes'"#), json!("with \"double\" quotes"));
+		assert_eq!(parse(r#""with \'escaped\' quotes""#), json!("with 'escaped' quotes"));
+		assert_eq!(parse("\"line \\\ncontinuation\""), json!("line continuation"));
+	}
+
+	#[test]
+	fn numbers() {
+		assert_eq!(parse("0xFF"), json!(255));
+		assert_eq!(parse("+1"), json!(1));
+		assert_eq!(parse(".5"), json!(0.5));
+		assert_eq!(parse("5."), json!(5.0));
+		assert_eq!(parse("[5.e1, 1e-1]"), json!([50.0, 0.1]));
+	}
+
+	#[test]
+	fn bare_words_in_values() {
+		assert!(parse_err("{ \"enabled\": ture }")
+			.to_string()
+			.contains("expected ident at line 1"));
+		assert!(parse_err("[yes]").is_syntax());
+	}
+
+	#[test]
+	fn infinity_and_nan() {
+		let err = parse_err("{\n\tmax: Infinity\n}");
+		assert!(err
+			.to_string()
+			.contains("`Infinity` can't be represented in JSON at line 2"));
+
+		assert!(parse_err("[-Infinity]").to_string().contains("`Infinity`"));
+		assert!(parse_err("[NaN]").to_string().contains("`NaN`"));
+
+		assert_eq!(parse("{ Infinity: 1, NaN: 2 }"), json!({ "Infinity": 1, "NaN": 2 }));
+		assert_eq!(parse("'Infinity'"), json!("Infinity"));
+	}
+
+	#[test]
+	fn error_lines() {
+		let err = parse_err("{\n\t// comment\n\t/* multi\n\tline */\n\ta: ?\n}");
+		assert_eq!(err.line(), 5);
+	}
+
+	#[test]
+	fn detects_comments() {
+		assert!(json::has_comments("{ \"a\": 1 } // comment"));
+		assert!(json::has_comments("[1, /* two */ 2]"));
+
+		assert!(!json::has_comments("{ \"url\": \"https://argon.wiki\" }"));
+		assert!(!json::has_comments("{ 'a': '/* \\' // */' }"));
+		assert!(!json::has_comments("[1 / 2]"));
+	}
+
+	#[test]
+	fn keeps_unchanged_files() {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("data.json");
+		let contents = "{\n\t// Comment\n\ta: 1,\n}";
+
+		vfs.write(path, contents.as_bytes()).unwrap();
+
+		json::write(path, b"{\"a\": 1}", &vfs).unwrap();
+		assert_eq!(vfs.read_to_string(path).unwrap(), contents);
+
+		let err = json::write(path, b"{\"a\": 2}", &vfs).unwrap_err();
+		assert!(err.to_string().contains("contains comments"));
+		assert_eq!(vfs.read_to_string(path).unwrap(), contents);
+
+		vfs.write(path, b"{ a: 1, }").unwrap();
+		json::write(path, b"{\"a\": 2}", &vfs).unwrap();
+		assert_eq!(vfs.read_to_string(path).unwrap(), "{\"a\": 2}");
+	}
+
+	#[test]
+	fn empty_tables() {
+		let write = |original: &str| {
+			let vfs = Vfs::new_virtual();
+			let path = Path::new("module.json");
+
+			vfs.write(path, original.as_bytes()).unwrap();
+
+			let mut properties = read_json(path, &vfs).unwrap().properties;
+			properties.insert(
+				ustr("Source"),
+				Variant::String(String::from(
+					"return { list = {}, map = {}, nested = { {} }, changed = true }",
+				)),
+			);
+
+			write_json(properties, path, &vfs).unwrap();
+
+			serde_json::from_str::<Value>(&vfs.read_to_string(path).unwrap()).unwrap()
+		};
+
+		assert_eq!(
+			write("{ \"list\": [], \"map\": {}, \"nested\": [[]] }"),
+			json!({ "list": [], "map": {}, "nested": [[]], "changed": true })
+		);
+		assert_eq!(
+			write("{}"),
+			json!({ "list": {}, "map": {}, "nested": [{}], "changed": true })
+		);
+	}
+}
@@ -0,0 +1,184 @@
+mod lua_syncback {
+	use argon::{
+		middleware::{
+			json::write_json,
+			msgpack::write_msgpack,
+			toml::write_toml,
+			yaml::{read_yaml, write_yaml},
+		},
+		vfs::Vfs,
+		Properties,
+	};
+	use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
+	use serde_json::{json, Value};
+	use std::path::Path;
+
+	fn properties(source: &str) -> Properties {
+		let mut properties = UstrMap::new();
+		properties.insert(ustr("Source"), Variant::String(source.into()));
+		properties
+	}
+
+	fn to_json(source: &str) -> Value {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("module.json");
+
+		write_json(properties(source), path, &vfs).unwrap();
+
+		serde_json::from_slice(&vfs.read(path).unwrap()).unwrap()
+	}
+
+	fn to_json_err(source: &str) -> String {
+		let vfs = Vfs::new_virtual();
+
+		write_json(properties(source), Path::new("module.json"), &vfs)
+			.unwrap_err()
+			.to_string()
+	}
+
+	#[test]
+	fn literals() {
+		assert_eq!(to_json("return true"), json!(true));
+		assert_eq!(to_json("return nil"), json!(null));
+		assert_eq!(to_json("return 'single'"), json!("single"));
+		assert_eq!(to_json("return `backtick`"), json!("backtick"));
+		assert_eq!(to_json("return 1337"), json!(1337));
+		assert_eq!(to_json("return -13.37"), json!(-13.37));
+		assert_eq!(to_json("return 0xFF"), json!(255));
+		assert_eq!(to_json("return 0b101"), json!(5));
+		assert_eq!(to_json("return 1_000_000"), json!(1000000));
+		assert_eq!(to_json("return 1e3"), json!(1000.0));
+		assert_eq!(to_json("return 1.5e-1"), json!(0.15));
+	}
+
+	#[test]
+	fn strings() {
+		assert_eq!(to_json(r#"return "a\nb\t\"c\"""#), json!("a\nb\t\"c\""));
+		assert_eq!(to_json(r#"return "\x41\65\u{42}""#), json!("AAB"));
+		assert_eq!(to_json("return [[\nlong\nstring]]"), json!("long\nstring"));
+		assert_eq!(to_json("return [==[with ]] inside]==]"), json!("with ]] inside"));
+	}
+
+	#[test]
+	fn comments() {
+		let source = "
+			-- Line comment
+			--[[ Block
+			comment ]]
+			return { --[=[ inline ]=] foo = 1 } -- trailing
+		";
+
+		assert_eq!(to_json(source), json!({ "foo": 1 }));
+	}
+
+	#[test]
+	fn tables() {
+		assert_eq!(to_json("return {}"), json!({}));
+		assert_eq!(to_json("return { 1, 2, 3; }"), json!([1, 2, 3]));
+		assert_eq!(to_json("return { [1] = 'a', [2] = 'b' }"), json!(["a", "b"]));
+		assert_eq!(
+			to_json("return { foo = 'bar', ['with space'] = true, [10] = 1 }"),
+			json!({ "foo": "bar", "with space": true, "10": 1 })
+		);
+		assert_eq!(
+			to_json("return { nested = { deep = { 1, { x = 2 } } } }"),
+			json!({ "nested": { "deep": [1, { "x": 2 }] } })
+		);
+	}
+
+	#[test]
+	fn mixed_tables() {
+		assert_eq!(to_json("return { a = 1, 'x' }"), json!({ "a": 1, "1": "x" }));
+		assert_eq!(
+			to_json("return { 'x', a = 1, 'y', b = 2, 'z' }"),
+			json!({ "1": "x", "a": 1, "2": "y", "b": 2, "3": "z" })
+		);
+		assert_eq!(to_json("return { nil, 'x', a = 1 }"), json!({ "2": "x", "a": 1 }));
+	}
+
+	#[test]
+	fn errors() {
+		assert!(to_json_err("return { foo = bar }").contains("only literal values are supported"));
+		assert!(to_json_err("return { 1, 2").contains("expected `,` or `}`"));
+		assert!(to_json_err("return 'unfinished").contains("unfinished string"));
+		assert!(to_json_err("return `{interpolated}`").contains("string interpolation is not supported"));
+		assert!(to_json_err("return {}\nreturn {}").contains("expected end of file at line 2"));
+		assert!(to_json_err("return math.floor").contains("unsupported `math` member"));
+	}
+
+	#[test]
+	fn empty_source() {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("module.json");
+
+		let properties = write_json(properties("  \n"), path, &vfs).unwrap();
+
+		assert!(properties.is_empty());
+		assert!(vfs.read(path).unwrap().is_empty());
+	}
+
+	#[test]
+	fn other_formats() {
+		let vfs = Vfs::new_virtual();
+		let source = "return { name = 'argon', list = { 1, 2 }, mixed = { a = 1, 'x' } }";
+
+		write_toml(properties(source), Path::new("module.toml"), &vfs).unwrap();
+		write_yaml(properties(source), Path::new("module.yaml"), &vfs).unwrap();
+		write_msgpack(properties(source), Path::new("module.msgpack"), &vfs).unwrap();
+
+		let toml = vfs.read_to_string(Path::new("module.toml")).unwrap();
+		let toml: toml::Value = toml::from_str(&toml).unwrap();
+
+		assert_eq!(toml["name"].as_str(), Some("argon"));
+		assert_eq!(toml["mixed"]["1"].as_str(), Some("x"));
+
+		assert_eq!(
+			vfs.read_to_string(Path::new("module.yaml")).unwrap(),
+			"name: argon\nlist:\n  - 1\n  - 2\nmixed:\n  a: 1\n  \"1\": x\n"
+		);
+
+		let msgpack = vfs.read(Path::new("module.msgpack")).unwrap();
+		let msgpack = rmpv::decode::read_value(&mut msgpack.as_slice()).unwrap();
+
+		let mixed = msgpack
+			.as_map()
+			.unwrap()
+			.iter()
+			.find(|(key, _)| key.as_str() == Some("mixed"));
+		let mixed = mixed.unwrap().1.as_map().unwrap();
+
+		assert!(mixed.contains(&(rmpv::Value::from(1), rmpv::Value::from("x"))));
+	}
+
+	#[test]
+	fn toml_requires_table() {
+		let vfs = Vfs::new_virtual();
+
+		assert!(write_toml(properties("return { 1, 2 }"), Path::new("module.toml"), &vfs).is_err());
+	}
+
+	#[test]
+	fn yaml_round_trip() {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("module.yaml");
+		let source = "return {
+			plain = 'some text', reserved = { 'true', 'No', 'null', '~' }, number = '1.5',
+			special = { '', 'a: b', '#hash', ' padded ', 'line\\nbreak', 'quote \\'' },
+			nested = { { a = 1, b = { 2, 3 } }, { { 4 } }, {} }, empty = {}, float = 0.5, yes = false,
+		}";
+
+		write_yaml(properties(source), path, &vfs).unwrap();
+
+		let yaml = vfs.read_to_string(path).unwrap();
+
+		assert!(yaml.starts_with("plain: some text\nreserved:\n  - \"true\"\n  - \"No\"\n"));
+		assert!(yaml.contains("nested:\n  - a: 1\n    b:\n      - 2\n      - 3\n  - - - 4\n  - {}\nempty: {}\n"));
+
+		let properties = read_yaml(path, &vfs).unwrap().properties;
+
+		match properties.get(&ustr("Source")) {
+			Some(Variant::String(read)) => assert_eq!(to_json(read), to_json(source)),
+			_ => panic!("missing Source property"),
+		}
+	}
+}
@@ -0,0 +1,94 @@
+mod luau_data {
+	use argon::{
+		core::snapshot::Snapshot,
+		middleware::luau_data::{read_luau_data, write_luau_data},
+		vfs::Vfs,
+	};
+	use rbx_dom_weak::{types::Variant, ustr};
+	use std::path::Path;
+
+	const SOURCE: &str = "--!argon class=Configuration
+-- Game settings
+return {
+	Zeta = 1,
+	Alpha = \"text\",
+}
+";
+
+	fn read(source: &str, vfs: &Vfs) -> Snapshot {
+		vfs.write(Path::new("data.luau"), source.as_bytes()).unwrap();
+		read_luau_data(Path::new("data.luau"), vfs).unwrap()
+	}
+
+	fn set_attribute(snapshot: &mut Snapshot, name: &str, value: Variant) {
+		match snapshot.properties.get_mut(&ustr("Attributes")) {
+			Some(Variant::Attributes(attributes)) => {
+				attributes.insert(name.into(), value);
+			}
+			_ => panic!("missing Attributes property"),
+		}
+	}
+
+	#[test]
+	fn keep_order_and_header() {
+		let vfs = Vfs::new_virtual();
+		let mut snapshot = read(SOURCE, &vfs);
+
+		set_attribute(&mut snapshot, "Beta", Variant::Float64(2.0));
+		set_attribute(&mut snapshot, "Zeta", Variant::Float64(3.0));
+
+		write_luau_data(snapshot, Path::new("data.luau"), &vfs).unwrap();
+
+		let source = vfs.read_to_string(Path::new("data.luau")).unwrap();
+		let position = |key: &str| source.find(key).unwrap();
+
+		assert!(source.starts_with("--!argon class=Configuration\n-- Game settings\nreturn {"));
+		assert!(position("Zeta") < position("Alpha") && position("Alpha") < position("Beta"));
+
+		let snapshot = read(&source, &vfs);
+
+		match snapshot.properties.get(&ustr("Attributes")) {
+			Some(Variant::Attributes(attributes)) => {
+				assert_eq!(attributes.get("Zeta"), Some(&Variant::Float64(3.0)));
+				assert_eq!(attributes.get("Beta"), Some(&Variant::Float64(2.0)));
+			}
+			_ => panic!("missing Attributes property"),
+		}
+	}
+
+	#[test]
+	fn keep_unchanged_files() {
+		let vfs = Vfs::new_virtual();
+		let source = SOURCE.replace("Zeta = 1,", "Zeta = 1, -- kept");
+		let snapshot = read(&source, &vfs);
+
+		write_luau_data(snapshot, Path::new("data.luau"), &vfs).unwrap();
+
+		assert_eq!(vfs.read_to_string(Path::new("data.luau")).unwrap(), source);
+	}
+
+	#[test]
+	fn refuse_to_drop_comments() {
+		let vfs = Vfs::new_virtual();
+		let source = SOURCE.replace("Zeta = 1,", "Zeta = 1, -- lost");
+		let mut snapshot = read(&source, &vfs);
+
+		set_attribute(&mut snapshot, "Zeta", Variant::Float64(3.0));
+
+		assert!(write_luau_data(snapshot, Path::new("data.luau"), &vfs).is_err());
+		assert_eq!(vfs.read_to_string(Path::new("data.luau")).unwrap(), source);
+	}
+
+	#[test]
+	fn new_file() {
+		let vfs = Vfs::new_virtual();
+		let snapshot = Snapshot::new().with_class("Configuration");
+
+		write_luau_data(snapshot, Path::new("data.luau"), &vfs).unwrap();
+
+		assert_eq!(
+			vfs.read_to_string(Path::new("data.luau")).unwrap(),
+			"--!argon class=Configuration\nreturn {}\n"
+		);
+	}
+}
@@ -0,0 +1,174 @@
+mod markdown {
+	use argon::{
+		middleware::md::{read_md, write_md},
+		vfs::Vfs,
+	};
+	use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
+	use std::path::Path;
+
+	const MONO: &str = "<font family=\"rbxasset://fonts/families/RobotoMono.json\">";
+
+	fn to_rich_text(markdown: &str) -> String {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("text.md");
+
+		vfs.write(path, markdown.as_bytes()).unwrap();
+
+		match read_md(path, &vfs).unwrap().properties.remove(&ustr("Value")) {
+			Some(Variant::String(rich_text)) => rich_text,
+			_ => panic!("missing Value property"),
+		}
+	}
+
+	fn to_markdown(rich_text: &str) -> String {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("text.md");
+
+		let mut properties = UstrMap::new();
+		properties.insert(ustr("Value"), Variant::String(rich_text.into()));
+
+		write_md(properties, path, &vfs).unwrap();
+
+		vfs.read_to_string(path).unwrap()
+	}
+
+	/// Asserts that Markdown written back from the RichText describes the same RichText
+	fn round_trip(markdown: &str) -> String {
+		let rich_text = to_rich_text(markdown);
+		let written = to_markdown(&rich_text);
+
+		assert_eq!(
+			to_rich_text(&written),
+			rich_text,
+			"\nsource:\n{markdown}\nwritten:\n{written}"
+		);
+
+		written
+	}
+
+	#[test]
+	fn inline() {
+		assert_eq!(
+			to_rich_text("*a* **b** ~~c~~ `d`"),
+			format!("<i>a</i> <b>b</b> <s>c</s> {MONO}d</font>")
+		);
+		assert_eq!(to_rich_text("a < b & \"c\""), "a &lt; b &amp; &quot;c&quot;");
+
+		round_trip("*a* **b** ~~c~~ `d` and ``with ` tick``");
+		round_trip("Special \\* \\_ \\` \\[ \\] \\< \\~ characters, snake_case and 1 ~ 2");
+		round_trip("Hard  \nbreak and\\\nanother");
+		round_trip("*a*_b_ and **c**<b>d</b>");
+	}
+
+	#[test]
+	fn blocks() {
+		assert_eq!(
+			to_rich_text("# Title\n\nText\n\n---"),
+			"<font size=\"30\"><b>Title</b></font>\n\nText\n\n<font transparency=\"0.5\">────────────────</font>"
+		);
+
+		round_trip("# Title\n\n###### Small\n\nText\nwith soft break\n\n---\n\n\\# not a heading");
+		round_trip("> Quote\n>\n> > Nested\n\n> - In list");
+	}
+
+	#[test]
+	fn lists() {
+		assert_eq!(
+			to_rich_text("- a\n- b\n\n3. c\n4. d"),
+			"<b>•</b> a\n<b>•</b> b\n\n3. c\n4. d"
+		);
+
+		assert_eq!(round_trip("- a\n- b\n- c\n"), "- a\n- b\n- c\n");
+		assert_eq!(round_trip("1. a\n2. b\n"), "1. a\n2. b\n");
+
+		round_trip("* star\n+ plus\n\n7) paren");
+		round_trip("- **bold** item\n- `code` item");
+	}
+
+	#[test]
+	fn nested_lists() {
+		assert_eq!(
+			to_rich_text("- a\n  - b\n    - c\n- d"),
+			"<b>•</b> a\n    <b>•</b> b\n        <b>•</b> c\n<b>•</b> d"
+		);
+
+		assert_eq!(round_trip("- a\n  - b\n    - c\n- d\n"), "- a\n  - b\n    - c\n- d\n");
+
+		round_trip("1. a\n   1. b\n   2. c\n2. d");
+		round_trip("1. a\n   - b\n\n     para in nested\n10. c\n    3. d");
+	}
+
+	#[test]
+	fn multi_block_items() {
+		assert_eq!(to_rich_text("1. x\n\n   para in item\n"), "1. x\n    para in item");
+
+		round_trip("1. x\n\n   para in item\n");
+		round_trip("- a\n\n  > quote\n  > in item\n\n- b");
+		round_trip("- a\n\n  ```lua\n  print(1)\n\n  print(2)\n  ```\n\n- b");
+	}
+
+	#[test]
+	fn code_blocks() {
+		assert_eq!(
+			to_rich_text("```lua\nprint(\"<hi>\")\n```"),
+			format!("<!--code:lua-->{MONO}print(&quot;&lt;hi&gt;&quot;)</font>")
+		);
+		assert_eq!(
+			to_rich_text("    indented\n    code"),
+			format!("{MONO}indented\ncode</font>")
+		);
+
+		assert_eq!(
+			round_trip("```lua\nlocal a = 1\n\nreturn a\n```\n"),
+			"```lua\nlocal a = 1\n\nreturn a\n```\n"
+		);
+
+		round_trip("```\nno language\n```");
+		round_trip("````md\n```\nfence inside\n```\n````");
+	}
+
+	#[test]
+	fn links() {
+		assert_eq!(
+			to_rich_text("[Argon](https://argon.wiki)"),
+			"<!--link(https://argon.wiki)--><u>Argon</u><!--/link-->"
+		);
+
+		assert_eq!(
+			round_trip("[Argon](https://argon.wiki \"Docs\")\n"),
+			"[Argon](https://argon.wiki \"Docs\")\n"
+		);
+
+		round_trip("[**bold** link](<with space>) and <https://argon.wiki>");
+		round_trip("![Alt text](image.png) and [empty]()");
+	}
+
+	#[test]
+	fn inline_html() {
+		assert_eq!(to_rich_text("<b>bold</b> text"), "<b>bold</b> text");
+		assert_eq!(
+			to_rich_text("<span style=\"color: red\">red</span>"),
+			"<font color=\"red\">red</font>"
+		);
+		assert_eq!(to_rich_text("<div>unknown</div>"), "&lt;div&gt;unknown&lt;/div&gt;");
+
+		round_trip("a<br>b and <u>underline</u> and <font color=\"#FF0000\">red</font>");
+		round_trip("<span style=\"color: red\">red</span> and <kbd>key</kbd>");
+		round_trip("<div>\nblock\n</div>");
+		round_trip("<!-- comment -->\n\ntext");
+	}
+
+	#[test]
+	fn keeps_formatting() {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("text.md");
+		let markdown = "Some *text*\n\n* list\n";
+
+		vfs.write(path, markdown.as_bytes()).unwrap();
+
+		let properties = read_md(path, &vfs).unwrap().properties;
+		write_md(properties, path, &vfs).unwrap();
+
+		assert_eq!(vfs.read_to_string(path).unwrap(), markdown);
+	}
+}
@@ -0,0 +1,187 @@
+mod preview {
+	use argon::vfs::{
+		preview::{Plan, PlannedChange},
+		Vfs,
+	};
+	use std::{
+		fs,
+		path::{Path, PathBuf},
+	};
+
+	/// Temporary directory with the given files that is removed on drop
+	struct Fixture {
+		dir: PathBuf,
+	}
+
+	impl Fixture {
+		fn new(name: &str, files: &[(&str, &str)]) -> Self {
+			let dir = std::env::temp_dir().join(format!("argon-preview-{}-{}", name, std::process::id()));
+
+			fs::remove_dir_all(&dir).ok();
+			fs::create_dir_all(&dir).unwrap();
+
+			for (path, contents) in files {
+				let path = dir.join(path);
+
+				fs::create_dir_all(path.parent().unwrap()).unwrap();
+				fs::write(path, contents).unwrap();
+			}
+
+			Self { dir }
+		}
+
+		fn path(&self, path: &str) -> PathBuf {
+			self.dir.join(path)
+		}
+
+		fn plan(&self, vfs: &Vfs) -> Plan {
+			vfs.plan().unwrap().relative_to(&self.dir)
+		}
+	}
+
+	impl Drop for Fixture {
+		fn drop(&mut self) {
+			fs::remove_dir_all(&self.dir).ok();
+		}
+	}
+
+	fn summary(plan: &Plan) -> Vec<String> {
+		plan.changes
+			.iter()
+			.map(|change| match change {
+				PlannedChange::Create { path, is_dir: true, .. } => format!("+ {}/", path.display()),
+				PlannedChange::Create { path, .. } => format!("+ {}", path.display()),
+				PlannedChange::Rewrite { path, .. } => format!("~ {}", path.display()),
+				PlannedChange::Rename { from, to } => format!("> {} {}", from.display(), to.display()),
+				PlannedChange::Remove { path } => format!("- {}", path.display()),
+			})
+			.collect()
+	}
+
+	fn diff<'a>(plan: &'a Plan, target: &str) -> Option<&'a str> {
+		plan.changes.iter().find_map(|change| match change {
+			PlannedChange::Create { path, diff, .. } | PlannedChange::Rewrite { path, diff }
+				if path == Path::new(target) =>
+			{
+				diff.as_deref()
+			}
+			_ => None,
+		})
+	}
+
+	#[test]
+	fn rename() {
+		let fixture = Fixture::new(
+			"rename",
+			&[("src/a.lua", "return 1\n"), ("src/dir/b.lua", "return 2\n")],
+		);
+		let vfs = Vfs::new_preview();
+
+		vfs.rename(&fixture.path("src/a.lua"), &fixture.path("src/c.lua"))
+			.unwrap();
+		vfs.rename(&fixture.path("src/dir"), &fixture.path("src/other"))
+			.unwrap();
+
+		assert_eq!(
+			summary(&fixture.plan(&vfs)),
+			vec!["> src/a.lua src/c.lua", "> src/dir src/other"]
+		);
+
+		assert!(vfs.exists(&fixture.path("src/other/b.lua")));
+		assert!(!vfs.exists(&fixture.path("src/dir/b.lua")));
+		assert!(fixture.path("src/a.lua").exists());
+	}
+
+	#[test]
+	fn rename_and_rewrite() {
+		let fixture = Fixture::new("rename-rewrite", &[("a.lua", "local a = 1\nreturn a\n")]);
+		let vfs = Vfs::new_preview();
+
+		vfs.rename(&fixture.path("a.lua"), &fixture.path("b.lua")).unwrap();
+		vfs.write(&fixture.path("b.lua"), b"local a = 2\nreturn a\n").unwrap();
+
+		let plan = fixture.plan(&vfs);
+
+		assert_eq!(summary(&plan), vec!["> a.lua b.lua", "~ b.lua"]);
+		assert_eq!(
+			diff(&plan, "b.lua"),
+			Some("@@ -1,2 +1,2 @@\n-local a = 1\n+local a = 2\n return a\n")
+		);
+	}
+
+	#[test]
+	fn remove_then_recreate() {
+		let fixture = Fixture::new(
+			"recreate",
+			&[
+				("src/keep.lua", "return 1\n"),
+				("src/edit.lua", "return 2\n"),
+				("src/gone.lua", "return 3\n"),
+			],
+		);
+		let vfs = Vfs::new_preview();
+
+		vfs.remove(&fixture.path("src")).unwrap();
+		vfs.create_dir(&fixture.path("src")).unwrap();
+		vfs.write(&fixture.path("src/keep.lua"), b"return 1\n").unwrap();
+		vfs.write(&fixture.path("src/edit.lua"), b"return 4\n").unwrap();
+
+		let plan = fixture.plan(&vfs);
+
+		assert_eq!(summary(&plan), vec!["- src/gone.lua", "~ src/edit.lua"]);
+		assert_eq!(diff(&plan, "src/edit.lua"), Some("@@ -1 +1 @@\n-return 2\n+return 4\n"));
+
+		assert_eq!(
+			vfs.read_dir(&fixture.path("src")).unwrap(),
+			vec![fixture.path("src/edit.lua"), fixture.path("src/keep.lua")]
+		);
+	}
+
+	#[test]
+	fn remove_then_recreate_file() {
+		let fixture = Fixture::new("recreate-file", &[("a.lua", "return 1\n")]);
+		let vfs = Vfs::new_preview();
+
+		vfs.remove(&fixture.path("a.lua")).unwrap();
+		vfs.write(&fixture.path("a.lua"), b"return 1\n").unwrap();
+
+		assert!(fixture.plan(&vfs).is_empty());
+	}
+
+	#[test]
+	fn nested_create() {
+		let fixture = Fixture::new("nested", &[("src/a.lua", "")]);
+		let vfs = Vfs::new_preview();
+
+		vfs.create_dir(&fixture.path("src/one/two")).unwrap();
+		vfs.write(&fixture.path("src/one/two/three.lua"), b"print(1)\nprint(2)\n")
+			.unwrap();
+
+		let plan = fixture.plan(&vfs);
+
+		assert_eq!(
+			summary(&plan),
+			vec!["+ src/one/", "+ src/one/two/", "+ src/one/two/three.lua"]
+		);
+		assert_eq!(
+			diff(&plan, "src/one/two/three.lua"),
+			Some("@@ -0,0 +1,2 @@\n+print(1)\n+print(2)\n")
+		);
+
+		assert!(vfs.is_dir(&fixture.path("src/one")));
+		assert!(!fixture.path("src/one").exists());
+	}
+
+	#[test]
+	fn binary_files() {
+		let fixture = Fixture::new("binary", &[]);
+		let vfs = Vfs::new_preview();
+
+		vfs.write(&fixture.path("model.rbxm"), &[0xff, 0xfe, 0x00]).unwrap();
+
+		let plan = fixture.plan(&vfs);
+
+		assert_eq!(summary(&plan), vec!["+ model.rbxm"]);
+		assert_eq!(diff(&plan, "model.rbxm"), None);
+	}
+}
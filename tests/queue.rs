@@ -0,0 +1,169 @@
+mod queue {
+	use argon::{
+		constants::QUEUE_CAPACITY,
+		core::{changes::Changes, queue::Queue},
+		server::{Disconnect, ExecutionResult, Message, SyncChanges},
+	};
+	use rbx_dom_weak::types::Ref;
+	use std::time::Duration;
+
+	fn removals(count: usize) -> SyncChanges {
+		let mut changes = Changes::new();
+
+		for _ in 0..count {
+			changes.remove(Ref::new());
+		}
+
+		SyncChanges(changes)
+	}
+
+	fn disconnect() -> Disconnect {
+		Disconnect {
+			message: String::from("Bye"),
+		}
+	}
+
+	/// Returns all queued messages without waiting for more
+	fn drain(queue: &Queue, id: u32) -> Vec<Message> {
+		let mut messages = Vec::new();
+
+		while !queue.is_empty(id) {
+			messages.push(queue.get(id).unwrap().unwrap());
+		}
+
+		messages
+	}
+
+	#[test]
+	fn coalesce_changes() {
+		let queue = Queue::new();
+		queue.subscribe(1, "Studio").unwrap();
+
+		queue.push(removals(1), None).unwrap();
+		queue.push(removals(2), None).unwrap();
+		queue.push(disconnect(), Some(1)).unwrap();
+		queue.push(removals(1), Some(1)).unwrap();
+
+		match drain(&queue, 1).as_slice() {
+			[Message::SyncChanges(first), Message::Disconnect(_), Message::SyncChanges(last)] => {
+				assert_eq!(first.0.removals.len(), 3);
+				assert_eq!(last.0.removals.len(), 1);
+			}
+			messages => panic!("unexpected messages: {messages:?}"),
+		}
+	}
+
+	#[test]
+	fn overflow() {
+		let queue = Queue::new();
+		queue.subscribe(1, "Studio").unwrap();
+
+		queue.push(removals(QUEUE_CAPACITY / 2), Some(1)).unwrap();
+		queue.push(removals(QUEUE_CAPACITY / 2), Some(1)).unwrap();
+
+		match drain(&queue, 1).as_slice() {
+			[Message::SyncChanges(changes)] => assert_eq!(changes.0.total(), QUEUE_CAPACITY),
+			messages => panic!("unexpected messages: {messages:?}"),
+		}
+
+		queue.push(removals(QUEUE_CAPACITY), Some(1)).unwrap();
+		queue.push(removals(1), Some(1)).unwrap();
+
+		// Pending changes are replaced with a single resync,
+		// changes pushed after it are dropped but other messages are kept
+		queue.push(removals(1), Some(1)).unwrap();
+		queue.push(disconnect(), Some(1)).unwrap();
+		queue.push(removals(1), Some(1)).unwrap();
+
+		match drain(&queue, 1).as_slice() {
+			[Message::Resync(_), Message::Disconnect(_)] => {}
+			messages => panic!("unexpected messages: {messages:?}"),
+		}
+	}
+
+	#[test]
+	fn resync_first_client() {
+		let queue = Queue::new();
+		queue.subscribe_internal().unwrap();
+
+		// Internal listeners don't count as synced clients
+		queue.push(removals(1), None).unwrap();
+		queue.push(removals(1), None).unwrap();
+
+		queue.subscribe(1, "Studio").unwrap();
+		queue.subscribe(2, "Studio").unwrap();
+
+		match drain(&queue, 1).as_slice() {
+			[Message::Resync(resync)] => assert!(resync.message.starts_with("2 changes")),
+			messages => panic!("unexpected messages: {messages:?}"),
+		}
+
+		assert!(queue.is_empty(2));
+
+		queue.unsubscribe(1).unwrap();
+		queue.unsubscribe(2).unwrap();
+		queue.subscribe(3, "Studio").unwrap();
+
+		assert!(queue.is_empty(3));
+	}
+
+	#[test]
+	fn unsubscribe() {
+		let queue = Queue::new();
+		queue.subscribe(1, "Studio").unwrap();
+
+		assert!(queue.subscribe(1, "Studio").is_err());
+
+		queue.unsubscribe(1).unwrap();
+
+		assert!(queue.push(removals(1), Some(1)).is_err());
+		assert!(queue.get_timeout(1).is_err());
+		assert!(queue.unsubscribe(1).is_err());
+	}
+
+	#[test]
+	fn execution_result() {
+		let queue = Queue::new();
+		let result = ExecutionResult {
+			values: vec![String::from("1")],
+			error: None,
+			traceback: None,
+			logs: Vec::new(),
+		};
+
+		let receiver = queue.expect_result("exec", 1);
+
+		assert!(queue.push_result("exec", 2, result.clone()).is_err());
+		assert!(queue.push_result("other", 1, result.clone()).is_err());
+
+		queue.push_result("exec", 1, result.clone()).unwrap();
+
+		assert_eq!(receiver.try_recv().unwrap().values, vec![String::from("1")]);
+		assert!(queue.push_result("exec", 1, result.clone()).is_err());
+
+		let _receiver = queue.expect_result("forgotten", 1);
+		queue.forget_result("forgotten");
+
+		assert!(queue.push_result("forgotten", 1, result).is_err());
+	}
+
+	#[test]
+	fn change_timeout() {
+		let queue = Queue::new();
+		queue.subscribe(1, "Studio").unwrap();
+
+		assert!(queue
+			.get_change_timeout(1, Duration::from_millis(10))
+			.unwrap()
+			.is_none());
+
+		queue.push(disconnect(), Some(1)).unwrap();
+		queue.push(removals(1), Some(1)).unwrap();
+
+		assert!(matches!(
+			queue.get_change_timeout(1, Duration::from_millis(10)).unwrap(),
+			Some(Message::SyncChanges(_))
+		));
+		assert!(queue.get_change_timeout(2, Duration::from_millis(10)).is_err());
+	}
+}
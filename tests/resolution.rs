@@ -280,8 +280,15 @@ mod unresolved_value {
 
 	#[test]
 	fn referent() {
-		// TODO: Implement Ref
-		// assert_eq!(resolve("Model", "PrimaryPart", ""), Ref::none().into());
+		// Instance paths are resolved once the whole tree is built
+		assert_eq!(
+			resolve("Model", "PrimaryPart", r#""./Handle""#),
+			Variant::String("./Handle".into())
+		);
+		assert_eq!(
+			resolve("ObjectValue", "Value", r#""Workspace/Baseplate""#),
+			Variant::String("Workspace/Baseplate".into())
+		);
 	}
 
 	#[test]
@@ -386,8 +393,8 @@ mod resolved_value {
 	use rbx_dom_weak::types::{
 		Attributes, Axes, BinaryString, BrickColor, CFrame, Color3, Color3uint8, ColorSequence, ColorSequenceKeypoint,
 		Content, ContentId, CustomPhysicalProperties, Enum, Faces, Font, FontStyle, FontWeight, Matrix3, NumberRange,
-		NumberSequence, NumberSequenceKeypoint, PhysicalProperties, Ray, Rect, Region3, Region3int16, SharedString,
-		Tags, UDim, UDim2, Variant, Vector2, Vector2int16, Vector3, Vector3int16,
+		NumberSequence, NumberSequenceKeypoint, PhysicalProperties, Ray, Rect, Ref, Region3, Region3int16,
+		SharedString, Tags, UDim, UDim2, Variant, Vector2, Vector2int16, Vector3, Vector3int16,
 	};
 	use serde_json::{json, Value};
 
@@ -669,8 +676,8 @@ mod resolved_value {
 
 	#[test]
 	fn referent() {
-		// TODO: Implement Ref
-		// assert_eq(from_variant(Ref::none()), json!(null));
+		// Refs have to be converted to instance paths before serialization
+		assert_eq(from_variant(Ref::none()), json!(""));
 	}
 
 	#[test]
@@ -0,0 +1,87 @@
+mod syncback {
+	use argon::{
+		core::{
+			helpers::syncback::get_middleware,
+			meta::{Meta, Source},
+		},
+		middleware::Middleware,
+		project::Project,
+		vfs::Vfs,
+		Properties,
+	};
+	use rbx_dom_weak::{types::Variant, ustr, HashMapExt, UstrMap};
+	use std::path::Path;
+
+	fn meta(project: &str) -> Meta {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("/place/default.project.json");
+
+		vfs.write(path, project.as_bytes()).unwrap();
+
+		Meta::from_project(&Project::load_vfs(path, &vfs).unwrap())
+	}
+
+	fn source(source: &str) -> Properties {
+		let mut properties = UstrMap::new();
+		properties.insert(ustr("Source"), Variant::String(source.into()));
+		properties
+	}
+
+	fn middleware(class: &str, path: &str, mut properties: Properties) -> Option<Middleware> {
+		let meta = meta(r#"{ "name": "Test", "tree": { "$path": "src" } }"#).with_source(Source::file(Path::new(path)));
+
+		get_middleware(class, &mut properties, &meta)
+	}
+
+	#[test]
+	fn keep_file_format() {
+		assert_eq!(
+			middleware("ModuleScript", "/place/src/config.toml", source("return { a = 1 }")),
+			Some(Middleware::TomlModule)
+		);
+		assert_eq!(
+			middleware("ModuleScript", "/place/src/config.json", source("")),
+			Some(Middleware::JsonModule)
+		);
+		assert_eq!(
+			middleware("StringValue", "/place/src/text.md", UstrMap::new()),
+			Some(Middleware::RichStringValue)
+		);
+	}
+
+	#[test]
+	fn fall_back_to_class() {
+		// Data modules can't store code and other classes can't reuse the file
+		assert_eq!(
+			middleware("ModuleScript", "/place/src/config.toml", source("print('Hello')")),
+			Some(Middleware::ModuleScript)
+		);
+		assert_eq!(
+			middleware("StringValue", "/place/src/config.toml", UstrMap::new()),
+			Some(Middleware::StringValue)
+		);
+		assert_eq!(middleware("Part", "/place/src/config.toml", UstrMap::new()), None);
+	}
+
+	#[test]
+	fn model_classes() {
+		let meta = meta(
+			r#"{ "name": "Test", "syncback": { "modelClasses": { "Part": "RbxmModel", "*": "JsonModel" } }, "tree": { "$path": "src" } }"#,
+		);
+
+		assert_eq!(meta.context.model_middleware("Part"), Some(Middleware::RbxmModel));
+		assert_eq!(meta.context.model_middleware("Model"), Some(Middleware::JsonModel));
+		assert_eq!(meta.context.model_middleware("Folder"), None);
+
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("default.project.json");
+
+		vfs.write(
+			path,
+			br#"{ "name": "Test", "syncback": { "modelClasses": { "Part": "StringValue" } }, "tree": {} }"#,
+		)
+		.unwrap();
+
+		assert!(Project::load_vfs(path, &vfs).is_err());
+	}
+}
@@ -0,0 +1,89 @@
+mod value {
+	use argon::{
+		middleware::value::{read_value, write_value},
+		vfs::Vfs,
+	};
+	use rbx_dom_weak::{
+		types::{Color3, Variant},
+		ustr, HashMapExt, UstrMap,
+	};
+	use std::path::Path;
+
+	fn read(contents: &str, class: &str) -> Option<Variant> {
+		let vfs = Vfs::new_virtual();
+		let path = Path::new("value.txt");
+
+		vfs.write(path, contents.as_bytes()).unwrap();
+
+		read_value(path, class, &vfs).unwrap().properties.remove(&ustr("Value"))
+	}
+
+	fn write(value: Variant, class: &str, vfs: &Vfs) -> String {
+		let path = Path::new("value.txt");
+
+		let mut properties = UstrMap::new();
+		properties.insert(ustr("Value"), value);
+
+		assert!(write_value(properties, class, path, vfs).unwrap().is_empty());
+
+		vfs.read_to_string(path).unwrap()
+	}
+
+	#[test]
+	fn read_values() {
+		assert_eq!(read("42\n", "IntValue"), Some(Variant::Int64(42)));
+		assert_eq!(read("1.5", "NumberValue"), Some(Variant::Float64(1.5)));
+		assert_eq!(read("true", "BoolValue"), Some(Variant::Bool(true)));
+		assert_eq!(read("", "BoolValue"), None);
+
+		let orange = Some(Variant::Color3(Color3::new(1.0, 136.0 / 255.0, 0.0)));
+
+		assert_eq!(read("#ff8800", "Color3Value"), orange);
+		assert_eq!(read("#f80", "Color3Value"), orange);
+		assert_eq!(read("255, 136, 0", "Color3Value"), orange);
+		assert_eq!(
+			read("[0, 0.5, 1]", "Color3Value"),
+			Some(Variant::Color3(Color3::new(0.0, 0.5, 1.0)))
+		);
+
+		let vfs = Vfs::new_virtual();
+		vfs.write(Path::new("value.txt"), b"#ff88").unwrap();
+
+		assert!(read_value(Path::new("value.txt"), "Color3Value", &vfs).is_err());
+	}
+
+	#[test]
+	fn write_values() {
+		let vfs = Vfs::new_virtual();
+
+		assert_eq!(write(Variant::Int64(42), "IntValue", &vfs), "42\n");
+		assert_eq!(write(Variant::Bool(false), "BoolValue", &vfs), "false\n");
+		assert_eq!(
+			write(
+				Variant::Color3(Color3::new(1.0, 136.0 / 255.0, 0.0)),
+				"Color3Value",
+				&vfs
+			),
+			"#ff8800\n"
+		);
+		assert_eq!(
+			write(Variant::Color3(Color3::new(0.0, 0.5, 1.0)), "Color3Value", &vfs),
+			"[0, 0.5, 1]\n"
+		);
+	}
+
+	#[test]
+	fn keep_formatting() {
+		let vfs = Vfs::new_virtual();
+		vfs.write(Path::new("value.txt"), b"255, 136, 0").unwrap();
+
+		assert_eq!(
+			write(
+				Variant::Color3(Color3::new(1.0, 136.0 / 255.0, 0.0)),
+				"Color3Value",
+				&vfs
+			),
+			"255, 136, 0"
+		);
+	}
+}